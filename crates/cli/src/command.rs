@@ -0,0 +1,178 @@
+use const_lang::Parser;
+use std::time::Instant;
+
+/// A command of the REPL like `:quit`, which is not a statement.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Quit,
+    /// List the definitions of the names bound so far.
+    Env,
+    Type(String),
+    /// Show the tree of an expression as it is parsed.
+    Ast(String),
+    /// Run a script in the session, so what it defines stays.
+    Load(String),
+    Reset,
+    Time(String),
+    /// Write the definitions to a file, which `:restore` runs in a new session.
+    Save(String),
+    Restore(String),
+}
+impl Command {
+    /// Parse the line if it starts with `:`, where it is an error if the command is unknown.
+    pub fn new(line: &str) -> Option<Result<Self, String>> {
+        let line = line.trim().strip_prefix(':')?;
+        let (name, argument) = line
+            .split_once(char::is_whitespace)
+            .map_or((line, ""), |(name, argument)| (name, argument.trim()));
+        let required = |what: &str| {
+            if argument.is_empty() {
+                Err(format!("Expect {what} after `:{name}`"))
+            } else {
+                Ok(argument.to_string())
+            }
+        };
+        let no_argument = |command| {
+            if argument.is_empty() {
+                Ok(command)
+            } else {
+                Err(format!("Expect nothing after `:{name}`"))
+            }
+        };
+        Some(match name {
+            "quit" | "exit" => no_argument(Self::Quit),
+            "env" => no_argument(Self::Env),
+            "reset" => no_argument(Self::Reset),
+            "type" => required("an expression").map(Self::Type),
+            "ast" => required("an expression").map(Self::Ast),
+            "time" => required("a statement").map(Self::Time),
+            "load" => required("a file").map(Self::Load),
+            "save" => required("a file").map(Self::Save),
+            "restore" => required("a file").map(Self::Restore),
+            name => Err(format!("Unknown command `:{name}`")),
+        })
+    }
+    /// Run the command other than `:quit`, and get what to show.
+    pub fn run(self, parser: &mut Parser) -> Result<String, String> {
+        match self {
+            Self::Quit => Ok(String::new()),
+            Self::Env => Ok(parser.definitions().join("\n")),
+            Self::Type(expression) => parser.type_of(&expression),
+            Self::Ast(expression) => Parser::ast(&expression),
+            Self::Load(path) => load(parser, &path),
+            Self::Save(path) => save(parser, &path).map(|()| String::new()),
+            Self::Restore(path) => {
+                let program = read(&path)?;
+                parser.reset();
+                parser.run(&program)
+            }
+            Self::Reset => {
+                parser.reset();
+                Ok(String::new())
+            }
+            Self::Time(statement) => {
+                let start = Instant::now();
+                let output = parser.parse(&statement)?;
+                let elapsed = start.elapsed();
+                if output.is_empty() {
+                    Ok(format!("time: {elapsed:?}"))
+                } else {
+                    Ok(format!("{output}\ntime: {elapsed:?}"))
+                }
+            }
+        }
+    }
+}
+fn read(path: &str) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|err| format!("Cannot read `{path}`: {err}"))
+}
+/// Run a script in the session.
+pub fn load(parser: &mut Parser, path: &str) -> Result<String, String> {
+    parser.run(&read(path)?)
+}
+/// Write the definitions of the session to a file.
+pub fn save(parser: &Parser, path: &str) -> Result<(), String> {
+    std::fs::write(path, parser.save()? + "\n")
+        .map_err(|err| format!("Cannot write `{path}`: {err}"))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parse_command() {
+        assert_eq!(Command::new(" :quit\n"), Some(Ok(Command::Quit)));
+        assert_eq!(Command::new(":exit"), Some(Ok(Command::Quit)));
+        assert_eq!(
+            Command::new(":type  len \"a\" "),
+            Some(Ok(Command::Type("len \"a\"".to_string())))
+        );
+        assert_eq!(
+            Command::new(":load lib.cl"),
+            Some(Ok(Command::Load("lib.cl".to_string())))
+        );
+        assert_eq!(Command::new("1 + 1"), None);
+    }
+    #[test]
+    fn parse_invalid_command() {
+        assert_eq!(
+            Command::new(":q"),
+            Some(Err("Unknown command `:q`".to_string()))
+        );
+        assert_eq!(
+            Command::new(":ast"),
+            Some(Err("Expect an expression after `:ast`".to_string()))
+        );
+        assert_eq!(
+            Command::new(":env x"),
+            Some(Err("Expect nothing after `:env`".to_string()))
+        );
+    }
+    #[test]
+    fn run_command() {
+        let parser = &mut Parser::with_type_check();
+        parser.parse("let x = 1;").unwrap();
+        assert_eq!(Command::Env.run(parser), Ok("let x = 1;".to_string()));
+        assert_eq!(
+            Command::Type("[x]".to_string()).run(parser),
+            Ok("[Int]".to_string())
+        );
+        assert!(
+            Command::Time("x + 1".to_string())
+                .run(parser)
+                .is_ok_and(|output| output.starts_with("2: Int\ntime: "))
+        );
+        assert_eq!(Command::Reset.run(parser), Ok(String::new()));
+        assert_eq!(Command::Env.run(parser), Ok(String::new()));
+    }
+    #[test]
+    fn run_type_without_type_check() {
+        let parser = &mut Parser::default();
+        parser.parse("fn f x => x * 2").unwrap();
+        assert_eq!(
+            Command::Type("f 2 // twice".to_string()).run(parser),
+            Ok("Int".to_string())
+        );
+    }
+    #[test]
+    fn save_and_restore() {
+        let path = std::env::temp_dir().join(format!("session-{}.cl", std::process::id()));
+        let path = path.to_str().unwrap();
+        let parser = &mut Parser::default();
+        parser.parse("let mut x = [1];").unwrap();
+        parser.parse("fn f y => push x y").unwrap();
+        assert_eq!(
+            Command::Save(path.to_string()).run(parser),
+            Ok(String::new())
+        );
+        let restored = &mut Parser::default();
+        restored.parse("let z = 1;").unwrap();
+        assert_eq!(
+            Command::Restore(path.to_string()).run(restored),
+            Ok(String::new())
+        );
+        assert_eq!(restored.definitions(), parser.definitions());
+        std::fs::remove_file(path).unwrap();
+        assert!(Command::Restore(path.to_string()).run(restored).is_err());
+        assert_eq!(restored.definitions(), parser.definitions());
+    }
+}
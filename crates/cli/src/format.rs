@@ -0,0 +1,53 @@
+/// Write each file in the canonical layout, or with `check`, list the files which are not.
+///
+/// # Errors
+///
+/// This function will return an error if a file cannot be read, written or parsed,
+/// or with `check`, if a file is not formatted.
+pub fn run(files: &[String], check: bool) -> Result<(), String> {
+    let mut unformatted = 0;
+    for path in files {
+        let source =
+            std::fs::read_to_string(path).map_err(|err| format!("Cannot read `{path}`: {err}"))?;
+        let formatted =
+            const_lang::Parser::format(&source).map_err(|err| format!("{path}: {err}"))?;
+        if formatted == source {
+            continue;
+        }
+        if check {
+            println!("{path}");
+            unformatted += 1;
+        } else {
+            std::fs::write(path, formatted)
+                .map_err(|err| format!("Cannot write `{path}`: {err}"))?;
+        }
+    }
+    match unformatted {
+        0 => Ok(()),
+        1 => Err("1 file is not formatted".to_string()),
+        n => Err(format!("{n} files are not formatted")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn format_files() {
+        let path = std::env::temp_dir().join(format!("fmt-{}.cl", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        std::fs::write(&path, "let x=1; // one\nx+1").unwrap();
+        let files = [path.clone()];
+        assert_eq!(
+            run(&files, true),
+            Err("1 file is not formatted".to_string())
+        );
+        assert_eq!(run(&files, false), Ok(()));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "let x = 1; // one\nx + 1\n"
+        );
+        assert_eq!(run(&files, true), Ok(()));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,84 @@
+use crate::highlight;
+use rustyline::completion::Completer;
+use rustyline::highlight::{CmdKind, Highlighter};
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+use std::borrow::Cow;
+
+/// Complete the word before the cursor from the names the parser knows,
+/// and highlight the input unless `color` is off.
+#[derive(Default)]
+pub struct ReplHelper {
+    pub names: Vec<String>,
+    pub color: bool,
+}
+impl ReplHelper {
+    /// Where the word before `pos` starts, with the names which start with it.
+    fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let start = line[..pos]
+            .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '#'))
+            .map_or(0, |nth| {
+                nth + line[nth..].chars().next().map_or(0, char::len_utf8)
+            });
+        let word = &line[start..pos];
+        if word.is_empty() {
+            return (start, Vec::new());
+        }
+        let names = self
+            .names
+            .iter()
+            .filter(|name| name.starts_with(word))
+            .cloned()
+            .collect();
+        (start, names)
+    }
+}
+impl Completer for ReplHelper {
+    type Candidate = String;
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(line, pos))
+    }
+}
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+impl Highlighter for ReplHelper {
+    fn highlight<'l>(&self, line: &'l str, _: usize) -> Cow<'l, str> {
+        if self.color {
+            Cow::Owned(highlight::highlight(line))
+        } else {
+            Cow::Borrowed(line)
+        }
+    }
+    fn highlight_char(&self, _: &str, _: usize, _: CmdKind) -> bool {
+        self.color
+    }
+}
+impl Validator for ReplHelper {}
+impl Helper for ReplHelper {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn complete_word_before_cursor() {
+        let helper = ReplHelper {
+            names: vec!["len".to_string(), "let".to_string(), "total".to_string()],
+            color: false,
+        };
+        assert_eq!(
+            helper.candidates("1 + le", 6),
+            (4, vec!["len".to_string(), "let".to_string()])
+        );
+        assert_eq!(
+            helper.candidates("(tot) 1", 4),
+            (1, vec!["total".to_string()])
+        );
+        assert_eq!(helper.candidates("1 + ", 4), (4, Vec::new()));
+    }
+}
@@ -0,0 +1,86 @@
+use const_lang::Parser;
+use std::io::IsTerminal;
+
+const RESET: &str = "\x1b[0m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const MAGENTA: &str = "\x1b[35m";
+const CYAN: &str = "\x1b[36m";
+
+/// Whether to write colors to the stream, which is not done if `NO_COLOR` is set.
+pub fn use_color(stream: &impl IsTerminal) -> bool {
+    std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()) && stream.is_terminal()
+}
+
+/// Color the tokens of the source, like keywords, numbers and strings.
+pub fn highlight(source: &str) -> String {
+    let mut highlighted = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(c) = rest.chars().next() {
+        let (len, color) = if c == '"' || rest.starts_with("f\"") {
+            (string_len(rest), Some(GREEN))
+        } else if c.is_ascii_digit() {
+            (word_len(rest), Some(YELLOW))
+        } else if c.is_alphabetic() || c == '_' {
+            let len = word_len(rest);
+            let color = if Parser::is_keyword(&rest[..len]) {
+                Some(MAGENTA)
+            } else if Parser::is_builtin(&rest[..len]) {
+                Some(CYAN)
+            } else {
+                None
+            };
+            (len, color)
+        } else {
+            (c.len_utf8(), None)
+        };
+        match color {
+            Some(color) => highlighted.push_str(&format!("{color}{}{RESET}", &rest[..len])),
+            None => highlighted.push_str(&rest[..len]),
+        }
+        rest = &rest[len..];
+    }
+    highlighted
+}
+
+/// Color `Error:` in front of the message.
+pub fn error(message: &str) -> String {
+    format!("{RED}Error:{RESET} {message}")
+}
+
+fn word_len(s: &str) -> usize {
+    s.find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(s.len())
+}
+
+/// The length of the string literal which `s` starts with, or the rest if it is not closed.
+fn string_len(s: &str) -> usize {
+    let start = s.find('"').map_or(0, |nth| nth + 1);
+    let mut escaped = false;
+    for (nth, c) in s[start..].char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return start + nth + 1,
+            _ => (),
+        }
+    }
+    s.len()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn highlight_tokens() {
+        assert_eq!(
+            highlight("let n = len \"a\\\"b\";"),
+            format!("{MAGENTA}let{RESET} n = {CYAN}len{RESET} {GREEN}\"a\\\"b\"{RESET};")
+        );
+        assert_eq!(
+            highlight("f\"{x}\" + 0x1F"),
+            format!("{GREEN}f\"{{x}}\"{RESET} + {YELLOW}0x1F{RESET}")
+        );
+        assert_eq!(highlight("lets \"ab"), format!("lets {GREEN}\"ab{RESET}"));
+    }
+}
@@ -0,0 +1,51 @@
+use const_lang::{Level, Linter, Rule};
+
+/// Print the lints in each file, with the level of each rule set in `levels`.
+///
+/// # Errors
+///
+/// This function will return an error if a file cannot be read or parsed,
+/// or if a lint of a denied rule is found.
+pub fn run(files: &[String], levels: &[(Rule, Level)]) -> Result<(), String> {
+    let mut denied = 0;
+    for path in files {
+        let source =
+            std::fs::read_to_string(path).map_err(|err| format!("Cannot read `{path}`: {err}"))?;
+        let mut linter = Linter::default();
+        for &(rule, level) in levels {
+            linter.set_level(rule, level);
+        }
+        let lints = linter
+            .lint(&source)
+            .map_err(|err| format!("{path}: {err}"))?;
+        for lint in lints {
+            println!("{path}: {lint}");
+            if lint.level == Level::Deny {
+                denied += 1;
+            }
+        }
+    }
+    match denied {
+        0 => Ok(()),
+        1 => Err("1 lint is denied".to_string()),
+        n => Err(format!("{n} lints are denied")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn lint_files() {
+        let path = std::env::temp_dir().join(format!("lint-{}.cl", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        std::fs::write(&path, "let x = 1;\nlet x = 2;").unwrap();
+        let files = [path.clone()];
+        assert_eq!(run(&files, &[]), Ok(()));
+        assert_eq!(
+            run(&files, &[(Rule::Shadowing, Level::Deny)]),
+            Err("1 lint is denied".to_string())
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+}
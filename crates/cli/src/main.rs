@@ -1,21 +1,285 @@
-use std::io::Write;
+mod command;
+mod format;
+mod helper;
+mod highlight;
+mod lint;
+mod repl;
 
-fn main() -> std::io::Result<()> {
-    let mut input = String::new();
-    let mut parser = const_lang::Parser::default();
-    let mut stdout = std::io::stdout();
-    let mut stderr = std::io::stderr();
+use const_lang::{Level, Rule};
+use std::io::Read;
+use std::process::ExitCode;
 
-    let stdin = std::io::stdin();
-    loop {
-        print!("> ");
-        stdout.flush()?;
-        stdin.read_line(&mut input)?;
-        match parser.parse(&input) {
-            Ok(s) if !s.is_empty() => writeln!(stdout, "{s}")?,
-            Ok(_) => (),
-            Err(e) => writeln!(stderr, "Error: {e}")?,
-        }
-        input.clear();
+const USAGE: &str = "Usage: cli [--check] [--session FILE | FILE | -e PROGRAM | -]\n       cli fmt [--check] FILE...\n       cli lint [--allow RULE | --warn RULE | --deny RULE]... FILE...";
+
+/// What to run, which is the REPL unless a program is given.
+#[derive(Debug, PartialEq, Eq)]
+enum Mode {
+    Repl,
+    Script(String),
+    Eval(String),
+    Stdin,
+    /// `fmt` rewrites the files in the canonical layout, or only checks them with `--check`.
+    Format(Vec<String>),
+    /// `lint` reports the lints in the files, with the levels of the rules set by the options.
+    Lint {
+        files: Vec<String>,
+        levels: Vec<(Rule, Level)>,
+    },
+}
+#[derive(Debug, PartialEq, Eq)]
+struct Args {
+    mode: Mode,
+    /// `--check` infers the types of statements before running them,
+    /// or with `fmt`, only checks that the files are formatted.
+    check: bool,
+    /// The file which the REPL restores the definitions from, and saves them to on exit.
+    session: Option<String>,
+}
+impl Args {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut args = args.into_iter().peekable();
+        if args.next_if(|arg| arg == "fmt").is_some() {
+            return Self::parse_format(args);
+        }
+        if args.next_if(|arg| arg == "lint").is_some() {
+            return Self::parse_lint(args);
+        }
+        let mut mode = Mode::Repl;
+        let mut check = false;
+        let mut session = None;
+        while let Some(arg) = args.next() {
+            let next = match arg.as_str() {
+                "--check" => {
+                    check = true;
+                    continue;
+                }
+                "--session" => {
+                    session = Some(args.next().ok_or("Expect a file after `--session`")?);
+                    continue;
+                }
+                "-e" => Mode::Eval(args.next().ok_or("Expect a program after `-e`")?),
+                "-" => Mode::Stdin,
+                arg if arg.starts_with('-') => return Err(format!("Unknown option `{arg}`")),
+                path => Mode::Script(path.to_string()),
+            };
+            if mode != Mode::Repl {
+                return Err("Expect only one program to run".to_string());
+            }
+            mode = next;
+        }
+        if session.is_some() && mode != Mode::Repl {
+            return Err("Expect `--session` only for the REPL".to_string());
+        }
+        Ok(Self {
+            mode,
+            check,
+            session,
+        })
+    }
+    fn parse_format(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut files = Vec::new();
+        let mut check = false;
+        for arg in args {
+            match arg.as_str() {
+                "--check" => check = true,
+                arg if arg.starts_with('-') => return Err(format!("Unknown option `{arg}`")),
+                path => files.push(path.to_string()),
+            }
+        }
+        if files.is_empty() {
+            return Err("Expect a file after `fmt`".to_string());
+        }
+        Ok(Self {
+            mode: Mode::Format(files),
+            check,
+            session: None,
+        })
+    }
+    fn parse_lint(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut files = Vec::new();
+        let mut levels = Vec::new();
+        while let Some(arg) = args.next() {
+            let level = match arg.as_str() {
+                "--allow" => Level::Allow,
+                "--warn" => Level::Warn,
+                "--deny" => Level::Deny,
+                arg if arg.starts_with('-') => return Err(format!("Unknown option `{arg}`")),
+                path => {
+                    files.push(path.to_string());
+                    continue;
+                }
+            };
+            let name = args
+                .next()
+                .ok_or_else(|| format!("Expect a rule after `{arg}`"))?;
+            let rule = Rule::new(&name).ok_or_else(|| {
+                let rules = Rule::ALL.map(Rule::name).join(", ");
+                format!("Unknown rule `{name}`, which is one of {rules}")
+            })?;
+            levels.push((rule, level));
+        }
+        if files.is_empty() {
+            return Err("Expect a file after `lint`".to_string());
+        }
+        Ok(Self {
+            mode: Mode::Lint { files, levels },
+            check: false,
+            session: None,
+        })
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("Error: {err}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    let mut parser = if args.check {
+        const_lang::Parser::with_type_check()
+    } else {
+        const_lang::Parser::default()
+    };
+    let program = match args.mode {
+        Mode::Format(files) => return exit_code(format::run(&files, args.check)),
+        Mode::Lint { files, levels } => return exit_code(lint::run(&files, &levels)),
+        Mode::Repl => return exit_code(repl::run(&mut parser, args.session.as_deref())),
+        Mode::Script(path) => {
+            std::fs::read_to_string(&path).map_err(|err| format!("Cannot read `{path}`: {err}"))
+        }
+        Mode::Eval(program) => Ok(program),
+        Mode::Stdin => {
+            let mut program = String::new();
+            std::io::stdin()
+                .read_to_string(&mut program)
+                .map(|_| program)
+                .map_err(|err| format!("Cannot read stdin: {err}"))
+        }
+    };
+    let output = program.and_then(|program| parser.run(&program));
+    exit_code(output.map(|output| {
+        if !output.is_empty() {
+            println!("{output}");
+        }
+    }))
+}
+
+fn exit_code(result: Result<(), String>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) if highlight::use_color(&std::io::stderr()) => {
+            eprintln!("{}", highlight::error(&err));
+            ExitCode::FAILURE
+        }
+        Err(err) => {
+            eprintln!("Error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(ToString::to_string))
+    }
+    #[test]
+    fn parse_args() {
+        assert_eq!(
+            parse(&[]),
+            Ok(Args {
+                mode: Mode::Repl,
+                check: false,
+                session: None
+            })
+        );
+        assert_eq!(
+            parse(&["--check", "main.cl"]),
+            Ok(Args {
+                mode: Mode::Script("main.cl".to_string()),
+                check: true,
+                session: None
+            })
+        );
+        assert_eq!(
+            parse(&["-e", "1 + 2"]),
+            Ok(Args {
+                mode: Mode::Eval("1 + 2".to_string()),
+                check: false,
+                session: None
+            })
+        );
+        assert_eq!(
+            parse(&["-", "--check"]),
+            Ok(Args {
+                mode: Mode::Stdin,
+                check: true,
+                session: None
+            })
+        );
+        assert_eq!(
+            parse(&["fmt", "--check", "a.cl", "b.cl"]),
+            Ok(Args {
+                mode: Mode::Format(vec!["a.cl".to_string(), "b.cl".to_string()]),
+                check: true,
+                session: None
+            })
+        );
+        assert_eq!(
+            parse(&[
+                "lint",
+                "--deny",
+                "shadowing",
+                "a.cl",
+                "--allow",
+                "unused-binding"
+            ]),
+            Ok(Args {
+                mode: Mode::Lint {
+                    files: vec!["a.cl".to_string()],
+                    levels: vec![
+                        (Rule::Shadowing, Level::Deny),
+                        (Rule::UnusedBinding, Level::Allow)
+                    ]
+                },
+                check: false,
+                session: None
+            })
+        );
+        assert_eq!(
+            parse(&["--session", "work.cl"]),
+            Ok(Args {
+                mode: Mode::Repl,
+                check: false,
+                session: Some("work.cl".to_string())
+            })
+        );
+    }
+    #[test]
+    fn parse_invalid_args() {
+        assert_eq!(
+            parse(&["-e"]),
+            Err("Expect a program after `-e`".to_string())
+        );
+        assert_eq!(parse(&["-x"]), Err("Unknown option `-x`".to_string()));
+        assert_eq!(
+            parse(&["fmt"]),
+            Err("Expect a file after `fmt`".to_string())
+        );
+        assert_eq!(
+            parse(&["fmt", "-e", "a.cl"]),
+            Err("Unknown option `-e`".to_string())
+        );
+        assert_eq!(
+            parse(&["a.cl", "b.cl"]),
+            Err("Expect only one program to run".to_string())
+        );
+        assert_eq!(
+            parse(&["--session", "work.cl", "main.cl"]),
+            Err("Expect `--session` only for the REPL".to_string())
+        );
     }
 }
@@ -0,0 +1,123 @@
+use crate::command::{self, Command};
+use crate::helper::ReplHelper;
+use crate::highlight;
+use const_lang::Parser;
+use rustyline::Editor;
+use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+use std::path::{Path, PathBuf};
+
+/// Run each statement typed, until EOF or `:quit`.
+///
+/// A statement goes on with the `..` prompt while it needs more input,
+/// and Ctrl-C cancels the statement being typed rather than the session.
+///
+/// The definitions in `session` are restored first if it exists, and saved to it at the end,
+/// even when the editor fails.
+pub fn run(parser: &mut Parser, session: Option<&str>) -> Result<(), String> {
+    if let Some(path) = session
+        && Path::new(path).exists()
+    {
+        command::load(parser, path)?;
+    }
+    let edited = edit(parser).map_err(|err| err.to_string());
+    let saved = match session {
+        Some(path) => command::save(parser, path),
+        None => Ok(()),
+    };
+    match (edited, saved) {
+        (Err(edit), Err(save)) => Err(format!("{edit}\n{save}")),
+        (edited, saved) => edited.and(saved),
+    }
+}
+
+fn edit(parser: &mut Parser) -> rustyline::Result<()> {
+    let mut editor = Editor::<ReplHelper, DefaultHistory>::new()?;
+    editor.set_helper(Some(ReplHelper {
+        names: parser.names(),
+        color: highlight::use_color(&std::io::stdout()),
+    }));
+    let history = history_path();
+    if let Some(path) = &history {
+        // There is no history before the first session.
+        let _ = editor.load_history(path);
+    }
+    let mut input = String::new();
+    loop {
+        let prompt = if input.is_empty() { "> " } else { ".. " };
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => {
+                input.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(err),
+        };
+        if input.is_empty() {
+            match Command::new(&line) {
+                Some(Ok(Command::Quit)) => break,
+                Some(command) => {
+                    editor.add_history_entry(&line)?;
+                    print_output(command.and_then(|command| command.run(parser)));
+                    update_names(&mut editor, parser);
+                    continue;
+                }
+                None => (),
+            }
+        } else {
+            input.push('\n');
+        }
+        input.push_str(&line);
+        if Parser::needs_more_input(&input) {
+            continue;
+        }
+        let statement = std::mem::take(&mut input);
+        editor.add_history_entry(&statement)?;
+        match parser.evaluate(&statement) {
+            Ok(Some(evaluation)) if highlight::use_color(&std::io::stdout()) => {
+                let value = highlight::highlight(&evaluation.value);
+                println!("{}: {} = {value}", Parser::LAST_VALUE, evaluation.ty);
+            }
+            Ok(Some(evaluation)) => println!("{evaluation}"),
+            Ok(None) => (),
+            Err(err) => print_error(&err),
+        }
+        update_names(&mut editor, parser);
+    }
+    if let Some(path) = &history {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        editor.save_history(path)?;
+    }
+    Ok(())
+}
+
+fn print_output(output: Result<String, String>) {
+    match output {
+        Ok(s) if !s.is_empty() => println!("{s}"),
+        Ok(_) => (),
+        Err(err) => print_error(&err),
+    }
+}
+
+fn print_error(err: &str) {
+    if highlight::use_color(&std::io::stderr()) {
+        eprintln!("{}", highlight::error(err));
+    } else {
+        eprintln!("Error: {err}");
+    }
+}
+
+/// Complete the names bound by the last input.
+fn update_names(editor: &mut Editor<ReplHelper, DefaultHistory>, parser: &Parser) {
+    if let Some(helper) = editor.helper_mut() {
+        helper.names = parser.names();
+    }
+}
+
+/// The file which keeps the lines typed across sessions, under the user's data directory.
+fn history_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("const-lang").join("history"))
+}
@@ -0,0 +1,179 @@
+use crate::internal::prelude::*;
+
+/// An assignment like `x = x + 1` or `x += 1`, which changes a binding declared with `let mut`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Assignment {
+    name: Identifier,
+    /// The operator of a compound assignment like `+=`.
+    op: Option<Operator>,
+    expr: Expression,
+}
+impl Assignment {
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        let nth = Self::find_equals_sign(s).ok_or(AssignmentError::NotFound)?;
+        let (target, expr) = (&s[..nth], &s[nth + '='.len_utf8()..]);
+        let op = Operator::from_suffix(target).filter(|op| op.is_arithmetic());
+        let target = match op {
+            Some(op) => &target[..target.len() - op.symbol().len()],
+            None => target,
+        };
+        let expr = Expression::new(&expr.into())?;
+        if expr.is_empty() {
+            return Err(AssignmentError::MissingValue)?;
+        }
+        Ok(Self {
+            name: Identifier::new(&target.into())?,
+            op,
+            expr,
+        })
+    }
+    /// Find the top-level `=`, which is not a part of `==`, `!=` or `=>`.
+    fn find_equals_sign(s: &str) -> Option<usize> {
+        TopLevelChars::new(s)
+            .filter(|&(_, c)| c == '=')
+            .map(|(nth, _)| nth)
+            .find(|&nth| !s[..nth].ends_with(['=', '!']) && !s[nth + 1..].starts_with(['=', '>']))
+    }
+    /// The value to assign, where `x += 1` assigns `x + 1`.
+    fn value(&self) -> Expression {
+        match self.op {
+            Some(op) => Expression::Operation(Operation::from_operands(
+                Expression::Binding(self.name.clone()),
+                op,
+                self.expr.clone(),
+            )),
+            None => self.expr.clone(),
+        }
+    }
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        resolver.lookup(&self.name)?;
+        self.expr.resolve(resolver)
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        linter.lookup(&self.name);
+        self.expr.lint(linter);
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<(), Error> {
+        let expected = checker.lookup(&self.name)?;
+        let found = self.value().infer(checker)?;
+        Ok(checker.unify(&expected, &found)?)
+    }
+    pub fn eval(&self, env: &Environment) -> Result<(), Error> {
+        let value = self.value().eval(env)?;
+        Ok(env.assign(&self.name, value)?)
+    }
+}
+impl std::fmt::Display for Assignment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ", self.name)?;
+        if let Some(op) = self.op {
+            write!(f, "{op}")?;
+        }
+        write!(f, "= ")?;
+        self.expr.fmt(f)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn store(s: &str, env: &mut Environment) {
+        BindingDef::new(&s.into()).unwrap().store(env).unwrap();
+    }
+    fn eval(s: &str, env: &Environment) -> Result<Value, Error> {
+        Assignment::new(&s.into())?.eval(env)?;
+        Expression::new(&"x".into())?.eval(env)
+    }
+    #[test]
+    fn parse_assignment() {
+        assert_eq!(
+            Assignment::new(&"x = x + 1".into()),
+            Ok(Assignment {
+                name: "x".try_into().unwrap(),
+                op: None,
+                expr: Expression::new(&"x + 1".into()).unwrap()
+            })
+        );
+        assert_eq!(
+            Assignment::new(&"x <<= 2".into()),
+            Ok(Assignment {
+                name: "x".try_into().unwrap(),
+                op: Some(Operator::Shl),
+                expr: Expression::Number(Number::from_i32(2))
+            })
+        );
+        assert_eq!(
+            Assignment::new(&"y = { let x = 1; x }".into()).map(|assignment| assignment.op),
+            Ok(None)
+        );
+    }
+    #[test]
+    fn parse_invalid_assignment() {
+        assert_eq!(
+            Assignment::new(&"x == 1".into()),
+            Err(Error::Assignment(AssignmentError::NotFound))
+        );
+        assert_eq!(
+            Assignment::new(&"x =".into()),
+            Err(Error::Assignment(AssignmentError::MissingValue))
+        );
+        assert_eq!(
+            Assignment::new(&"x.a = 1".into()),
+            Err(Error::Identifier(IdentifierError::ContainSpecialCharacters))
+        );
+    }
+    #[test]
+    fn eval_assignment() {
+        let env = &mut Environment::default();
+        store("let mut x = 1", env);
+        assert_eq!(
+            eval("x = x * 10", env),
+            Ok(Value::Number(Number::from_i32(10)))
+        );
+        assert_eq!(eval("x += 2", env), Ok(Value::Number(Number::from_i32(12))));
+        assert_eq!(eval("x = \"a\"", env), Ok(Value::Str("a".to_string())));
+    }
+    #[test]
+    fn eval_in_child_scope() {
+        let parent = &mut Environment::default();
+        store("let mut x = 1", parent);
+        let child = &mut parent.create_child();
+        assert_eq!(
+            eval("x -= 3", child),
+            Ok(Value::Number(Number::from_i32(-2)))
+        );
+        store("let mut x = 10", child);
+        assert_eq!(
+            eval("x += 1", child),
+            Ok(Value::Number(Number::from_i32(11)))
+        );
+        assert_eq!(
+            Expression::new(&"x".into()).unwrap().eval(parent),
+            Ok(Value::Number(Number::from_i32(-2)))
+        );
+    }
+    #[test]
+    fn eval_invalid_assignment() {
+        let env = &mut Environment::default();
+        assert_eq!(
+            eval("x = 1", env),
+            Err(Error::Assignment(AssignmentError::Undefined(
+                "x".to_string()
+            )))
+        );
+        store("let x = 1", env);
+        assert_eq!(
+            eval("x = 2", env),
+            Err(Error::Assignment(AssignmentError::Immutable(
+                "x".to_string()
+            )))
+        );
+        store("let mut x = 1", env);
+        store("let x = x", env);
+        assert_eq!(
+            eval("x += 1", env),
+            Err(Error::Assignment(AssignmentError::Immutable(
+                "x".to_string()
+            )))
+        );
+    }
+}
@@ -2,29 +2,88 @@ use crate::internal::prelude::*;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct BindingDef {
-    name: Identifier,
+    mutable: bool,
+    pattern: Pattern,
     expr: Expression,
 }
 
 impl BindingDef {
     pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
-        let s = s
-            .strip_prefix("let ")
+        let s = Keyword::Let
+            .strip(s)
             .ok_or(BindingDefError::MissingLetKeyword)?;
-        let (identifier, expr) = s
+        let (mutable, s) = match Keyword::Mut.strip(s.trim_start()) {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        let (pattern, expr) = s
             .split_once('=')
             .ok_or(BindingDefError::MissingEqualsSign)?;
 
+        let pattern = Pattern::new(&pattern.into())?;
+        if pattern.contains_literal() {
+            return Err(BindingDefError::LiteralPattern)?;
+        }
         Ok(Self {
-            name: identifier.try_into()?,
+            mutable,
+            pattern,
             expr: Expression::new(&expr.into())?,
         })
     }
-    pub fn store(&self, env: &mut Environment) {
-        env.insert_binding(self.name.clone(), self.expr.clone());
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        self.expr.resolve(resolver)?;
+        self.pattern.resolve(resolver)
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        self.expr.lint(linter);
+        self.pattern.lint(linter, Rule::UnusedBinding);
+    }
+    /// An immutable binding like `let x = 1`.
+    pub const fn from_parts(pattern: Pattern, expr: Expression) -> Self {
+        Self {
+            mutable: false,
+            pattern,
+            expr,
+        }
+    }
+    /// The names which the pattern binds.
+    pub fn identifiers(&self) -> Vec<&Identifier> {
+        self.pattern.identifiers()
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<(), Error> {
+        let ty = self.expr.infer(checker)?;
+        let bindings = self.pattern.infer(checker, &ty)?;
+        for (name, ty) in bindings {
+            // a mutable binding may be assigned later, so its type is not generalized
+            if self.mutable {
+                checker.bind(name, ty);
+            } else {
+                checker.bind_generalized(name, &ty);
+            }
+        }
+        Ok(())
+    }
+    /// Evaluate the expression in `env` and bind the value to the pattern.
+    pub fn store(&self, env: &mut Environment) -> Result<(), Error> {
+        let value = self.expr.eval(env)?;
+        if self.mutable {
+            self.pattern.bind_mutable(value, env)
+        } else {
+            self.pattern.bind(value, env)
+        }
     }
 }
 
+impl std::fmt::Display for BindingDef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ", Keyword::Let)?;
+        if self.mutable {
+            write!(f, "{} ", Keyword::Mut)?;
+        }
+        write!(f, "{} = ", self.pattern)?;
+        self.expr.fmt(f)
+    }
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -33,7 +92,8 @@ mod tests {
         assert_eq!(
             BindingDef::new(&"let foo = 1 + 1".into()),
             Ok(BindingDef {
-                name: "foo".try_into().unwrap(),
+                mutable: false,
+                pattern: "foo".try_into().unwrap(),
                 expr: Expression::Operation(Operation::new(&"1 + 1".into()).unwrap())
             })
         );
@@ -43,10 +103,28 @@ mod tests {
         assert_eq!(
             BindingDef::new(&"let foo = 3".into()),
             Ok(BindingDef {
-                name: "foo".try_into().unwrap(),
+                mutable: false,
+                pattern: "foo".try_into().unwrap(),
+                expr: Expression::Number(Number::from_i32(3))
+            })
+        );
+    }
+    #[test]
+    fn parse_mutable_binding_def() {
+        assert_eq!(
+            BindingDef::new(&"let mut foo = 3".into()),
+            Ok(BindingDef {
+                mutable: true,
+                pattern: "foo".try_into().unwrap(),
                 expr: Expression::Number(Number::from_i32(3))
             })
         );
+        assert_eq!(
+            BindingDef::new(&"let mut mut = 3".into()),
+            Err(Error::Identifier(IdentifierError::ReservedKeyword(
+                "mut".to_string()
+            )))
+        );
     }
     #[test]
     fn parse_without_equal() {
@@ -56,6 +134,24 @@ mod tests {
         );
     }
     #[test]
+    fn parse_with_keyword() {
+        assert_eq!(
+            BindingDef::new(&"let let = 1".into()),
+            Err(Error::Identifier(IdentifierError::ReservedKeyword(
+                "let".to_string()
+            )))
+        );
+        let env = &mut Environment::default();
+        BindingDef::new(&"let r#let = 1".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        assert_eq!(
+            Expression::new(&"r#let + 1".into()).unwrap().eval(env),
+            Ok(Value::Number(Number::from_i32(2)))
+        );
+    }
+    #[test]
     fn parse_without_let() {
         assert_eq!(
             BindingDef::new(&"good morning".into()),
@@ -69,5 +165,91 @@ mod tests {
             Err(Error::BindingDef(BindingDefError::MissingLetKeyword))
         );
         assert!(BindingDef::new(&"let a=a=1".into()).is_err());
+        assert_eq!(
+            BindingDef::new(&"let (a, 1) = (1, 1)".into()),
+            Err(Error::BindingDef(BindingDefError::LiteralPattern))
+        );
+    }
+    #[test]
+    fn store_evaluated_value() {
+        let env = &mut Environment::default();
+        BindingDef::new(&"let x = 1".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        BindingDef::new(&"let x = x + 1".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        assert_eq!(
+            env.get_from_self_and_parent(&"x".try_into().unwrap()),
+            Some(NamedValue::Binding(Value::Number(Number::from_i32(2))))
+        );
+    }
+    #[test]
+    fn store_destructured_tuple() {
+        let env = &mut Environment::default();
+        BindingDef::new(&"let (q, (r, _)) = (1, (2, 3))".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        assert_eq!(
+            env.get_from_self_and_parent(&"q".try_into().unwrap()),
+            Some(NamedValue::Binding(Value::Number(Number::from_i32(1))))
+        );
+        assert_eq!(
+            env.get_from_self_and_parent(&"r".try_into().unwrap()),
+            Some(NamedValue::Binding(Value::Number(Number::from_i32(2))))
+        );
+        assert_eq!(
+            BindingDef::new(&"let (a, b) = (1, 2, 3)".into())
+                .unwrap()
+                .store(env),
+            Err(Error::Pattern(PatternError::TupleLengthMismatch {
+                expected: 2,
+                found: 3
+            }))
+        );
+    }
+    #[test]
+    fn store_annotated() {
+        let env = &mut Environment::default();
+        BindingDef::new(&"let x: Int = 3".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        assert_eq!(
+            BindingDef::new(&"let y: [Str] = [x]".into())
+                .unwrap()
+                .store(env),
+            Err(Error::Annotation(AnnotationError::TypeMismatch {
+                name: "y".to_string(),
+                expected: "[Str]".to_string(),
+                found: "List".to_string()
+            }))
+        );
+    }
+    #[test]
+    fn infer_annotated() {
+        let checker = &mut TypeChecker::default();
+        assert_eq!(
+            BindingDef::new(&"let xs: [Str] = [1]".into())
+                .unwrap()
+                .infer(checker),
+            Err(Error::Annotation(AnnotationError::TypeMismatch {
+                name: "xs".to_string(),
+                expected: "[Str]".to_string(),
+                found: "[Int]".to_string()
+            }))
+        );
+    }
+    #[test]
+    fn store_with_error() {
+        let env = &mut Environment::default();
+        assert_eq!(
+            BindingDef::new(&"let x = y".into()).unwrap().store(env),
+            Err(Error::Binding(BindingError::NotFound))
+        );
+        assert_eq!(env.get_from_self_and_parent(&"x".try_into().unwrap()), None);
     }
 }
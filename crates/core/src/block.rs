@@ -1,4 +1,5 @@
 use crate::internal::prelude::*;
+use std::fmt::Write;
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Block {
     statements: Vec<Statement>,
@@ -11,18 +12,91 @@ impl Block {
         let Some(s) = s.strip_suffix('}') else {
             return Err(BlockError::MissingClosingBrace)?;
         };
-        let statements = TrimmedStr::new(s)
-            .split_inclusive(';')
-            .map(|s| Statement::new(&s.into()))
+        let statements = Self::split_statements(s)
+            .into_iter()
+            .map(|(_, s)| Statement::new(&s.into()))
             .collect::<Result<_, _>>()?;
         Ok(Self { statements })
     }
-    pub fn get_expression_from(&self, local: &mut Environment) -> Expression {
+    /// Split a sequence of statements, with where each of them starts in `s`.
+    ///
+    /// A statement ends with a top-level `;`, except the last one, which gives the value.
+    pub fn split_statements(s: &str) -> Vec<(usize, &str)> {
+        let mut statements = Vec::new();
+        let mut start = 0;
+        for (nth, c) in TopLevelChars::new(s) {
+            if c == ';' {
+                statements.push((start, &s[start..=nth]));
+                start = nth + ';'.len_utf8();
+            }
+        }
+        if !s[start..].trim().is_empty() {
+            statements.push((start, &s[start..]));
+        }
+        statements
+    }
+    /// Split `s` before the block it ends with, like the body of `while x { ... }`.
+    pub fn split_trailing(s: &str) -> Option<(&str, Self)> {
+        let (nth, _) = TopLevelChars::new(s).filter(|&(_, c)| c == '{').last()?;
+        let block = Self::new(&s[nth..].into()).ok()?;
+        Some((&s[..nth], block))
+    }
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        for statement in &self.statements {
+            statement.resolve(resolver)?;
+        }
+        Ok(())
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        linter.lint_statements(&self.statements);
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<Type, Error> {
+        let mut last = Type::Empty;
+        for statement in &self.statements {
+            last = statement.infer(checker)?;
+        }
+        Ok(last)
+    }
+    pub fn get_expression_from(&self, local: &mut Environment) -> Result<Expression, Error> {
         let mut last = Expression::Empty;
         for statement in &self.statements {
-            last = statement.get_expression_in(local);
+            last = statement.get_expression_in(local)?;
         }
-        last
+        Ok(last)
+    }
+    /// Evaluate the statements in a child of `env`, and get the value of the last one.
+    pub fn eval(&self, env: &Environment) -> Result<Value, Error> {
+        let local = &mut env.create_child();
+        self.get_expression_from(local)?.eval(local)
+    }
+}
+impl std::fmt::Display for Block {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.statements.is_empty() {
+            return write!(f, "{{}}");
+        }
+        // the alternate form puts each statement on its own line, unless there is
+        // only one and it fits on a line
+        let alternate = f.alternate();
+        let multiline = alternate
+            && (self.statements.len() > 1 || format!("{:#}", self.statements[0]).contains('\n'));
+        let separator = if multiline { "\n" } else { " " };
+        write!(f, "{{")?;
+        let mut indented = Indented::new(f);
+        for (i, statement) in self.statements.iter().enumerate() {
+            if alternate {
+                write!(indented, "{separator}{statement:#}")?;
+            } else {
+                write!(indented, "{separator}{statement}")?;
+            }
+            // a definition is followed by `;` unless it is the last statement
+            let is_definition =
+                matches!(statement, Statement::FunctionDef(_) | Statement::EnumDef(_));
+            if is_definition && i + 1 < self.statements.len() {
+                write!(indented, ";")?;
+            }
+        }
+        write!(f, "{separator}}}")
     }
 }
 #[cfg(test)]
@@ -132,7 +206,7 @@ mod tests {
     fn get_expression_from_empty() {
         assert_eq!(
             Block { statements: vec![] }.get_expression_from(&mut Environment::default()),
-            Expression::Empty
+            Ok(Expression::Empty)
         );
     }
     #[test]
@@ -142,19 +216,15 @@ mod tests {
             Block::new(&"{let a = 11451; let b = 11452; b}".into())
                 .unwrap()
                 .get_expression_from(local),
-            Expression::Binding(Identifier::new(&"b".into()).unwrap())
+            Ok(Expression::Binding(Identifier::new(&"b".into()).unwrap()))
         );
         assert_eq!(
             local.get_from_self_and_parent(&"a".try_into().unwrap()),
-            Some(NamedValue::Binding(Expression::Number(Number::from_i32(
-                11451
-            ))))
+            Some(NamedValue::Binding(Value::Number(Number::from_i32(11451))))
         );
         assert_eq!(
             local.get_from_self_and_parent(&"b".try_into().unwrap()),
-            Some(NamedValue::Binding(Expression::Number(Number::from_i32(
-                11452
-            ))))
+            Some(NamedValue::Binding(Value::Number(Number::from_i32(11452))))
         );
     }
     #[test]
@@ -163,24 +233,59 @@ mod tests {
             Block::new(&"{114; 514; 1919;}".into())
                 .unwrap()
                 .get_expression_from(&mut Environment::default()),
-            Expression::Empty
+            Ok(Expression::Empty)
         );
         assert_eq!(
             Block::new(&"{114; 514; 1919; 810}".into())
                 .unwrap()
                 .get_expression_from(&mut Environment::default()),
-            Expression::Number(Number::from_i32(810))
+            Ok(Expression::Number(Number::from_i32(810)))
         );
     }
     #[test]
-    #[should_panic]
     fn get_block_expression_from_block() {
-        // TODO: fix it
         assert_eq!(
             Block::new(&"{let a = 11451;{let b = 11452; b}}".into())
                 .unwrap()
                 .get_expression_from(&mut Environment::default()),
-            Expression::Block(Block::new(&"{let b = 11452; b}".into()).unwrap())
+            Ok(Expression::Block(
+                Block::new(&"{let b = 11452; b}".into()).unwrap()
+            ))
+        );
+    }
+    #[test]
+    fn parse_nested_blocks() {
+        assert_eq!(
+            Block::new(&"{ let a = { let b = 1; b }; a }".into()),
+            Ok(Block {
+                statements: vec![
+                    Statement::BindingDef(
+                        BindingDef::new(&"let a = { let b = 1; b }".into()).unwrap()
+                    ),
+                    Statement::Expression(Expression::Binding(
+                        Identifier::new(&"a".into()).unwrap()
+                    ))
+                ]
+            })
+        );
+        assert_eq!(
+            Block::new(&r#"{ let s = "a;b"; s }"#.into()).map(|block| block.statements.len()),
+            Ok(2)
+        );
+    }
+    #[test]
+    fn eval_trailing_expression() {
+        let eval = |s: &str| Block::new(&s.into()).unwrap().eval(&Environment::default());
+        assert_eq!(eval("{ 1 }"), Ok(Value::Number(Number::from_i32(1))));
+        assert_eq!(eval("{ 1; }"), Ok(Value::Empty));
+        assert_eq!(
+            eval("{ let a = { let b = 1; b + 1 }; { a * 2 } }"),
+            Ok(Value::Number(Number::from_i32(4)))
+        );
+        assert_eq!(eval("{ let a = { 1; }; a }"), Ok(Value::Empty));
+        assert_eq!(
+            eval("{ fn double x => x * 2; enum Unit { Unit }; double 3 }"),
+            Ok(Value::Number(Number::from_i32(6)))
         );
     }
 }
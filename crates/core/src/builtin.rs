@@ -0,0 +1,469 @@
+use crate::internal::prelude::*;
+
+/// Functions which are always defined, unless a `FunctionDef` with the same name shadows them.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Builtin {
+    Len,
+    Upper,
+    Lower,
+    Substr,
+    ToString,
+    ParseInt,
+    Push,
+    Concat,
+    Map,
+    Filter,
+    Fold,
+    Range,
+    Divmod,
+}
+impl Builtin {
+    /// The most elements `range` builds, as the whole list is kept in memory.
+    pub const MAX_RANGE_LEN: usize = 1_000_000;
+    pub const ALL: [Self; 13] = [
+        Self::Len,
+        Self::Upper,
+        Self::Lower,
+        Self::Substr,
+        Self::ToString,
+        Self::ParseInt,
+        Self::Push,
+        Self::Concat,
+        Self::Map,
+        Self::Filter,
+        Self::Fold,
+        Self::Range,
+        Self::Divmod,
+    ];
+    pub fn new(name: &Identifier) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|builtin| builtin.name() == name.as_str())
+    }
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Len => "len",
+            Self::Upper => "upper",
+            Self::Lower => "lower",
+            Self::Substr => "substr",
+            Self::ToString => "to_string",
+            Self::ParseInt => "parse_int",
+            Self::Push => "push",
+            Self::Concat => "concat",
+            Self::Map => "map",
+            Self::Filter => "filter",
+            Self::Fold => "fold",
+            Self::Range => "range",
+            Self::Divmod => "divmod",
+        }
+    }
+    /// Call the builtin, where `env` is used to call the functions passed to it.
+    pub fn call(self, arguments: Vec<Value>, env: &Environment) -> Result<Value, Error> {
+        let value = match self {
+            Self::Len => {
+                let [value] = Self::arguments(arguments)?;
+                let len = match value {
+                    Value::List(list) => list.len(),
+                    value => value.try_into_str()?.chars().count(),
+                };
+                Value::Number(Self::len(len))
+            }
+            Self::Upper => {
+                let [s] = Self::arguments(arguments)?;
+                Value::Str(s.try_into_str()?.to_uppercase())
+            }
+            Self::Lower => {
+                let [s] = Self::arguments(arguments)?;
+                Value::Str(s.try_into_str()?.to_lowercase())
+            }
+            Self::Substr => {
+                let [s, start, len] = Self::arguments(arguments)?;
+                Value::Str(Self::substr(
+                    &s.try_into_str()?,
+                    start.try_into_number()?.inner(),
+                    len.try_into_number()?.inner(),
+                )?)
+            }
+            Self::ToString => {
+                let [value] = Self::arguments(arguments)?;
+                Value::Str(value.to_string())
+            }
+            Self::ParseInt => {
+                let [s] = Self::arguments(arguments)?;
+                Value::Number(Number::new(&s.try_into_str()?.as_str().into())?)
+            }
+            Self::Push => {
+                let [list, value] = Self::arguments(arguments)?;
+                let mut list = list.try_into_list()?;
+                list.push(value);
+                Value::List(list)
+            }
+            Self::Concat => {
+                let [lhs, rhs] = Self::arguments(arguments)?;
+                let mut list = lhs.try_into_list()?;
+                list.extend(rhs.try_into_list()?);
+                Value::List(list)
+            }
+            Self::Map => {
+                let [function, list] = Self::arguments(arguments)?;
+                let function = function.try_into_function()?;
+                Value::List(
+                    list.try_into_list()?
+                        .into_iter()
+                        .map(|value| function.call(vec![value], env))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            Self::Filter => {
+                let [function, list] = Self::arguments(arguments)?;
+                let function = function.try_into_function()?;
+                let mut filtered = Vec::new();
+                for value in list.try_into_list()? {
+                    if function
+                        .call(vec![value.clone()], env)?
+                        .try_into_condition()?
+                    {
+                        filtered.push(value);
+                    }
+                }
+                Value::List(filtered)
+            }
+            Self::Fold => {
+                let [function, init, list] = Self::arguments(arguments)?;
+                let function = function.try_into_function()?;
+                let mut accumulator = init;
+                for value in list.try_into_list()? {
+                    accumulator = function.call(vec![accumulator, value], env)?;
+                }
+                accumulator
+            }
+            Self::Range => {
+                let range = Self::range(Self::range_bounds(arguments)?)?;
+                Value::List(
+                    range
+                        .map(|value| Value::Number(Number::from_i32(value)))
+                        .collect(),
+                )
+            }
+            Self::Divmod => {
+                let [lhs, rhs] = Self::arguments(arguments)?;
+                let (lhs, rhs) = (
+                    lhs.try_into_number()?.inner(),
+                    rhs.try_into_number()?.inner(),
+                );
+                if rhs == 0 {
+                    return Err(OperationError::DivisionByZero)?;
+                }
+                let (Some(quotient), Some(remainder)) =
+                    (lhs.checked_div(rhs), lhs.checked_rem(rhs))
+                else {
+                    return Err(OperationError::Overflow)?;
+                };
+                Value::Tuple(vec![
+                    Value::Number(Number::from_i32(quotient)),
+                    Value::Number(Number::from_i32(remainder)),
+                ])
+            }
+        };
+        Ok(value)
+    }
+    fn arguments<const N: usize>(arguments: Vec<Value>) -> Result<[Value; N], FunctionCallError> {
+        let got = arguments.len();
+        arguments
+            .try_into()
+            .map_err(|_| FunctionCallError::WrongParameterCount { expected: N, got })
+    }
+    /// Get the bounds of a `range`, which a `for` loop goes through without making the list.
+    pub fn range_bounds(arguments: Vec<Value>) -> Result<std::ops::Range<i32>, Error> {
+        let [start, end] = Self::arguments(arguments)?;
+        Ok(start.try_into_number()?.inner()..end.try_into_number()?.inner())
+    }
+    fn range(range: std::ops::Range<i32>) -> Result<std::ops::Range<i32>, ValueError> {
+        let len = usize::try_from(i64::from(range.end) - i64::from(range.start)).unwrap_or(0);
+        if len > Self::MAX_RANGE_LEN {
+            return Err(ValueError::RangeTooLong {
+                len,
+                max: Self::MAX_RANGE_LEN,
+            });
+        }
+        Ok(range)
+    }
+    fn len(len: usize) -> Number {
+        Number::from_i32(i32::try_from(len).unwrap_or(i32::MAX))
+    }
+    /// Take `len` chars starting from the char at `start`.
+    fn substr(s: &str, start: i32, len: i32) -> Result<String, ValueError> {
+        let count = s.chars().count();
+        let out_of_range = |index| ValueError::IndexOutOfRange { index, len: count };
+        let skip = usize::try_from(start)
+            .ok()
+            .filter(|&start| start <= count)
+            .ok_or(out_of_range(start))?;
+        let take = usize::try_from(len)
+            .ok()
+            .filter(|&len| skip + len <= count)
+            .ok_or(out_of_range(start.saturating_add(len)))?;
+        Ok(s.chars().skip(skip).take(take).collect())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn call(name: &str, arguments: Vec<Value>) -> Result<Value, Error> {
+        Builtin::new(&name.try_into().unwrap())
+            .unwrap()
+            .call(arguments, &Environment::default())
+    }
+    fn numbers(values: &[i32]) -> Value {
+        Value::List(values.iter().copied().map(Value::from).collect())
+    }
+    fn function(s: &str) -> Value {
+        let def = FunctionDef::new(&s.into()).unwrap();
+        let env = &mut Environment::default();
+        def.store(env).unwrap();
+        let name = s.split_whitespace().nth(1).unwrap();
+        Identifier::new(&name.into())
+            .unwrap()
+            .try_get_value_from(env)
+            .unwrap()
+    }
+    #[test]
+    fn new() {
+        assert_eq!(Builtin::new(&"len".try_into().unwrap()), Some(Builtin::Len));
+        assert_eq!(Builtin::new(&"length".try_into().unwrap()), None);
+    }
+    #[test]
+    fn call_len() {
+        assert_eq!(
+            call("len", vec![Value::from("中文abc")]),
+            Ok(Value::from(5))
+        );
+        assert_eq!(
+            call("len", vec![Value::from(1)]),
+            Err(Error::Value(ValueError::TypeMismatch {
+                expected: "Str",
+                found: "Int"
+            }))
+        );
+    }
+    #[test]
+    fn call_len_of_list() {
+        assert_eq!(call("len", vec![numbers(&[1, 2, 3])]), Ok(Value::from(3)));
+    }
+    #[test]
+    fn call_upper_and_lower() {
+        assert_eq!(
+            call("upper", vec![Value::from("Hello")]),
+            Ok(Value::from("HELLO"))
+        );
+        assert_eq!(
+            call("lower", vec![Value::from("Hello")]),
+            Ok(Value::from("hello"))
+        );
+    }
+    #[test]
+    fn call_substr() {
+        assert_eq!(
+            call(
+                "substr",
+                vec![Value::from("hello"), Value::from(1), Value::from(3)]
+            ),
+            Ok(Value::from("ell"))
+        );
+        assert_eq!(
+            call(
+                "substr",
+                vec![Value::from("hello"), Value::from(5), Value::from(0)]
+            ),
+            Ok(Value::from(""))
+        );
+        assert_eq!(
+            call(
+                "substr",
+                vec![Value::from("hello"), Value::from(6), Value::from(0)]
+            ),
+            Err(Error::Value(ValueError::IndexOutOfRange {
+                index: 6,
+                len: 5
+            }))
+        );
+        assert_eq!(
+            call(
+                "substr",
+                vec![Value::from("hello"), Value::from(3), Value::from(3)]
+            ),
+            Err(Error::Value(ValueError::IndexOutOfRange {
+                index: 6,
+                len: 5
+            }))
+        );
+        assert_eq!(
+            call(
+                "substr",
+                vec![Value::from("hello"), Value::from(-1), Value::from(1)]
+            ),
+            Err(Error::Value(ValueError::IndexOutOfRange {
+                index: -1,
+                len: 5
+            }))
+        );
+    }
+    #[test]
+    fn call_to_string() {
+        assert_eq!(
+            call("to_string", vec![Value::from(-42)]),
+            Ok(Value::from("-42"))
+        );
+        assert_eq!(
+            call("to_string", vec![Value::from("a")]),
+            Ok(Value::from("a"))
+        );
+    }
+    #[test]
+    fn call_parse_int() {
+        assert_eq!(
+            call("parse_int", vec![Value::from("0x10")]),
+            Ok(Value::from(16))
+        );
+        assert!(matches!(
+            call("parse_int", vec![Value::from("ten")]),
+            Err(Error::Number(NumberError::InvalidNumber(_)))
+        ));
+    }
+    #[test]
+    fn call_push_and_concat() {
+        assert_eq!(
+            call("push", vec![numbers(&[1]), Value::from(2)]),
+            Ok(numbers(&[1, 2]))
+        );
+        assert_eq!(
+            call("concat", vec![numbers(&[1]), numbers(&[2, 3])]),
+            Ok(numbers(&[1, 2, 3]))
+        );
+        assert_eq!(
+            call("concat", vec![numbers(&[1]), Value::from(2)]),
+            Err(Error::Value(ValueError::TypeMismatch {
+                expected: "List",
+                found: "Int"
+            }))
+        );
+    }
+    #[test]
+    fn call_map() {
+        assert_eq!(
+            call(
+                "map",
+                vec![function("fn square x => x * x"), numbers(&[1, 2, 3])]
+            ),
+            Ok(numbers(&[1, 4, 9]))
+        );
+        assert_eq!(
+            call(
+                "map",
+                vec![
+                    Value::Function(Function::Builtin(Builtin::ToString)),
+                    numbers(&[1])
+                ]
+            ),
+            Ok(Value::List(vec![Value::from("1")]))
+        );
+        assert_eq!(
+            call("map", vec![Value::from(1), numbers(&[1])]),
+            Err(Error::Value(ValueError::TypeMismatch {
+                expected: "Fn",
+                found: "Int"
+            }))
+        );
+    }
+    #[test]
+    fn call_filter() {
+        assert_eq!(
+            call(
+                "filter",
+                vec![function("fn odd x => x & 1"), numbers(&[1, 2, 3, 4, 5])]
+            ),
+            Ok(numbers(&[1, 3, 5]))
+        );
+    }
+    #[test]
+    fn call_fold() {
+        assert_eq!(
+            call(
+                "fold",
+                vec![
+                    function("fn sub acc x => acc - x"),
+                    Value::from(10),
+                    numbers(&[1, 2, 3])
+                ]
+            ),
+            Ok(Value::from(10 - 1 - 2 - 3))
+        );
+        assert_eq!(
+            call(
+                "fold",
+                vec![
+                    function("fn sub acc x => acc - x"),
+                    Value::from(10),
+                    numbers(&[])
+                ]
+            ),
+            Ok(Value::from(10))
+        );
+    }
+    #[test]
+    fn call_range() {
+        assert_eq!(
+            call("range", vec![Value::from(-1), Value::from(3)]),
+            Ok(numbers(&[-1, 0, 1, 2]))
+        );
+        assert_eq!(
+            call("range", vec![Value::from(3), Value::from(1)]),
+            Ok(numbers(&[]))
+        );
+        assert_eq!(
+            call("range", vec![Value::from(0), Value::from(2_000_000_000)]),
+            Err(Error::Value(ValueError::RangeTooLong {
+                len: 2_000_000_000,
+                max: Builtin::MAX_RANGE_LEN
+            }))
+        );
+        assert_eq!(
+            call("range", vec![Value::from(i32::MIN), Value::from(i32::MAX)]),
+            Err(Error::Value(ValueError::RangeTooLong {
+                len: u32::MAX as usize,
+                max: Builtin::MAX_RANGE_LEN
+            }))
+        );
+    }
+    #[test]
+    fn call_divmod() {
+        assert_eq!(
+            call("divmod", vec![Value::from(7), Value::from(2)]),
+            Ok(Value::Tuple(vec![Value::from(3), Value::from(1)]))
+        );
+        assert_eq!(
+            call("divmod", vec![Value::from(-7), Value::from(2)]),
+            Ok(Value::Tuple(vec![Value::from(-3), Value::from(-1)]))
+        );
+        assert_eq!(
+            call("divmod", vec![Value::from(7), Value::from(0)]),
+            Err(Error::Operation(OperationError::DivisionByZero))
+        );
+        assert_eq!(
+            call("divmod", vec![Value::from(i32::MIN), Value::from(-1)]),
+            Err(Error::Operation(OperationError::Overflow))
+        );
+    }
+    #[test]
+    fn call_with_wrong_parameter_count() {
+        assert_eq!(
+            call("upper", vec![]),
+            Err(Error::FunctionCall(
+                FunctionCallError::WrongParameterCount {
+                    expected: 1,
+                    got: 0
+                }
+            ))
+        );
+    }
+}
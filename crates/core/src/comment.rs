@@ -0,0 +1,81 @@
+/// A `//` comment, which runs to the end of the line and is skipped by the parser.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Comment<'a> {
+    /// Where the comment starts in the source, at its `//`.
+    pub start: usize,
+    /// The comment from its `//`, without the newline.
+    pub text: &'a str,
+}
+impl<'a> Comment<'a> {
+    pub const PREFIX: &'static str = "//";
+    /// Find the comments in `s`, skipping `//` in string literals.
+    pub fn find(s: &'a str) -> Vec<Self> {
+        let mut comments = Vec::new();
+        let mut in_string = false;
+        let mut escaped = false;
+        // the end of the last comment, which the chars before are part of
+        let mut comment_end = 0;
+        for (index, c) in s.char_indices() {
+            if index < comment_end {
+                continue;
+            }
+            if in_string {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == '"' {
+                    in_string = false;
+                }
+            } else if c == '"' {
+                in_string = true;
+            } else if s[index..].starts_with(Self::PREFIX) {
+                comment_end = s[index..].find('\n').map_or(s.len(), |end| index + end);
+                comments.push(Self {
+                    start: index,
+                    text: &s[index..comment_end],
+                });
+            }
+        }
+        comments
+    }
+    /// Replace the comments in `s` with spaces, so the rest keeps its position and line.
+    pub fn strip(s: &str) -> String {
+        let mut stripped = s.to_string();
+        for comment in Comment::find(s) {
+            let end = comment.end();
+            stripped.replace_range(comment.start..end, &" ".repeat(end - comment.start));
+        }
+        stripped
+    }
+    /// Where the comment ends in the source, before the newline.
+    pub const fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn find_comments() {
+        assert_eq!(
+            Comment::find("1 // one\n\"// two\" // \"three\"\n4 / 2"),
+            vec![
+                Comment {
+                    start: 2,
+                    text: "// one"
+                },
+                Comment {
+                    start: 18,
+                    text: "// \"three\""
+                },
+            ]
+        );
+    }
+    #[test]
+    fn strip_comments() {
+        assert_eq!(Comment::strip("x // é\ny"), "x      \ny");
+        assert_eq!(Comment::strip("\"a // b\""), "\"a // b\"");
+    }
+}
@@ -0,0 +1,123 @@
+use crate::internal::prelude::*;
+
+/// A `break` or `continue` in the body of a loop.
+///
+/// It is evaluated to `ControlFlowError`, which unwinds to the nearest loop.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ControlFlow {
+    Break(Option<Box<Expression>>),
+    Continue,
+}
+impl ControlFlow {
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        if **s == *Keyword::Continue.as_str() {
+            return Ok(Self::Continue);
+        }
+        if **s == *Keyword::Break.as_str() {
+            return Ok(Self::Break(None));
+        }
+        let value = Keyword::Break.strip(s).ok_or(ControlFlowError::NotFound)?;
+        Ok(Self::Break(Some(Expression::new(&value.into())?.into())))
+    }
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        match self {
+            Self::Break(Some(value)) => value.resolve(resolver),
+            Self::Break(None) | Self::Continue => Ok(()),
+        }
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        if let Self::Break(Some(value)) = self {
+            value.lint(linter);
+        }
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<Type, Error> {
+        let Some(output) = checker.loop_output() else {
+            return Err(ControlFlowError::OutsideLoop(self.keyword().to_string()))?;
+        };
+        match self {
+            Self::Break(Some(value)) => {
+                let value = value.infer(checker)?;
+                checker.unify(&output, &value)?;
+            }
+            Self::Break(None) => checker.unify(&output, &Type::Empty)?,
+            Self::Continue => (),
+        }
+        // the control never reaches what follows, so it fits any type
+        Ok(checker.fresh())
+    }
+    pub fn eval(&self, env: &Environment) -> Result<Value, Error> {
+        let signal = match self {
+            Self::Break(Some(value)) => ControlFlowError::Break(value.eval(env)?.into()),
+            Self::Break(None) => ControlFlowError::Break(Value::Empty.into()),
+            Self::Continue => ControlFlowError::Continue,
+        };
+        Err(signal)?
+    }
+    const fn keyword(&self) -> Keyword {
+        match self {
+            Self::Break(_) => Keyword::Break,
+            Self::Continue => Keyword::Continue,
+        }
+    }
+    /// Run an iteration of a loop, where `Some` is the value of the loop if it breaks.
+    pub fn iterate(body: &Block, env: &Environment) -> Result<Option<Value>, Error> {
+        env.step()?;
+        match body.eval(env) {
+            Ok(_) | Err(Error::ControlFlow(ControlFlowError::Continue)) => Ok(None),
+            Err(Error::ControlFlow(ControlFlowError::Break(value))) => Ok(Some(*value)),
+            Err(err) => Err(err),
+        }
+    }
+}
+impl std::fmt::Display for ControlFlow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Break(None) => write!(f, "{}", Keyword::Break),
+            Self::Break(Some(value)) => {
+                write!(f, "{} ", Keyword::Break)?;
+                value.fmt(f)
+            }
+            Self::Continue => write!(f, "{}", Keyword::Continue),
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parse_control_flow() {
+        assert_eq!(
+            ControlFlow::new(&"continue".into()),
+            Ok(ControlFlow::Continue)
+        );
+        assert_eq!(
+            ControlFlow::new(&"break".into()),
+            Ok(ControlFlow::Break(None))
+        );
+        assert_eq!(
+            ControlFlow::new(&"break x + 1".into()),
+            Ok(ControlFlow::Break(Some(
+                Expression::new(&"x + 1".into()).unwrap().into()
+            )))
+        );
+        assert_eq!(
+            ControlFlow::new(&"breaks".into()),
+            Err(Error::ControlFlow(ControlFlowError::NotFound))
+        );
+    }
+    #[test]
+    fn eval_outside_loop() {
+        assert_eq!(
+            ControlFlow::new(&"break 1".into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Err(Error::ControlFlow(ControlFlowError::Break(
+                Value::Number(Number::from_i32(1)).into()
+            )))
+        );
+        assert_eq!(
+            ControlFlowError::Continue.to_string(),
+            "`continue` outside of a loop"
+        );
+    }
+}
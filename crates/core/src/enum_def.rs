@@ -0,0 +1,254 @@
+use crate::internal::prelude::*;
+use std::rc::Rc;
+
+/// A declaration of an algebraic data type like `enum Shape { Circle(r), Rect(w, h) }`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EnumDef {
+    name: Identifier,
+    variants: Vec<Variant>,
+}
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Variant {
+    name: Identifier,
+    fields: Vec<Identifier>,
+}
+/// A variant of an enum, which constructs values like `Circle 1`, or `None` if it has no field.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Constructor {
+    enum_def: Rc<EnumDef>,
+    nth: usize,
+}
+impl EnumDef {
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        let Some(s) = Keyword::Enum.strip(s) else {
+            return Err(EnumDefError::MissingEnumKeyword)?;
+        };
+        let nth = s.find('{').ok_or(EnumDefError::MissingOpeningBrace)?;
+        let name = Identifier::new(&s[..nth].into())?;
+        let Some(variants) = TrimmedStr::new(&s[nth..]).strip_wrapping('{', '}') else {
+            return Err(EnumDefError::MissingClosingBrace)?;
+        };
+        let mut parsed: Vec<Variant> = Vec::new();
+        for variant in variants.split_list() {
+            let variant = Variant::new(&variant)?;
+            if parsed.iter().any(|parsed| parsed.name == variant.name) {
+                return Err(EnumDefError::DuplicateVariant(variant.name.to_string()))?;
+            }
+            parsed.push(variant);
+        }
+        if parsed.is_empty() {
+            return Err(EnumDefError::NoVariant)?;
+        }
+        Ok(Self {
+            name,
+            variants: parsed,
+        })
+    }
+    pub const fn name(&self) -> &Identifier {
+        &self.name
+    }
+    pub fn resolve(&self, resolver: &mut Resolver) {
+        for variant in &self.variants {
+            resolver.declare(variant.name.clone());
+        }
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        for variant in &self.variants {
+            linter.declare_variant(&variant.name);
+        }
+    }
+    /// Define the variants, whose fields get their types from how they are used.
+    pub fn infer(&self, checker: &mut TypeChecker) {
+        let ty = Type::Enum(self.name.clone());
+        for variant in &self.variants {
+            let fields = variant.fields.iter().map(|_| checker.fresh()).collect();
+            checker.define_constructor(variant.name.clone(), fields, ty.clone());
+        }
+    }
+    /// Bind the constructors of the variants, where a variant without fields is bound as a value.
+    pub fn store(&self, env: &mut Environment) {
+        let enum_def = Rc::new(self.clone());
+        for (nth, variant) in self.variants.iter().enumerate() {
+            let constructor = Constructor {
+                enum_def: Rc::clone(&enum_def),
+                nth,
+            };
+            let value = if variant.fields.is_empty() {
+                Value::Variant(constructor, vec![])
+            } else {
+                Value::Function(Function::Constructor(constructor))
+            };
+            env.insert_binding(variant.name.clone(), value);
+        }
+    }
+}
+impl Variant {
+    fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        let Some(nth) = s.find('(') else {
+            return Ok(Self {
+                name: Identifier::new(s)?,
+                fields: vec![],
+            });
+        };
+        let Some(fields) = TrimmedStr::new(&s[nth..]).strip_wrapping('(', ')') else {
+            return Err(EnumDefError::InvalidVariant(s.to_string()))?;
+        };
+        Ok(Self {
+            name: Identifier::new(&s[..nth].into())?,
+            fields: fields
+                .split_list()
+                .iter()
+                .map(Identifier::new)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+impl Constructor {
+    pub fn name(&self) -> &Identifier {
+        &self.variant().name
+    }
+    pub fn enum_def(&self) -> &EnumDef {
+        &self.enum_def
+    }
+    pub fn enum_name(&self) -> &Identifier {
+        &self.enum_def.name
+    }
+    pub fn arity(&self) -> usize {
+        self.variant().fields.len()
+    }
+    /// All the variants of the enum, including this one.
+    pub fn siblings(&self) -> impl Iterator<Item = &Identifier> {
+        self.enum_def.variants.iter().map(|variant| &variant.name)
+    }
+    pub fn call(&self, arguments: Vec<Value>) -> Result<Value, Error> {
+        if arguments.len() != self.arity() {
+            return Err(FunctionCallError::WrongParameterCount {
+                expected: self.arity(),
+                got: arguments.len(),
+            })?;
+        }
+        Ok(Value::Variant(self.clone(), arguments))
+    }
+    fn variant(&self) -> &Variant {
+        &self.enum_def.variants[self.nth]
+    }
+}
+impl std::fmt::Display for EnumDef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {{", Keyword::Enum, self.name)?;
+        for (i, variant) in self.variants.iter().enumerate() {
+            write!(f, "{} {variant}", if i > 0 { "," } else { "" })?;
+        }
+        write!(f, " }}")
+    }
+}
+impl std::fmt::Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.fields.is_empty() {
+            write!(f, "(")?;
+            for (i, field) in self.fields.iter().enumerate() {
+                write!(f, "{}{field}", if i > 0 { ", " } else { "" })?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn variant(name: &str, fields: &[&str]) -> Variant {
+        Variant {
+            name: name.try_into().unwrap(),
+            fields: fields
+                .iter()
+                .map(|field| (*field).try_into().unwrap())
+                .collect(),
+        }
+    }
+    #[test]
+    fn parse_enum_def() {
+        assert_eq!(
+            EnumDef::new(&"enum Shape { Circle(r), Rect(w, h), Empty }".into()),
+            Ok(EnumDef {
+                name: "Shape".try_into().unwrap(),
+                variants: vec![
+                    variant("Circle", &["r"]),
+                    variant("Rect", &["w", "h"]),
+                    variant("Empty", &[])
+                ]
+            })
+        );
+    }
+    #[test]
+    fn parse_invalid_enum_def() {
+        assert_eq!(
+            EnumDef::new(&"Shape { Circle(r) }".into()),
+            Err(Error::EnumDef(EnumDefError::MissingEnumKeyword))
+        );
+        assert_eq!(
+            EnumDef::new(&"enum Shape".into()),
+            Err(Error::EnumDef(EnumDefError::MissingOpeningBrace))
+        );
+        assert_eq!(
+            EnumDef::new(&"enum Shape { Circle(r) ".into()),
+            Err(Error::EnumDef(EnumDefError::MissingClosingBrace))
+        );
+        assert_eq!(
+            EnumDef::new(&"enum Shape { Circle(r) x }".into()),
+            Err(Error::EnumDef(EnumDefError::InvalidVariant(
+                "Circle(r) x".to_string()
+            )))
+        );
+        assert_eq!(
+            EnumDef::new(&"enum Shape { }".into()),
+            Err(Error::EnumDef(EnumDefError::NoVariant))
+        );
+        assert_eq!(
+            EnumDef::new(&"enum Shape { Circle, Circle(r) }".into()),
+            Err(Error::EnumDef(EnumDefError::DuplicateVariant(
+                "Circle".to_string()
+            )))
+        );
+    }
+    #[test]
+    fn store_constructors() {
+        let env = &mut Environment::default();
+        EnumDef::new(&"enum Option { Some(x), None }".into())
+            .unwrap()
+            .store(env);
+        let Ok(Value::Function(Function::Constructor(some))) = Identifier::new(&"Some".into())
+            .unwrap()
+            .try_get_value_from(env)
+        else {
+            panic!("`Some` should be a constructor");
+        };
+        assert_eq!(some.name().as_str(), "Some");
+        assert_eq!(some.enum_name().as_str(), "Option");
+        assert_eq!(
+            some.siblings().map(Identifier::as_str).collect::<Vec<_>>(),
+            ["Some", "None"]
+        );
+        assert_eq!(
+            some.call(vec![Value::Number(Number::from_i32(1))]),
+            Ok(Value::Variant(
+                some.clone(),
+                vec![Value::Number(Number::from_i32(1))]
+            ))
+        );
+        assert_eq!(
+            some.call(vec![]),
+            Err(Error::FunctionCall(
+                FunctionCallError::WrongParameterCount {
+                    expected: 1,
+                    got: 0
+                }
+            ))
+        );
+        assert!(matches!(
+            Identifier::new(&"None".into()).unwrap().try_get_value_from(env),
+            Ok(Value::Variant(none, fields)) if none.name().as_str() == "None" && fields.is_empty()
+        ));
+    }
+}
@@ -1,29 +1,103 @@
 use crate::internal::prelude::*;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct Environment<'parent> {
-    bindings: HashMap<Identifier, NamedValue>,
+    /// The bindings declared with `let mut` can be assigned through a shared reference.
+    bindings: RefCell<HashMap<Identifier, NamedValue>>,
+    mutable: HashSet<Identifier>,
+    /// The loop iterations left, which are only counted in the root.
+    steps: Cell<Option<usize>>,
+    /// The calls of functions being run, which are only counted in the root.
+    calls: Cell<usize>,
     parent: Option<&'parent Self>,
 }
 
 impl<'parent> Environment<'parent> {
+    /// The calls which may be nested, which is few enough for the stack of the main thread.
+    pub const MAX_CALL_DEPTH: usize = 200;
     pub fn create_child(&'parent self) -> Self {
         Self {
             parent: Some(self),
             ..Default::default()
         }
     }
-    pub fn insert_binding(&mut self, name: Identifier, expr: Expression) {
-        self.bindings.insert(name, NamedValue::Binding(expr));
+    /// Limit the loop iterations, so that an infinite loop stops with an error.
+    pub fn set_step_budget(&self, steps: Option<usize>) {
+        self.steps.set(steps);
+    }
+    /// Count an iteration of a loop against the budget of the root.
+    pub fn step(&self) -> Result<(), LoopError> {
+        if let Some(parent) = self.parent {
+            return parent.step();
+        }
+        match self.steps.get() {
+            Some(0) => Err(LoopError::StepBudgetExceeded),
+            steps => {
+                self.steps.set(steps.map(|steps| steps - 1));
+                Ok(())
+            }
+        }
+    }
+    /// Count a call of a function in the root, so that a function which calls itself without end
+    /// stops with an error rather than overflowing the stack.
+    pub fn enter_call(&self, function: &str) -> Result<(), FunctionCallError> {
+        if let Some(parent) = self.parent {
+            return parent.enter_call(function);
+        }
+        if self.calls.get() == Self::MAX_CALL_DEPTH {
+            return Err(FunctionCallError::TooDeep {
+                function: function.to_string(),
+                depth: Self::MAX_CALL_DEPTH,
+            });
+        }
+        self.calls.set(self.calls.get() + 1);
+        Ok(())
+    }
+    /// Count the end of a call counted by `enter_call`.
+    pub fn leave_call(&self) {
+        match self.parent {
+            Some(parent) => parent.leave_call(),
+            None => self.calls.set(self.calls.get().saturating_sub(1)),
+        }
+    }
+    pub fn insert_binding(&mut self, name: Identifier, value: Value) {
+        self.mutable.remove(&name);
+        self.bindings
+            .get_mut()
+            .insert(name, NamedValue::Binding(value));
+    }
+    pub fn insert_mutable_binding(&mut self, name: Identifier, value: Value) {
+        self.mutable.insert(name.clone());
+        self.bindings
+            .get_mut()
+            .insert(name, NamedValue::Binding(value));
     }
     pub fn insert_function(
         &mut self,
         name: Identifier,
-        parameters: Vec<Identifier>,
+        parameters: Vec<Pattern>,
+        output: Option<Type>,
         body: Expression,
+        captured: Vec<(Identifier, NamedValue)>,
     ) {
-        self.bindings
-            .insert(name, NamedValue::Function { parameters, body });
+        self.mutable.remove(&name);
+        self.bindings.get_mut().insert(
+            name,
+            NamedValue::Function {
+                parameters,
+                output,
+                body,
+                captured,
+            },
+        );
+    }
+    /// Bind the names which a function captured where it is defined.
+    pub fn insert_captured(&mut self, captured: &[(Identifier, NamedValue)]) {
+        for (name, value) in captured {
+            self.mutable.remove(name);
+            self.bindings.get_mut().insert(name.clone(), value.clone());
+        }
     }
     pub fn get_from_self_and_parent(&self, name: &Identifier) -> Option<NamedValue> {
         self.get_from_self(name).or_else(|| {
@@ -43,37 +117,287 @@ impl<'parent> Environment<'parent> {
             })
         })
     }
+    /// The names bound here or in a parent, like for completion.
+    pub fn names(&self) -> Vec<Identifier> {
+        let mut names: Vec<_> = self.bindings.borrow().keys().cloned().collect();
+        if let Some(parent) = self.parent {
+            names.extend(parent.names());
+        }
+        names
+    }
+    /// The definitions of the names bound here, like `let x = 1;` and `fn f x => x;`, sorted by name.
+    ///
+    /// The constructors of an enum are defined by the `enum` itself.
+    pub fn definitions(&self) -> Vec<String> {
+        self.named_definitions()
+            .into_iter()
+            .map(|(_, definition)| definition)
+            .collect()
+    }
+    pub fn definition(&self, name: &str) -> Option<String> {
+        self.named_definitions()
+            .into_iter()
+            .find(|(defined, _)| defined == name)
+            .map(|(_, definition)| definition)
+    }
+    /// Write the definitions as a program, which binds the same values when it runs.
+    ///
+    /// Each definition is written after the names it uses, and a value which a function
+    /// captured before its name was bound again is written before the function.
+    /// The `omitted` names are only written if something else uses them.
+    pub fn to_source(&self, omitted: &[&str]) -> Result<String, SessionError> {
+        let mut names: Vec<_> = self
+            .bindings
+            .borrow()
+            .keys()
+            .filter(|name| !omitted.contains(&name.as_str()))
+            .cloned()
+            .collect();
+        names.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        let mut restoring = Restoring::default();
+        // writing what a function captured may bind a name again, which is written after it
+        for _ in 0..=names.len() {
+            let stale: Vec<_> = names
+                .iter()
+                .filter(|name| !self.is_restored(name, &restoring.env))
+                .collect();
+            if stale.is_empty() {
+                return Ok(restoring.source.join("\n"));
+            }
+            for name in stale {
+                if let Some(named_value) = self.get_from_self(name) {
+                    self.restore(
+                        name,
+                        &named_value,
+                        self.mutable.contains(name),
+                        &mut restoring,
+                    )?;
+                }
+            }
+        }
+        Err(SessionError::Unordered(names[0].to_string()))
+    }
+    fn is_restored(&self, name: &Identifier, restored: &Environment) -> bool {
+        restored.get_from_self(name) == self.get_from_self(name)
+            && restored.mutable.contains(name) == self.mutable.contains(name)
+    }
+    /// Write the definition of `name`, after the names it uses.
+    fn restore(
+        &self,
+        name: &Identifier,
+        named_value: &NamedValue,
+        mutable: bool,
+        restoring: &mut Restoring,
+    ) -> Result<(), SessionError> {
+        if restoring.env.get_from_self(name).as_ref() == Some(named_value)
+            && restoring.env.mutable.contains(name) == mutable
+        {
+            return Ok(());
+        }
+        if restoring.pending.contains(name) {
+            return Err(SessionError::Unordered(name.to_string()));
+        }
+        restoring.pending.push(name.clone());
+        if let NamedValue::Function { captured, .. } = named_value {
+            for (captured_name, captured_value) in captured {
+                self.restore(captured_name, captured_value, false, restoring)?;
+            }
+        }
+        let (_, definition) = Self::definition_of(name, named_value, mutable);
+        loop {
+            match Self::replay(&definition, &mut restoring.env) {
+                Ok(()) => break,
+                Err(Error::Resolve(ResolveError::UnknownIdentifier(used))) => {
+                    let used: Identifier = used
+                        .as_str()
+                        .try_into()
+                        .map_err(|_| SessionError::Unrestorable(name.to_string()))?;
+                    let Some(used_value) = self.get_from_self(&used) else {
+                        return Err(SessionError::Unrestorable(name.to_string()));
+                    };
+                    self.restore(&used, &used_value, self.mutable.contains(&used), restoring)?;
+                }
+                Err(_) => return Err(SessionError::Unrestorable(name.to_string())),
+            }
+        }
+        restoring.pending.pop();
+        if restoring.env.get_from_self(name).as_ref() != Some(named_value) {
+            return Err(SessionError::Unrestorable(name.to_string()));
+        }
+        restoring.source.push(definition);
+        Ok(())
+    }
+    /// Run a definition written by `to_source`, checking the names it uses are defined.
+    fn replay(definition: &str, env: &mut Environment) -> Result<(), Error> {
+        let statement = Statement::new(&definition.into())?;
+        statement.resolve(&mut Resolver::new(env))?;
+        statement.get_expression_in(env)?;
+        Ok(())
+    }
+    fn named_definitions(&self) -> Vec<(String, String)> {
+        let mut definitions: Vec<(String, String)> = self
+            .bindings
+            .borrow()
+            .iter()
+            .map(|(name, named_value)| {
+                Self::definition_of(name, named_value, self.mutable.contains(name))
+            })
+            .collect();
+        definitions.sort();
+        definitions.dedup();
+        definitions
+    }
+    /// The name defined and its definition, where a constructor is defined by its `enum`.
+    ///
+    /// Each definition ends with `;` like in a program, even a `fn` or an `enum`.
+    fn definition_of(
+        name: &Identifier,
+        named_value: &NamedValue,
+        mutable: bool,
+    ) -> (String, String) {
+        let constructor = match named_value {
+            NamedValue::Binding(Value::Function(Function::Constructor(constructor))) => {
+                Some(constructor)
+            }
+            NamedValue::Binding(Value::Variant(constructor, fields))
+                if fields.is_empty() && constructor.arity() == 0 =>
+            {
+                Some(constructor)
+            }
+            _ => None,
+        };
+        match (named_value, constructor) {
+            (_, Some(constructor)) if constructor.name() == name => {
+                let enum_def = constructor.enum_def();
+                (enum_def.name().to_string(), format!("{enum_def};"))
+            }
+            (NamedValue::Binding(value), _) => {
+                let keyword = if mutable {
+                    format!("{} {}", Keyword::Let, Keyword::Mut)
+                } else {
+                    Keyword::Let.to_string()
+                };
+                let source = value.to_source();
+                (name.to_string(), format!("{keyword} {name} = {source};"))
+            }
+            (
+                NamedValue::Function {
+                    parameters,
+                    output,
+                    body,
+                    ..
+                },
+                _,
+            ) => {
+                let function_def = FunctionDef::from_parts(
+                    name.clone(),
+                    parameters.clone(),
+                    output.clone(),
+                    body.clone(),
+                );
+                (name.to_string(), format!("{function_def};"))
+            }
+        }
+    }
     fn get_from_self(&self, name: &Identifier) -> Option<NamedValue> {
-        self.bindings.get(name).cloned()
+        self.bindings.borrow().get(name).cloned()
+    }
+    /// Whether the nearest binding of the name is declared with `let mut`.
+    pub fn is_mutable(&self, name: &Identifier) -> bool {
+        if self.bindings.borrow().contains_key(name) {
+            return self.mutable.contains(name);
+        }
+        self.parent.is_some_and(|parent| parent.is_mutable(name))
     }
+    /// Change the value of the nearest binding of the name, which must be mutable.
+    pub fn assign(&self, name: &Identifier, value: Value) -> Result<(), AssignmentError> {
+        match self.bindings.borrow_mut().get_mut(name) {
+            Some(NamedValue::Binding(bound)) if self.mutable.contains(name) => {
+                *bound = value;
+                Ok(())
+            }
+            Some(_) => Err(AssignmentError::Immutable(name.to_string())),
+            None => self.parent.map_or_else(
+                || Err(AssignmentError::Undefined(name.to_string())),
+                |parent| parent.assign(name, value),
+            ),
+        }
+    }
+}
+
+/// The program written by `Environment::to_source` so far, and what it binds.
+#[derive(Default)]
+struct Restoring {
+    env: Environment<'static>,
+    source: Vec<String>,
+    /// The names whose definitions wait for the names they use.
+    pending: Vec<Identifier>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    fn run(program: &str, env: &mut Environment) {
+        for (_, s) in Block::split_statements(program) {
+            let statement = Statement::new(&s.into()).unwrap();
+            statement.get_expression_in(env).unwrap().eval(env).unwrap();
+        }
+    }
+    #[test]
+    fn restore_from_source() {
+        let env = &mut Environment::default();
+        run(
+            r#"enum Shape { Circle(r), Empty };
+            let x = 1;
+            fn area s => match s { Circle(r) => r * r * x, Empty => 0 };
+            let mut shapes = [Circle (-2), Empty];
+            let measure = area;
+            fn total xs => fold xs 0 (measure);
+            let x = { a: "3\n", b: (measure,) };"#,
+            env,
+        );
+        let source = env.to_source(&[]).unwrap();
+        let restored = &mut Environment::default();
+        run(&source, restored);
+        assert_eq!(restored.bindings, env.bindings);
+        assert_eq!(restored.mutable, env.mutable);
+        // `area` uses `x` as it was before `x` is bound again
+        assert!(source.contains("let x = 1;\nfn area s =>"));
+    }
+    #[test]
+    fn restore_invalid_source() {
+        let env = &mut Environment::default();
+        run("fn f x => 1; let g = f; fn f x => 2;", env);
+        assert_eq!(
+            env.to_source(&[]),
+            Err(SessionError::Unrestorable("g".to_string()))
+        );
+    }
     #[test]
     fn get_from_self_and_parent() {
         let env = &mut Environment::default();
         env.insert_binding(
             "something".try_into().unwrap(),
-            Expression::Number(Number::from_i32(11451)),
+            Value::Number(Number::from_i32(11451)),
         );
         assert_eq!(
             env.get_from_self_and_parent(&"something".try_into().unwrap()),
-            Some(NamedValue::Binding(Expression::Number(Number::from_i32(
-                11451
-            ))))
+            Some(NamedValue::Binding(Value::Number(Number::from_i32(11451))))
         );
         env.insert_function(
             "something".try_into().unwrap(),
             vec!["x".try_into().unwrap(), "y".try_into().unwrap()],
+            None,
             Expression::Operation(Operation::new(&"x + y".into()).unwrap()),
+            vec![],
         );
         assert_eq!(
             env.get_from_self_and_parent(&"something".try_into().unwrap()),
             Some(NamedValue::Function {
                 parameters: vec!["x".try_into().unwrap(), "y".try_into().unwrap()],
-                body: Expression::Operation(Operation::new(&"x+y".into()).unwrap())
+                output: None,
+                body: Expression::Operation(Operation::new(&"x+y".into()).unwrap()),
+                captured: vec![],
             })
         );
     }
@@ -82,13 +406,12 @@ mod tests {
         let parent = &mut Environment::default();
         BindingDef::new(&"let x = 114".into())
             .unwrap()
-            .store(parent);
+            .store(parent)
+            .unwrap();
         let child = &mut parent.create_child();
         assert_eq!(
             child.get_from_self_and_parent(&"x".try_into().unwrap()),
-            Some(NamedValue::Binding(Expression::Number(Number::from_i32(
-                114
-            ))))
+            Some(NamedValue::Binding(Value::Number(Number::from_i32(114))))
         );
     }
     #[test]
@@ -96,7 +419,8 @@ mod tests {
         let parent = &mut Environment::default();
         BindingDef::new(&"let x = 114".into())
             .unwrap()
-            .store(parent);
+            .store(parent)
+            .unwrap();
         let child = &mut parent.create_child();
         assert_eq!(child.get_from_self(&"x".try_into().unwrap()), None);
     }
@@ -105,10 +429,12 @@ mod tests {
         let parent = &mut Environment::default();
         BindingDef::new(&"let x = 114".into())
             .unwrap()
-            .store(parent);
+            .store(parent)
+            .unwrap();
         FunctionDef::new(&"fn f => 514".into())
             .unwrap()
-            .store(parent);
+            .store(parent)
+            .unwrap();
         let child = &mut parent.create_child();
         assert_eq!(
             child.get_from_self_and_get_function_from_parent(&"x".try_into().unwrap()),
@@ -118,7 +444,9 @@ mod tests {
             child.get_from_self_and_get_function_from_parent(&"f".try_into().unwrap()),
             Some(NamedValue::Function {
                 parameters: vec![],
-                body: Expression::Number(Number::from_i32(514))
+                output: None,
+                body: Expression::Number(Number::from_i32(514)),
+                captured: vec![],
             })
         );
     }
@@ -127,41 +455,48 @@ mod tests {
         let parent = &mut Environment::default();
         BindingDef::new(&"let x = 114".into())
             .unwrap()
-            .store(parent);
+            .store(parent)
+            .unwrap();
         let child = &mut parent.create_child();
-        BindingDef::new(&"let x = 514".into()).unwrap().store(child);
+        BindingDef::new(&"let x = 514".into())
+            .unwrap()
+            .store(child)
+            .unwrap();
         assert_eq!(
             child.get_from_self_and_parent(&"x".try_into().unwrap()),
-            Some(NamedValue::Binding(Expression::Number(Number::from_i32(
-                514
-            ))))
+            Some(NamedValue::Binding(Value::Number(Number::from_i32(514))))
         );
     }
     #[test]
     fn insert_multiple() {
         let env = &mut Environment::default();
-        BindingDef::new(&"let x = 11451".into()).unwrap().store(env);
+        BindingDef::new(&"let x = 11451".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
         assert_eq!(
             env.get_from_self(&"x".try_into().unwrap()),
-            Some(NamedValue::Binding(Expression::Number(Number::from_i32(
-                11451
-            ))))
+            Some(NamedValue::Binding(Value::Number(Number::from_i32(11451))))
         );
-        BindingDef::new(&"let x = 19198".into()).unwrap().store(env);
+        BindingDef::new(&"let x = 19198".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
         assert_eq!(
             env.get_from_self(&"x".try_into().unwrap()),
-            Some(NamedValue::Binding(Expression::Number(Number::from_i32(
-                19198
-            ))))
+            Some(NamedValue::Binding(Value::Number(Number::from_i32(19198))))
         );
         FunctionDef::new(&"fn x => 114+514".into())
             .unwrap()
-            .store(env);
+            .store(env)
+            .unwrap();
         assert_eq!(
             env.get_from_self(&"x".try_into().unwrap()),
             Some(NamedValue::Function {
                 parameters: vec![],
-                body: Expression::Operation(Operation::new(&"114 + 514".into()).unwrap())
+                output: None,
+                body: Expression::Operation(Operation::new(&"114 + 514".into()).unwrap()),
+                captured: vec![],
             })
         );
     }
@@ -171,13 +506,17 @@ mod tests {
         env.insert_function(
             "add".try_into().unwrap(),
             vec!["x".try_into().unwrap(), "y".try_into().unwrap()],
+            None,
             Expression::Operation(Operation::new(&"x + y".into()).unwrap()),
+            vec![],
         );
         assert_eq!(
-            env.bindings.get(&"add".try_into().unwrap()),
+            env.bindings.get_mut().get(&"add".try_into().unwrap()),
             Some(&NamedValue::Function {
                 parameters: vec!["x".try_into().unwrap(), "y".try_into().unwrap()],
-                body: Expression::Operation(Operation::new(&"x + y".into()).unwrap())
+                output: None,
+                body: Expression::Operation(Operation::new(&"x + y".into()).unwrap()),
+                captured: vec![],
             })
         );
     }
@@ -25,6 +25,42 @@ pub enum Error {
     BindingDef(#[from] BindingDefError),
     #[error(transparent)]
     Block(#[from] BlockError),
+    #[error(transparent)]
+    StringLiteral(#[from] StringLiteralError),
+    #[error(transparent)]
+    FormatString(#[from] FormatStringError),
+    #[error(transparent)]
+    Value(#[from] ValueError),
+    #[error(transparent)]
+    List(#[from] ListError),
+    #[error(transparent)]
+    Index(#[from] IndexError),
+    #[error(transparent)]
+    Tuple(#[from] TupleError),
+    #[error(transparent)]
+    Pattern(#[from] PatternError),
+    #[error(transparent)]
+    Record(#[from] RecordError),
+    #[error(transparent)]
+    FieldAccess(#[from] FieldAccessError),
+    #[error(transparent)]
+    EnumDef(#[from] EnumDefError),
+    #[error(transparent)]
+    Match(#[from] MatchError),
+    #[error(transparent)]
+    Type(#[from] TypeError),
+    #[error(transparent)]
+    Annotation(#[from] AnnotationError),
+    #[error(transparent)]
+    Resolve(#[from] ResolveError),
+    #[error(transparent)]
+    Assignment(#[from] AssignmentError),
+    #[error(transparent)]
+    Loop(#[from] LoopError),
+    #[error(transparent)]
+    ControlFlow(#[from] ControlFlowError),
+    #[error(transparent)]
+    Session(#[from] SessionError),
 }
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum BindingDefError {
@@ -32,6 +68,8 @@ pub enum BindingDefError {
     MissingLetKeyword,
     #[error("Expect `=` here")]
     MissingEqualsSign,
+    #[error("Expect a pattern which matches any value, found a literal")]
+    LiteralPattern,
     // #[error("Invalid binding definition")]
     // InvalidBindingDef
 }
@@ -48,6 +86,8 @@ pub enum IdentifierError {
     ContainSpecialCharacters,
     #[error("Identifier must not be empty")]
     Empty,
+    #[error("`{0}` is a keyword, which can be used as an identifier like `r#{0}`")]
+    ReservedKeyword(String),
 }
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum ExpressionError {
@@ -69,6 +109,16 @@ pub enum OperationError {
     InvalidLhs,
     #[error("Expect a number in the right-hand side")]
     InvalidRhs,
+    #[error("Expect a number as the operand")]
+    InvalidOperand,
+    #[error("Cannot divide by zero")]
+    DivisionByZero,
+    #[error("The result overflows a 32-bit integer")]
+    Overflow,
+    #[error("Cannot shift by {0} bits")]
+    InvalidShift(i32),
+    #[error("Expect a string in the right-hand side to concatenate")]
+    InvalidConcat,
 }
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum NumberError {
@@ -104,4 +154,217 @@ pub enum FunctionCallError {
     WrongParameterCount { expected: usize, got: usize },
     #[error("Expect a function call here")]
     Empty,
+    #[error("Stop calling `{function}`, which is nested more than {depth} calls deep")]
+    TooDeep { function: String, depth: usize },
+}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum StringLiteralError {
+    #[error("Missing opening quote `\"`")]
+    MissingOpeningQuote,
+    #[error("Missing closing quote `\"`")]
+    MissingClosingQuote,
+    #[error("Invalid escape sequence `\\{0}`")]
+    InvalidEscape(char),
+    #[error("Invalid unicode escape sequence, expect `\\u{{...}}` with a valid code point")]
+    InvalidUnicodeEscape,
+}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum FormatStringError {
+    #[error("Expect `f\"` here")]
+    MissingPrefix,
+    #[error("Missing closing brace `}}` of the placeholder")]
+    MissingClosingBrace,
+    #[error("Unmatched closing brace `}}`, use `}}}}` for a literal one")]
+    UnmatchedClosingBrace,
+    #[error("Expect an expression in the placeholder")]
+    EmptyPlaceholder,
+    #[error("Invalid format spec `{0}`")]
+    InvalidSpec(String),
+    #[error("Escape the quotes of a string in a placeholder, like `f\"{{upper \\\"a\\\"}}\"`")]
+    UnescapedQuote,
+}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ListError {
+    #[error("Missing opening bracket `[`")]
+    MissingOpeningBracket,
+    #[error("Missing closing bracket `]`")]
+    MissingClosingBracket,
+    #[error("Expect an element between `,`")]
+    EmptyElement,
+}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum IndexError {
+    #[error("Expect `[...]` after the indexed expression")]
+    NotFound,
+    #[error("Expect an index in `[]`")]
+    EmptyIndex,
+}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TupleError {
+    #[error("Expect `(...)` with `,` between elements")]
+    NotFound,
+}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum PatternError {
+    #[error("Invalid pattern")]
+    InvalidPattern,
+    #[error("Value `{0}` does not match the pattern")]
+    NoMatch(String),
+    #[error("Variant `{0}` is not found")]
+    UnknownConstructor(String),
+    #[error("Expect {expected} fields in the variant pattern, found {found}")]
+    WrongFieldCount { expected: usize, found: usize },
+    #[error("Expect a tuple of {expected} elements, found a tuple of {found} elements")]
+    TupleLengthMismatch { expected: usize, found: usize },
+}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RecordError {
+    #[error("Expect `{{...}}` with `name: value` between `,`")]
+    NotFound,
+    #[error("Expect `..` only before the first field")]
+    MisplacedBase,
+    #[error("Field `{0}` is defined more than once")]
+    DuplicateField(String),
+    #[error("Expect a value for field `{0}`")]
+    MissingValue(String),
+    #[error("Field `{0}` is not found")]
+    FieldNotFound(String),
+}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum FieldAccessError {
+    #[error("Expect `.` before the field name")]
+    NotFound,
+}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum EnumDefError {
+    #[error("Expect `enum` here")]
+    MissingEnumKeyword,
+    #[error("Missing opening brace `{{` before the variants")]
+    MissingOpeningBrace,
+    #[error("Missing closing brace `}}` after the variants")]
+    MissingClosingBrace,
+    #[error("Expect `Name` or `Name(fields)` as a variant, found `{0}`")]
+    InvalidVariant(String),
+    #[error("Expect at least one variant")]
+    NoVariant,
+    #[error("Variant `{0}` is defined more than once")]
+    DuplicateVariant(String),
+}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum MatchError {
+    #[error("Expect `match` here")]
+    MissingMatchKeyword,
+    #[error("Expect an expression to match")]
+    MissingScrutinee,
+    #[error("Expect `{{...}}` with arms between `,`")]
+    MissingArms,
+    #[error("Expect `=>` after the pattern")]
+    MissingArrow,
+    #[error("Patterns not covered: {0}")]
+    NonExhaustive(String),
+}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ValueError {
+    #[error("Expect a value of type `{expected}`, found `{found}`")]
+    TypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    #[error("Index {index} is out of range for length {len}")]
+    IndexOutOfRange { index: i32, len: usize },
+    #[error("Slice starts at {start}, after its end at {end}")]
+    ReversedSlice { start: usize, end: usize },
+    #[error("Range of {len} elements is longer than the limit of {max}")]
+    RangeTooLong { len: usize, max: usize },
+}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TypeError {
+    #[error("Expect a value of type `{expected}`, found `{found}`")]
+    Mismatch { expected: String, found: String },
+    #[error("Expect {expected} arguments, found {found}")]
+    ArityMismatch { expected: usize, found: usize },
+    #[error("Cannot construct an infinite type")]
+    InfiniteType,
+    #[error("Identifier `{0}` is not found")]
+    UnknownIdentifier(String),
+    #[error("Field `{0}` is not found")]
+    FieldNotFound(String),
+    #[error("Cannot infer the type of the record with field `{0}`, so annotate it")]
+    UnknownRecord(String),
+    #[error("Expect a string or a list to get the length of, found `{0}`")]
+    NotMeasurable(String),
+}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum AnnotationError {
+    #[error("Invalid type `{0}`")]
+    InvalidType(String),
+    #[error("Expect `{name}` of type `{expected}`, found `{found}`")]
+    TypeMismatch {
+        name: String,
+        expected: String,
+        found: String,
+    },
+    #[error("Expect function `{function}` to return `{expected}`, found `{found}`")]
+    OutputTypeMismatch {
+        function: String,
+        expected: String,
+        found: String,
+    },
+}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ResolveError {
+    #[error("Identifier `{0}` is not defined")]
+    UnknownIdentifier(String),
+    #[error("Parameter `{0}` is defined more than once")]
+    DuplicateParameter(String),
+}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum AssignmentError {
+    #[error("Expect `=` here")]
+    NotFound,
+    #[error("Expect a value after `=`")]
+    MissingValue,
+    #[error("Cannot assign to `{0}`, which is not defined")]
+    Undefined(String),
+    #[error("Cannot assign twice to `{0}`, which is not declared with `let mut`")]
+    Immutable(String),
+}
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum LoopError {
+    #[error("Expect `while` or `for` here")]
+    NotFound,
+    #[error("Expect a condition after `while`")]
+    MissingCondition,
+    #[error("Expect `in` after the pattern of `for`")]
+    MissingIn,
+    #[error("Expect a list to iterate after `in`")]
+    MissingIterable,
+    #[error("Expect a block as the body of the loop")]
+    MissingBody,
+    #[error("Stop the loop, which runs out of the step budget")]
+    StepBudgetExceeded,
+}
+/// `Break`, `Continue` and `Return` unwind to the nearest loop or function, which handles them.
+///
+/// They are errors only if nothing handles them.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ControlFlowError {
+    #[error("Expect `break` or `continue` here")]
+    NotFound,
+    #[error("`break` outside of a loop")]
+    Break(Box<crate::internal::prelude::Value>),
+    #[error("`continue` outside of a loop")]
+    Continue,
+    #[error("`{0}` outside of a loop")]
+    OutsideLoop(String),
+    #[error("`return` outside of a function")]
+    Return(Box<crate::internal::prelude::Value>),
+}
+/// Errors of writing the names bound in an `Environment` back as source.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SessionError {
+    #[error("Cannot save `{0}`, which uses a name defined after it")]
+    Unordered(String),
+    #[error("Cannot save `{0}`, whose value cannot be written as source")]
+    Unrestorable(String),
 }
@@ -2,9 +2,21 @@ use crate::internal::prelude::*;
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
 pub enum Expression {
     Number(Number),
+    Str(StringLiteral),
+    FormatString(FormatString),
+    List(List),
+    Tuple(Tuple),
+    Record(Record),
+    Index(Index),
+    FieldAccess(FieldAccess),
     Operation(Operation),
+    UnaryOperation(UnaryOperation),
     Binding(Identifier),
     Block(Block),
+    Match(MatchExpression),
+    While(WhileLoop),
+    For(ForLoop),
+    ControlFlow(ControlFlow),
     FunctionCall(FunctionCall),
     #[default]
     Empty,
@@ -13,16 +25,90 @@ impl Expression {
     pub const fn is_empty(&self) -> bool {
         matches!(self, Self::Empty)
     }
-    pub fn new(s: &TrimmedStr) -> Result<Self, ExpressionError> {
+    /// Whether the expression is written as a single term, like an argument of a function call,
+    /// where `f -1` would be a subtraction.
+    const fn is_term(&self) -> bool {
+        match self {
+            Self::Number(number) => number.inner() >= 0,
+            Self::Operation(_)
+            | Self::UnaryOperation(_)
+            | Self::Match(_)
+            | Self::While(_)
+            | Self::For(_)
+            | Self::ControlFlow(_)
+            | Self::FunctionCall(_) => false,
+            _ => true,
+        }
+    }
+    /// Write the expression as a term, in parentheses unless it is one.
+    pub fn fmt_term(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_term() {
+            std::fmt::Display::fmt(self, f)
+        } else {
+            write!(f, "(")?;
+            std::fmt::Display::fmt(self, f)?;
+            write!(f, ")")
+        }
+    }
+    /// Write the expression as an operand of an `Operation`, which may be a call like `f x`.
+    pub fn fmt_operand(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(_) | Self::UnaryOperation(_) | Self::FunctionCall(_) | Self::Match(_) => {
+                std::fmt::Display::fmt(self, f)
+            }
+            _ => self.fmt_term(f),
+        }
+    }
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        // these start with a keyword, and `break -1` is not a subtraction
+        if let Ok(control_flow) = ControlFlow::new(s) {
+            return Ok(Self::ControlFlow(control_flow));
+        }
+        if let Ok(while_loop) = WhileLoop::new(s) {
+            return Ok(Self::While(while_loop));
+        }
+        if let Ok(for_loop) = ForLoop::new(s) {
+            return Ok(Self::For(for_loop));
+        }
         if let Ok(op) = Operation::new(s) {
             return Ok(Self::Operation(op));
         }
+        if let Ok(tuple) = Tuple::new(s) {
+            return Ok(Self::Tuple(tuple));
+        }
+        if let Some(inner) = s.strip_wrapping('(', ')') {
+            return Self::new(&inner);
+        }
+        if let Ok(s) = StringLiteral::new(s) {
+            return Ok(Self::Str(s));
+        }
+        if let Ok(s) = FormatString::new(s) {
+            return Ok(Self::FormatString(s));
+        }
         if let Ok(number) = Number::new(s) {
             return Ok(Self::Number(number));
         }
+        if let Ok(op) = UnaryOperation::new(s) {
+            return Ok(Self::UnaryOperation(op));
+        }
+        if let Ok(list) = List::new(s) {
+            return Ok(Self::List(list));
+        }
+        if let Ok(index) = Index::new(s) {
+            return Ok(Self::Index(index));
+        }
+        if let Ok(access) = FieldAccess::new(s) {
+            return Ok(Self::FieldAccess(access));
+        }
         if let Ok(binding) = Identifier::new(s) {
             return Ok(Self::Binding(binding));
         }
+        if let Ok(match_expression) = MatchExpression::new(s) {
+            return Ok(Self::Match(match_expression));
+        }
+        if let Ok(record) = Record::new(s) {
+            return Ok(Self::Record(record));
+        }
         if let Ok(block) = Block::new(s) {
             return Ok(Self::Block(block));
         }
@@ -32,30 +118,179 @@ impl Expression {
         if s.is_empty() {
             return Ok(Self::Empty);
         }
-        Err(ExpressionError::InvalidExpression)
+        Err(Self::literal_error(s).unwrap_or(ExpressionError::InvalidExpression.into()))
+    }
+    /// The error of the literal which `s` is written as, like `[1,,2]` which is a list with an
+    /// empty element, rather than just an invalid expression.
+    fn literal_error(s: &TrimmedStr) -> Option<Error> {
+        if s.starts_with('"') {
+            return StringLiteral::new(s).err().map(Into::into);
+        }
+        if s.starts_with("f\"") {
+            return FormatString::new(s).err();
+        }
+        if s.strip_wrapping('[', ']').is_some() {
+            return List::new(s).err();
+        }
+        // `{ x }` is a block, so only the errors of a record with fields are reported
+        Record::new(s)
+            .err()
+            .filter(|err| *err != RecordError::NotFound.into())
+    }
+    /// Check the names used are defined, see `Resolver`.
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        match self {
+            Self::Number(_) | Self::Str(_) | Self::Empty => Ok(()),
+            Self::FormatString(s) => s.resolve(resolver),
+            Self::List(list) => list.resolve(resolver),
+            Self::Tuple(tuple) => tuple.resolve(resolver),
+            Self::Record(record) => record.resolve(resolver),
+            Self::Index(index) => index.resolve(resolver),
+            Self::FieldAccess(access) => access.resolve(resolver),
+            Self::Operation(operation) => operation.resolve(resolver),
+            Self::UnaryOperation(operation) => operation.resolve(resolver),
+            Self::Binding(binding) => Ok(resolver.lookup(binding)?),
+            Self::FunctionCall(function_call) => function_call.resolve(resolver),
+            Self::Match(match_expression) => match_expression.resolve(resolver),
+            Self::While(while_loop) => while_loop.resolve(resolver),
+            Self::For(for_loop) => for_loop.resolve(resolver),
+            Self::ControlFlow(control_flow) => control_flow.resolve(resolver),
+            Self::Block(block) => resolver.scoped(|resolver| block.resolve(resolver)),
+        }
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        match self {
+            Self::Number(_) | Self::Str(_) | Self::Empty => (),
+            Self::FormatString(s) => s.lint(linter),
+            Self::List(list) => list.lint(linter),
+            Self::Tuple(tuple) => tuple.lint(linter),
+            Self::Record(record) => record.lint(linter),
+            Self::Index(index) => index.lint(linter),
+            Self::FieldAccess(access) => access.lint(linter),
+            Self::Operation(operation) => operation.lint(linter),
+            Self::UnaryOperation(operation) => operation.lint(linter),
+            Self::Binding(binding) => linter.lookup(binding),
+            Self::FunctionCall(function_call) => function_call.lint(linter),
+            Self::Match(match_expression) => match_expression.lint(linter),
+            Self::While(while_loop) => while_loop.lint(linter),
+            Self::For(for_loop) => for_loop.lint(linter),
+            Self::ControlFlow(control_flow) => control_flow.lint(linter),
+            Self::Block(block) => linter.scoped(|linter| block.lint(linter)),
+        }
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<Type, Error> {
+        match self {
+            Self::Number(_) => Ok(Type::Int),
+            Self::Str(_) => Ok(Type::Str),
+            Self::FormatString(s) => s.infer(checker),
+            Self::List(list) => list.infer(checker),
+            Self::Tuple(tuple) => tuple.infer(checker),
+            Self::Record(record) => record.infer(checker),
+            Self::Index(index) => index.infer(checker),
+            Self::FieldAccess(access) => access.infer(checker),
+            Self::Operation(operation) => operation.infer(checker),
+            Self::UnaryOperation(operation) => operation.infer(checker),
+            Self::Empty => Ok(Type::Empty),
+            Self::Binding(binding) => Ok(checker.lookup(binding)?),
+            Self::FunctionCall(function_call) => function_call.infer(checker),
+            Self::Match(match_expression) => match_expression.infer(checker),
+            Self::While(while_loop) => while_loop.infer(checker),
+            Self::For(for_loop) => for_loop.infer(checker),
+            Self::ControlFlow(control_flow) => control_flow.infer(checker),
+            Self::Block(block) => checker.scoped(|checker| block.infer(checker)),
+        }
     }
     pub fn eval(&self, env: &Environment) -> Result<Value, Error> {
         match self {
             Self::Number(number) => Ok(Value::Number(*number)),
+            Self::Str(s) => Ok(Value::Str(s.inner().to_string())),
+            Self::FormatString(s) => s.eval(env),
+            Self::List(list) => list.eval(env),
+            Self::Tuple(tuple) => tuple.eval(env),
+            Self::Record(record) => record.eval(env),
+            Self::Index(index) => index.eval(env),
+            Self::FieldAccess(access) => access.eval(env),
             Self::Operation(operation) => operation.eval(env),
+            Self::UnaryOperation(operation) => operation.eval(env),
             Self::Empty => Ok(Value::Empty),
-            Self::Binding(binding) => binding.try_get_expression_from(env)?.eval(env),
-            Self::FunctionCall(function_call) => {
-                let local = &mut env.create_child();
-                function_call.try_get_expression_from(local)?.eval(local)
-            }
-            Self::Block(block) => {
-                let local = &mut env.create_child();
-                block.get_expression_from(local).eval(local)
-            }
+            Self::Binding(binding) => Ok(binding.try_get_value_from(env)?),
+            Self::FunctionCall(function_call) => function_call.eval(env),
+            Self::Match(match_expression) => match_expression.eval(env),
+            Self::While(while_loop) => while_loop.eval(env),
+            Self::For(for_loop) => for_loop.eval(env),
+            Self::ControlFlow(control_flow) => control_flow.eval(env),
+            Self::Block(block) => block.eval(env),
         }
     }
 }
 
+impl std::fmt::Display for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the children are written through `fmt` to keep the alternate flag
+        match self {
+            Self::Number(number) => number.fmt(f),
+            Self::Str(s) => s.fmt(f),
+            Self::FormatString(s) => s.fmt(f),
+            Self::List(list) => list.fmt(f),
+            Self::Tuple(tuple) => tuple.fmt(f),
+            Self::Record(record) => record.fmt(f),
+            Self::Index(index) => index.fmt(f),
+            Self::FieldAccess(access) => access.fmt(f),
+            Self::Operation(operation) => operation.fmt(f),
+            Self::UnaryOperation(operation) => operation.fmt(f),
+            Self::Empty => Ok(()),
+            Self::Binding(binding) => write!(f, "{binding}"),
+            Self::FunctionCall(function_call) => function_call.fmt(f),
+            Self::Match(match_expression) => match_expression.fmt(f),
+            Self::While(while_loop) => while_loop.fmt(f),
+            Self::For(for_loop) => for_loop.fmt(f),
+            Self::ControlFlow(control_flow) => control_flow.fmt(f),
+            Self::Block(block) => block.fmt(f),
+        }
+    }
+}
 #[cfg(test)]
 mod tests {
     use super::*;
     #[test]
+    fn display_as_source() {
+        let display = |s: &str| Expression::new(&s.into()).unwrap().to_string();
+        assert_eq!(display("(1+2)*3"), "(1 + 2) * 3");
+        assert_eq!(display("1 - (2 - 3)"), "1 - (2 - 3)");
+        assert_eq!(display("(1 - 2) - 3"), "1 - 2 - 3");
+        assert_eq!(display("f (g x) (-1)"), "f (g x) (-1)");
+        assert_eq!(display("(-x)[0]"), "(-x)[0]");
+        assert_eq!(display("{ let a = 1;a }"), "{ let a = 1; a }");
+        assert_eq!(
+            display("{a:1,..r}".replace("a:1,..r", "..r,a:1").as_str()),
+            "{ ..r, a: 1 }"
+        );
+    }
+    #[test]
+    fn display_round_trip() {
+        for s in [
+            "1 + 2 * 3 - -4",
+            "~(1 | 2) ^ 3 & 4 << 5 == 6 != 7",
+            "f (g x) [1, 2] (1,) { a: 1 } -2",
+            r#""a\"b\n\u{7}" + f"{x:>8} {{}} {upper \"é\"}\t""#,
+            "xs[i + 1][..2].name",
+            "{ ..r, a: (1, \"x\"), b: [] }",
+            "match f x { Some(n) if n != 0 => n, (a, _) => { a }, _ => 0 }",
+            "{ fn f (x: Int) -> Int => x; enum E { A(x), B }; let mut y = f 1; y += 1; y; }",
+            "{ while i != 0 { i -= 1; if_x; continue; }; for (a, b) in pairs { break a } }",
+            "{ fn r#match r#in => r#in }",
+            "(break 1) + (while x { 1 })",
+            "-0x8000_0000",
+        ] {
+            let expression = Expression::new(&s.into()).expect(s);
+            assert_eq!(
+                Expression::new(&expression.to_string().as_str().into()),
+                Ok(expression),
+                "{s}"
+            );
+        }
+    }
+    #[test]
     fn parse_operation_without_binding() {
         assert_eq!(
             Expression::new(&"1+2".into()),
@@ -74,6 +309,32 @@ mod tests {
         );
     }
     #[test]
+    fn parse_unary_operation() {
+        assert_eq!(
+            Expression::new(&"~x".into()),
+            Ok(Expression::UnaryOperation(
+                UnaryOperation::new(&"~x".into()).unwrap()
+            ))
+        );
+    }
+    #[test]
+    fn parse_parentheses() {
+        assert_eq!(
+            Expression::new(&"((114))".into()),
+            Ok(Expression::Number(Number::from_i32(114)))
+        );
+        assert_eq!(
+            Expression::new(&"(1 + 2) * (3 + 4)".into()),
+            Ok(Expression::Operation(
+                Operation::new(&"(1 + 2) * (3 + 4)".into()).unwrap()
+            ))
+        );
+        assert_eq!(
+            Expression::new(&"(1 + 2".into()),
+            Err(Error::Expression(ExpressionError::InvalidExpression))
+        );
+    }
+    #[test]
     fn parse_number() {
         assert_eq!(
             Expression::new(&"114".into()),
@@ -81,6 +342,77 @@ mod tests {
         );
     }
     #[test]
+    fn parse_str() {
+        assert_eq!(
+            Expression::new(&r#""1 + 1""#.into()),
+            Ok(Expression::Str(
+                StringLiteral::new(&r#""1 + 1""#.into()).unwrap()
+            ))
+        );
+    }
+    #[test]
+    fn parse_format_string() {
+        assert_eq!(
+            Expression::new(&r#"f"{x}""#.into()),
+            Ok(Expression::FormatString(
+                FormatString::new(&r#"f"{x}""#.into()).unwrap()
+            ))
+        );
+    }
+    #[test]
+    fn parse_tuple() {
+        assert_eq!(
+            Expression::new(&"(1, 2)".into()),
+            Ok(Expression::Tuple(Tuple::new(&"(1, 2)".into()).unwrap()))
+        );
+        assert_eq!(
+            Expression::new(&"(1,)".into()),
+            Ok(Expression::Tuple(Tuple::new(&"(1,)".into()).unwrap()))
+        );
+    }
+    #[test]
+    fn parse_record_and_field_access() {
+        assert_eq!(
+            Expression::new(&"{ width: 10 }".into()),
+            Ok(Expression::Record(
+                Record::new(&"{ width: 10 }".into()).unwrap()
+            ))
+        );
+        assert_eq!(
+            Expression::new(&"{ width }".into()),
+            Ok(Expression::Block(Block::new(&"{ width }".into()).unwrap()))
+        );
+        assert_eq!(
+            Expression::new(&"r.width".into()),
+            Ok(Expression::FieldAccess(
+                FieldAccess::new(&"r.width".into()).unwrap()
+            ))
+        );
+    }
+    #[test]
+    fn parse_list_and_index() {
+        assert_eq!(
+            Expression::new(&"[1, 2]".into()),
+            Ok(Expression::List(List::new(&"[1, 2]".into()).unwrap()))
+        );
+        assert_eq!(
+            Expression::new(&"xs[0]".into()),
+            Ok(Expression::Index(Index::new(&"xs[0]".into()).unwrap()))
+        );
+        assert_eq!(
+            Expression::new(&"-xs[0]".into()),
+            Ok(Expression::UnaryOperation(
+                UnaryOperation::new(&"-xs[0]".into()).unwrap()
+            ))
+        );
+        assert_eq!(
+            Expression::new(&"len xs[0]".into()),
+            Ok(Expression::FunctionCall(
+                FunctionCall::new(&"len xs[0]".into()).unwrap()
+            ))
+        );
+    }
+    #[test]
     fn parse_empty() {
         assert_eq!(Expression::new(&"".into()), Ok(Expression::Empty));
     }
@@ -122,11 +454,32 @@ mod tests {
     fn parse_invalid_expr() {
         assert_eq!(
             Expression::new(&"++".into()),
-            Err(ExpressionError::InvalidExpression)
+            Err(Error::Expression(ExpressionError::InvalidExpression))
         );
         assert_eq!(
             Expression::new(&"1+".into()),
-            Err(ExpressionError::InvalidExpression)
+            Err(Error::Expression(ExpressionError::InvalidExpression))
+        );
+    }
+    #[test]
+    fn parse_invalid_literal() {
+        assert_eq!(
+            Expression::new(&r#""unterminated"#.into()),
+            Err(Error::StringLiteral(
+                StringLiteralError::MissingClosingQuote
+            ))
+        );
+        assert_eq!(
+            Expression::new(&r#"f"{x""#.into()),
+            Err(Error::FormatString(FormatStringError::MissingClosingBrace))
+        );
+        assert_eq!(
+            Expression::new(&"[1, [1,,2]]".into()),
+            Err(Error::List(ListError::EmptyElement))
+        );
+        assert_eq!(
+            Expression::new(&"{ a: 1, a: 2 }".into()),
+            Err(Error::Record(RecordError::DuplicateField("a".to_string())))
         );
     }
     #[test]
@@ -140,7 +493,10 @@ mod tests {
     #[test]
     fn eval_operation_with_binding() {
         let env = &mut Environment::default();
-        BindingDef::new(&"let x = 114".into()).unwrap().store(env);
+        BindingDef::new(&"let x = 114".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
         assert_eq!(
             Expression::Operation(Operation::new(&"x+2".into()).unwrap()).eval(env),
             Ok(Value::Number(Number::from_i32(114 + 2)))
@@ -154,6 +510,15 @@ mod tests {
         );
     }
     #[test]
+    fn eval_str() {
+        assert_eq!(
+            Expression::new(&r#""a\tb""#.into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Ok(Value::Str("a\tb".to_string()))
+        );
+    }
+    #[test]
     fn eval_empty() {
         assert_eq!(
             Expression::Empty.eval(&Environment::default()),
@@ -163,7 +528,10 @@ mod tests {
     #[test]
     fn eval_existing_binding() {
         let env = &mut Environment::default();
-        BindingDef::new(&"let a = 114".into()).unwrap().store(env);
+        BindingDef::new(&"let a = 114".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
         assert_eq!(
             Expression::Binding(Identifier::new(&"a".into()).unwrap()).eval(env),
             Ok(Value::Number(Number::from_i32(114)))
@@ -198,7 +566,8 @@ mod tests {
             let mut binding = Environment::default();
             BindingDef::new(&"let a = 11451".into())
                 .unwrap()
-                .store(&mut binding);
+                .store(&mut binding)
+                .unwrap();
             binding
         };
         assert_eq!(
@@ -212,7 +581,8 @@ mod tests {
         let env = &mut Environment::default();
         FunctionDef::new(&"fn homo_number => 114+514".into())
             .unwrap()
-            .store(env);
+            .store(env)
+            .unwrap();
         // TODO: fix it because now expression will parse it as a binding
         assert_eq!(
             Expression::new(&"homo_number".into()).unwrap().eval(env),
@@ -224,12 +594,76 @@ mod tests {
         let env = &mut Environment::default();
         FunctionDef::new(&"fn add x y=> x + y".into())
             .unwrap()
-            .store(env);
-        BindingDef::new(&"let a = 114".into()).unwrap().store(env);
-        BindingDef::new(&"let b = 514".into()).unwrap().store(env);
+            .store(env)
+            .unwrap();
+        BindingDef::new(&"let a = 114".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        BindingDef::new(&"let b = 514".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
         assert_eq!(
             Expression::new(&"add a b".into()).unwrap().eval(env),
             Ok(Value::Number(Number::from_i32(114 + 514)))
         );
     }
+    #[test]
+    fn eval_builtin_function_call() {
+        let env = &mut Environment::default();
+        BindingDef::new(&r#"let s = "Hello""#.into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        assert_eq!(
+            Expression::new(&r#"upper (s + ", world")"#.into())
+                .unwrap()
+                .eval(env),
+            Ok(Value::Str("HELLO, WORLD".to_string()))
+        );
+        assert_eq!(
+            Expression::new(&"len s + 1".into()).unwrap().eval(env),
+            Ok(Value::Number(Number::from_i32(6)))
+        );
+    }
+    #[test]
+    fn eval_list_built_ins() {
+        let env = &mut Environment::default();
+        FunctionDef::new(&"fn square x => x * x".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        FunctionDef::new(&"fn add x y => x + y".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        assert_eq!(
+            Expression::new(&"fold add 0 (map square (range 1 4))".into())
+                .unwrap()
+                .eval(env),
+            Ok(Value::Number(Number::from_i32(1 + 4 + 9)))
+        );
+        assert_eq!(
+            Expression::new(&"(push [1, 2] 3)[1..]".into())
+                .unwrap()
+                .eval(env),
+            Ok(Value::List(vec![
+                Value::Number(Number::from_i32(2)),
+                Value::Number(Number::from_i32(3))
+            ]))
+        );
+    }
+    #[test]
+    fn eval_shadowed_builtin_function_call() {
+        let env = &mut Environment::default();
+        FunctionDef::new(&"fn len x => 0".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        assert_eq!(
+            Expression::new(&r#"len "abc""#.into()).unwrap().eval(env),
+            Ok(Value::Number(Number::from_i32(0)))
+        );
+    }
 }
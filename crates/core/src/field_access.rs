@@ -0,0 +1,123 @@
+use crate::internal::prelude::*;
+
+/// Access to a field of a record like `r.width`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FieldAccess {
+    target: Box<Expression>,
+    field: Identifier,
+}
+impl FieldAccess {
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        let Some((nth, _)) = TopLevelChars::new(s).filter(|&(_, c)| c == '.').last() else {
+            return Err(FieldAccessError::NotFound)?;
+        };
+        let (target, field) = (&s[..nth], &s[nth + '.'.len_utf8()..]);
+        // `f r.width` is a function call whose argument is a field, like `f xs[1]`
+        if target.is_empty() || TopLevelChars::new(target).any(|(_, c)| c.is_whitespace()) {
+            return Err(FieldAccessError::NotFound)?;
+        }
+        Ok(Self {
+            target: Expression::new(&target.into())?.into(),
+            field: Identifier::new(&field.into())?,
+        })
+    }
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        self.target.resolve(resolver)
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        self.target.lint(linter);
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<Type, Error> {
+        let target = self.target.infer(checker)?;
+        match checker.resolve(&target) {
+            Type::Record(mut record) => Ok(record
+                .remove(self.field.as_str())
+                .ok_or_else(|| TypeError::FieldNotFound(self.field.to_string()))?),
+            // the other fields are unknown, so no record type can be inferred from one of them
+            Type::Var(_) => Err(TypeError::UnknownRecord(self.field.to_string()))?,
+            found => Err(TypeError::Mismatch {
+                expected: "Record".to_string(),
+                found: found.normalize().to_string(),
+            })?,
+        }
+    }
+    pub fn eval(&self, env: &Environment) -> Result<Value, Error> {
+        let mut record = self.target.eval(env)?.try_into_record()?;
+        record
+            .remove(self.field.as_str())
+            .ok_or_else(|| RecordError::FieldNotFound(self.field.to_string()).into())
+    }
+}
+impl std::fmt::Display for FieldAccess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.target.fmt_term(f)?;
+        write!(f, ".{}", self.field)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parse_field_access() {
+        assert_eq!(
+            FieldAccess::new(&"r.size.width".into()),
+            Ok(FieldAccess {
+                target: Expression::FieldAccess(FieldAccess::new(&"r.size".into()).unwrap()).into(),
+                field: "width".try_into().unwrap()
+            })
+        );
+        assert_eq!(
+            FieldAccess::new(&"rs[0].width".into()),
+            Ok(FieldAccess {
+                target: Expression::Index(Index::new(&"rs[0]".into()).unwrap()).into(),
+                field: "width".try_into().unwrap()
+            })
+        );
+    }
+    #[test]
+    fn parse_non_field_access() {
+        assert_eq!(
+            FieldAccess::new(&"r".into()),
+            Err(Error::FieldAccess(FieldAccessError::NotFound))
+        );
+        assert_eq!(
+            FieldAccess::new(&"f r.width".into()),
+            Err(Error::FieldAccess(FieldAccessError::NotFound))
+        );
+        assert_eq!(
+            FieldAccess::new(&r#""a.b""#.into()),
+            Err(Error::FieldAccess(FieldAccessError::NotFound))
+        );
+        assert_eq!(
+            FieldAccess::new(&"r.1".into()),
+            Err(Error::Identifier(IdentifierError::StartWithNonLetter))
+        );
+    }
+    #[test]
+    fn eval() {
+        let env = &mut Environment::default();
+        BindingDef::new(&"let r = { size: { width: 10 } }".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        assert_eq!(
+            FieldAccess::new(&"r.size.width".into()).unwrap().eval(env),
+            Ok(Value::Number(Number::from_i32(10)))
+        );
+        assert_eq!(
+            FieldAccess::new(&"r.width".into()).unwrap().eval(env),
+            Err(Error::Record(RecordError::FieldNotFound(
+                "width".to_string()
+            )))
+        );
+        assert_eq!(
+            FieldAccess::new(&"r.size.width.value".into())
+                .unwrap()
+                .eval(env),
+            Err(Error::Value(ValueError::TypeMismatch {
+                expected: "Record",
+                found: "Int"
+            }))
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use crate::internal::prelude::*;
+
+/// A loop like `for i in range 0 10 { ... }`, which binds each element of a list in turn.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ForLoop {
+    pattern: Box<Pattern>,
+    iterable: Box<Expression>,
+    body: Block,
+}
+impl ForLoop {
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        let s = Keyword::For.strip(s).ok_or(LoopError::NotFound)?;
+        // the pattern ends before the first top-level `in`
+        let (nth, iterable) = TopLevelChars::new(s)
+            .filter(|&(nth, _)| s[..nth].ends_with(char::is_whitespace))
+            .find_map(|(nth, _)| Some((nth, Keyword::In.strip(&s[nth..])?)))
+            .ok_or(LoopError::MissingIn)?;
+        let (iterable, body) = Block::split_trailing(iterable).ok_or(LoopError::MissingBody)?;
+        let iterable = Expression::new(&iterable.into())?;
+        if iterable.is_empty() {
+            return Err(LoopError::MissingIterable)?;
+        }
+        Ok(Self {
+            pattern: Pattern::new(&s[..nth].into())?.into(),
+            iterable: iterable.into(),
+            body,
+        })
+    }
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        self.iterable.resolve(resolver)?;
+        resolver.scoped(|resolver| {
+            self.pattern.resolve(resolver)?;
+            self.body.resolve(resolver)
+        })
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        self.iterable.lint(linter);
+        linter.scoped(|linter| {
+            self.pattern.lint(linter, Rule::UnusedBinding);
+            self.body.lint(linter);
+        });
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<Type, Error> {
+        let element = checker.fresh();
+        let iterable = self.iterable.infer(checker)?;
+        checker.unify(&Type::list(element.clone()), &iterable)?;
+        checker.in_loop(true, |checker| {
+            checker.scoped(|checker| {
+                for (name, ty) in self.pattern.infer(checker, &element)? {
+                    checker.bind(name, ty);
+                }
+                self.body.infer(checker)
+            })
+        })
+    }
+    pub fn eval(&self, env: &Environment) -> Result<Value, Error> {
+        // a range is gone through lazily, so only the step budget limits how long it is
+        if let Expression::FunctionCall(call) = &*self.iterable
+            && let Some(range) = call.eval_range(env)?
+        {
+            let elements = range.map(|value| Value::Number(Number::from_i32(value)));
+            return self.eval_elements(elements, env);
+        }
+        let elements = self.iterable.eval(env)?.try_into_list()?;
+        self.eval_elements(elements, env)
+    }
+    fn eval_elements(
+        &self,
+        elements: impl IntoIterator<Item = Value>,
+        env: &Environment,
+    ) -> Result<Value, Error> {
+        for element in elements {
+            let local = &mut env.create_child();
+            self.pattern.bind(element, local)?;
+            if let Some(value) = ControlFlow::iterate(&self.body, local)? {
+                return Ok(value);
+            }
+        }
+        Ok(Value::Empty)
+    }
+}
+impl std::fmt::Display for ForLoop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {} ", Keyword::For, self.pattern, Keyword::In)?;
+        self.iterable.fmt(f)?;
+        write!(f, " ")?;
+        self.body.fmt(f)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn eval(s: &str, env: &mut Environment) -> Result<Value, Error> {
+        let statement = Statement::new(&s.into())?;
+        statement.get_expression_in(env)?.eval(env)
+    }
+    #[test]
+    fn parse_for_loop() {
+        assert_eq!(
+            ForLoop::new(&"for (i, x) in items { sum += i * x; }".into()),
+            Ok(ForLoop {
+                pattern: Pattern::new(&"(i, x)".into()).unwrap().into(),
+                iterable: Expression::new(&"items".into()).unwrap().into(),
+                body: Block::new(&"{ sum += i * x; }".into()).unwrap()
+            })
+        );
+        assert_eq!(
+            ForLoop::new(&"for index in range 0 3 { }".into()).map(|for_loop| *for_loop.pattern),
+            Ok(Pattern::new(&"index".into()).unwrap())
+        );
+        assert_eq!(
+            ForLoop::new(&"for i range 0 3 { }".into()),
+            Err(Error::Loop(LoopError::MissingIn))
+        );
+        assert_eq!(
+            ForLoop::new(&"for i in { }".into()),
+            Err(Error::Loop(LoopError::MissingIterable))
+        );
+    }
+    #[test]
+    fn eval_for_loop() {
+        let env = &mut Environment::default();
+        eval("let mut sum = 0;", env).unwrap();
+        assert_eq!(
+            eval("for i in range 0 5 { sum += i; }", env),
+            Ok(Value::Empty)
+        );
+        assert_eq!(eval("sum", env), Ok(Value::Number(Number::from_i32(10))));
+        assert_eq!(env.get_from_self_and_parent(&"i".try_into().unwrap()), None);
+    }
+    #[test]
+    fn eval_huge_range() {
+        let env = &mut Environment::default();
+        // the range is longer than a list of it may be, but no list is made
+        assert_eq!(
+            eval("for i in range 5 2000000000 { break i }", env),
+            Ok(Value::Number(Number::from_i32(5)))
+        );
+        env.set_step_budget(Some(100));
+        assert_eq!(
+            eval("for i in range 0 2000000000 { i }", env),
+            Err(Error::Loop(LoopError::StepBudgetExceeded))
+        );
+    }
+    #[test]
+    fn eval_with_break() {
+        let env = &mut Environment::default();
+        assert_eq!(
+            eval(
+                "for (a, b) in [(1, 2), (3, 3)] { match a == b { 1 => break a, _ => 0 } }",
+                env
+            ),
+            Ok(Value::Number(Number::from_i32(3)))
+        );
+        assert_eq!(
+            eval("for x in 1 { }", env),
+            Err(Error::Value(ValueError::TypeMismatch {
+                expected: "List",
+                found: "Int"
+            }))
+        );
+    }
+}
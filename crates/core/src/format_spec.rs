@@ -0,0 +1,284 @@
+use crate::internal::prelude::*;
+
+/// How a placeholder in a `FormatString` is rendered, like `>8`, `08` or `#x` in `{x:#010x}`.
+///
+/// The syntax is `[[fill]align][#][0][width][radix]`, where `align` is one of `<`, `^` and `>`,
+/// and `radix` is one of `x`, `X`, `b` and `o`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct FormatSpec {
+    fill: char,
+    align: Option<Align>,
+    alternate: bool,
+    zero: bool,
+    width: usize,
+    radix: Option<Radix>,
+}
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Radix {
+    Hex,
+    UpperHex,
+    Binary,
+    Octal,
+}
+impl Default for FormatSpec {
+    fn default() -> Self {
+        Self {
+            fill: ' ',
+            align: None,
+            alternate: false,
+            zero: false,
+            width: 0,
+            radix: None,
+        }
+    }
+}
+impl FormatSpec {
+    /// The widest a placeholder can be padded to, so a typo cannot take all the memory.
+    pub const MAX_WIDTH: usize = 1024;
+    pub fn new(s: &str) -> Result<Self, FormatStringError> {
+        let invalid = || FormatStringError::InvalidSpec(s.to_string());
+        let mut spec = Self::default();
+        let mut chars = s.chars();
+        let first = chars.next();
+        let mut rest = s;
+        if let (Some(fill), Some(align)) = (first, chars.next().and_then(Align::new)) {
+            spec.fill = fill;
+            spec.align = Some(align);
+            rest = chars.as_str();
+        } else if let Some(align) = first.and_then(Align::new) {
+            spec.align = Some(align);
+            rest = &s[1..];
+        }
+        if let Some(s) = rest.strip_prefix('#') {
+            spec.alternate = true;
+            rest = s;
+        }
+        if let Some(s) = rest.strip_prefix('0') {
+            spec.zero = true;
+            rest = s;
+        }
+        let (width, radix) = rest.split_at(
+            rest.find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len()),
+        );
+        if !width.is_empty() {
+            spec.width = width
+                .parse()
+                .ok()
+                .filter(|&width| width <= Self::MAX_WIDTH)
+                .ok_or_else(invalid)?;
+        }
+        spec.radix = match radix {
+            "" => None,
+            "x" => Some(Radix::Hex),
+            "X" => Some(Radix::UpperHex),
+            "b" => Some(Radix::Binary),
+            "o" => Some(Radix::Octal),
+            _ => return Err(invalid()),
+        };
+        Ok(spec)
+    }
+    pub fn format(&self, value: &Value) -> Result<String, ValueError> {
+        let (prefix, body) = match (value, self.radix) {
+            (Value::Number(number), Some(radix)) => {
+                let prefix = if self.alternate { radix.prefix() } else { "" };
+                (prefix, radix.format(number.inner()))
+            }
+            (Value::Number(number), None) => {
+                let value = number.inner();
+                let sign = if value < 0 { "-" } else { "" };
+                (sign, value.unsigned_abs().to_string())
+            }
+            (value, Some(_)) => {
+                return Err(ValueError::TypeMismatch {
+                    expected: "Int",
+                    found: value.type_name(),
+                });
+            }
+            (value, None) => ("", value.to_string()),
+        };
+        let is_number = matches!(value, Value::Number(_));
+        let padding = self
+            .width
+            .saturating_sub(prefix.chars().count() + body.chars().count());
+        if self.zero && is_number {
+            return Ok(format!("{prefix}{}{body}", "0".repeat(padding)));
+        }
+        let default_align = if is_number { Align::Right } else { Align::Left };
+        let (left, right) = match self.align.unwrap_or(default_align) {
+            Align::Left => (0, padding),
+            Align::Center => (padding / 2, padding - padding / 2),
+            Align::Right => (padding, 0),
+        };
+        let fill = |count| self.fill.to_string().repeat(count);
+        Ok(format!("{}{prefix}{body}{}", fill(left), fill(right)))
+    }
+}
+impl Align {
+    const fn new(c: char) -> Option<Self> {
+        match c {
+            '<' => Some(Self::Left),
+            '^' => Some(Self::Center),
+            '>' => Some(Self::Right),
+            _ => None,
+        }
+    }
+    const fn symbol(self) -> char {
+        match self {
+            Self::Left => '<',
+            Self::Center => '^',
+            Self::Right => '>',
+        }
+    }
+}
+impl Radix {
+    const fn symbol(self) -> char {
+        match self {
+            Self::Hex => 'x',
+            Self::UpperHex => 'X',
+            Self::Binary => 'b',
+            Self::Octal => 'o',
+        }
+    }
+    const fn prefix(self) -> &'static str {
+        match self {
+            Self::Hex | Self::UpperHex => "0x",
+            Self::Binary => "0b",
+            Self::Octal => "0o",
+        }
+    }
+    /// Format the bit pattern, so negative numbers are in two's complement.
+    fn format(self, value: i32) -> String {
+        let bits = value.cast_unsigned();
+        match self {
+            Self::Hex => format!("{bits:x}"),
+            Self::UpperHex => format!("{bits:X}"),
+            Self::Binary => format!("{bits:b}"),
+            Self::Octal => format!("{bits:o}"),
+        }
+    }
+}
+impl std::fmt::Display for FormatSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(align) = self.align {
+            if self.fill != ' ' {
+                write!(f, "{}", self.fill)?;
+            }
+            write!(f, "{}", align.symbol())?;
+        }
+        if self.alternate {
+            write!(f, "#")?;
+        }
+        if self.zero {
+            write!(f, "0")?;
+        }
+        if self.width > 0 {
+            write!(f, "{}", self.width)?;
+        }
+        if let Some(radix) = self.radix {
+            write!(f, "{}", radix.symbol())?;
+        }
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn format(spec: &str, value: &Value) -> Result<String, ValueError> {
+        FormatSpec::new(spec).unwrap().format(value)
+    }
+    #[test]
+    fn display_spec() {
+        for spec in ["", ">8", "*^#010x", "<", "08b", "#o"] {
+            assert_eq!(FormatSpec::new(spec).unwrap().to_string(), spec);
+        }
+    }
+    #[test]
+    fn parse_empty() {
+        assert_eq!(FormatSpec::new(""), Ok(FormatSpec::default()));
+    }
+    #[test]
+    fn parse_full() {
+        assert_eq!(
+            FormatSpec::new("*^#010x"),
+            Ok(FormatSpec {
+                fill: '*',
+                align: Some(Align::Center),
+                alternate: true,
+                zero: true,
+                width: 10,
+                radix: Some(Radix::Hex),
+            })
+        );
+    }
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(
+            FormatSpec::new("8d"),
+            Err(FormatStringError::InvalidSpec("8d".to_string()))
+        );
+        assert_eq!(
+            FormatSpec::new("x8"),
+            Err(FormatStringError::InvalidSpec("x8".to_string()))
+        );
+        assert_eq!(
+            FormatSpec::new(">1025"),
+            Err(FormatStringError::InvalidSpec(">1025".to_string()))
+        );
+        assert_eq!(
+            FormatSpec::new("99999999999999999999999"),
+            Err(FormatStringError::InvalidSpec(
+                "99999999999999999999999".to_string()
+            ))
+        );
+        assert_eq!(FormatSpec::new("1024").map(|spec| spec.width), Ok(1024));
+    }
+    #[test]
+    fn format_without_spec() {
+        assert_eq!(format("", &Value::from(-42)), Ok("-42".to_string()));
+        assert_eq!(format("", &Value::from("abc")), Ok("abc".to_string()));
+    }
+    #[test]
+    fn format_with_width() {
+        assert_eq!(format("5", &Value::from(42)), Ok("   42".to_string()));
+        assert_eq!(format("5", &Value::from("ab")), Ok("ab   ".to_string()));
+        assert_eq!(format("1", &Value::from("abc")), Ok("abc".to_string()));
+    }
+    #[test]
+    fn format_with_align() {
+        assert_eq!(format("<5", &Value::from(42)), Ok("42   ".to_string()));
+        assert_eq!(format(">5", &Value::from("ab")), Ok("   ab".to_string()));
+        assert_eq!(format("-^6", &Value::from("ab")), Ok("--ab--".to_string()));
+        assert_eq!(format("-^5", &Value::from("ab")), Ok("-ab--".to_string()));
+    }
+    #[test]
+    fn format_with_zero() {
+        assert_eq!(format("05", &Value::from(-42)), Ok("-0042".to_string()));
+        assert_eq!(format("05", &Value::from("ab")), Ok("ab   ".to_string()));
+    }
+    #[test]
+    fn format_with_radix() {
+        assert_eq!(format("x", &Value::from(255)), Ok("ff".to_string()));
+        assert_eq!(format("X", &Value::from(255)), Ok("FF".to_string()));
+        assert_eq!(format("#b", &Value::from(5)), Ok("0b101".to_string()));
+        assert_eq!(format("o", &Value::from(8)), Ok("10".to_string()));
+        assert_eq!(
+            format("#010x", &Value::from(255)),
+            Ok("0x000000ff".to_string())
+        );
+        assert_eq!(format("x", &Value::from(-1)), Ok("ffffffff".to_string()));
+        assert_eq!(
+            format("x", &Value::from("ff")),
+            Err(ValueError::TypeMismatch {
+                expected: "Int",
+                found: "Str"
+            })
+        );
+    }
+}
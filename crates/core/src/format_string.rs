@@ -0,0 +1,300 @@
+use crate::internal::prelude::*;
+use std::str::Chars;
+
+/// An interpolated literal like `f"x = {x + 1:>4}"`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FormatString {
+    parts: Vec<FormatPart>,
+}
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum FormatPart {
+    Literal(String),
+    Placeholder {
+        expression: Expression,
+        spec: FormatSpec,
+    },
+}
+impl FormatString {
+    /// Besides the escape sequences of `StringLiteral`, `{{` and `}}` stand for `{` and `}`.
+    ///
+    /// A string literal in a placeholder has its quotes escaped, like `f"{upper \"a\"}"`.
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        let Some(quoted) = s.strip_prefix('f').filter(|s| s.starts_with('"')) else {
+            return Err(FormatStringError::MissingPrefix)?;
+        };
+        if TrimmedStr::new(quoted).strip_wrapping('"', '"').is_none() {
+            if Self::has_unescaped_quote(quoted) {
+                return Err(FormatStringError::UnescapedQuote)?;
+            }
+            return Err(StringLiteralError::MissingClosingQuote)?;
+        }
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = quoted[1..quoted.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    literal.push(c);
+                    literal.extend(chars.next());
+                }
+                '{' | '}' if chars.as_str().starts_with(c) => {
+                    chars.next();
+                    literal.push(c);
+                }
+                '}' => return Err(FormatStringError::UnmatchedClosingBrace)?,
+                '{' => {
+                    if !literal.is_empty() {
+                        let literal = StringLiteral::unescape(&std::mem::take(&mut literal))?;
+                        parts.push(FormatPart::Literal(literal));
+                    }
+                    parts.push(Self::parse_placeholder(&mut chars)?);
+                }
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(FormatPart::Literal(StringLiteral::unescape(&literal)?));
+        }
+        Ok(Self { parts })
+    }
+    /// Whether a placeholder of the quoted literal has a `"` which is not escaped,
+    /// like `"{upper "a"}"`, which ends the literal early.
+    fn has_unescaped_quote(quoted: &str) -> bool {
+        let Some(inner) = quoted.strip_prefix('"').and_then(|s| s.strip_suffix('"')) else {
+            return false;
+        };
+        let (mut chars, mut depth) = (inner.chars(), 0_usize);
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '{' | '}' if depth == 0 && chars.as_str().starts_with(c) => {
+                    chars.next();
+                }
+                '{' => depth += 1,
+                '}' => depth = depth.saturating_sub(1),
+                '"' if depth > 0 => return true,
+                _ => (),
+            }
+        }
+        false
+    }
+    /// Parse the placeholder after its `{`, till the `}` which closes it.
+    fn parse_placeholder(chars: &mut Chars) -> Result<FormatPart, Error> {
+        let mut source = String::new();
+        loop {
+            match chars.next() {
+                None => return Err(FormatStringError::MissingClosingBrace)?,
+                Some('\\') if chars.as_str().starts_with(['"', '\\']) => {
+                    source.extend(chars.next());
+                }
+                Some('}') if TopLevelChars::new(&source).is_closed() => break,
+                Some(c) => source.push(c),
+            }
+        }
+        let separator = TopLevelChars::new(&source)
+            .filter(|&(_, c)| c == ':')
+            .last()
+            .map(|(index, _)| index);
+        let (expression, spec) = match separator {
+            Some(index) => (&source[..index], FormatSpec::new(&source[index + 1..])?),
+            None => (source.as_str(), FormatSpec::default()),
+        };
+        let expression = Expression::new(&expression.into())?;
+        if expression.is_empty() {
+            return Err(FormatStringError::EmptyPlaceholder)?;
+        }
+        Ok(FormatPart::Placeholder { expression, spec })
+    }
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        for part in &self.parts {
+            if let FormatPart::Placeholder { expression, .. } = part {
+                expression.resolve(resolver)?;
+            }
+        }
+        Ok(())
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        for part in &self.parts {
+            if let FormatPart::Placeholder { expression, .. } = part {
+                expression.lint(linter);
+            }
+        }
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<Type, Error> {
+        for part in &self.parts {
+            if let FormatPart::Placeholder { expression, .. } = part {
+                expression.infer(checker)?;
+            }
+        }
+        Ok(Type::Str)
+    }
+    pub fn eval(&self, env: &Environment) -> Result<Value, Error> {
+        let mut s = String::new();
+        for part in &self.parts {
+            match part {
+                FormatPart::Literal(literal) => s.push_str(literal),
+                FormatPart::Placeholder { expression, spec } => {
+                    s.push_str(&spec.format(&expression.eval(env)?)?);
+                }
+            }
+        }
+        Ok(Value::Str(s))
+    }
+}
+impl std::fmt::Display for FormatString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "f\"")?;
+        for part in &self.parts {
+            match part {
+                FormatPart::Literal(literal) => {
+                    for c in literal.chars() {
+                        match c {
+                            '{' | '}' => write!(f, "{c}{c}")?,
+                            c => write!(f, "{}", c.to_string().escape_debug())?,
+                        }
+                    }
+                }
+                FormatPart::Placeholder { expression, spec } => {
+                    let mut source = expression.to_string();
+                    if *spec != FormatSpec::default() {
+                        source = format!("{source}:{spec}");
+                    }
+                    // the quotes of a string literal in the placeholder are escaped
+                    let source = source.replace('\\', "\\\\").replace('"', "\\\"");
+                    write!(f, "{{{source}}}")?;
+                }
+            }
+        }
+        write!(f, "\"")
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn placeholder(expression: &str, spec: &str) -> FormatPart {
+        FormatPart::Placeholder {
+            expression: Expression::new(&expression.into()).unwrap(),
+            spec: FormatSpec::new(spec).unwrap(),
+        }
+    }
+    #[test]
+    fn parse_without_placeholder() {
+        assert_eq!(
+            FormatString::new(&r#"f"a\tb""#.into()),
+            Ok(FormatString {
+                parts: vec![FormatPart::Literal("a\tb".to_string())]
+            })
+        );
+        assert_eq!(
+            FormatString::new(&r#"f"""#.into()),
+            Ok(FormatString { parts: vec![] })
+        );
+    }
+    #[test]
+    fn parse_with_placeholders() {
+        assert_eq!(
+            FormatString::new(&r#"f"x = {x + 1}, {y:>4}!""#.into()),
+            Ok(FormatString {
+                parts: vec![
+                    FormatPart::Literal("x = ".to_string()),
+                    placeholder("x + 1", ""),
+                    FormatPart::Literal(", ".to_string()),
+                    placeholder("y", ">4"),
+                    FormatPart::Literal("!".to_string()),
+                ]
+            })
+        );
+    }
+    #[test]
+    fn parse_with_nested_braces_and_quotes() {
+        assert_eq!(
+            FormatString::new(&r#"f"{{{ {let a = 1; a} }}} {upper \"}\"}""#.into()),
+            Ok(FormatString {
+                parts: vec![
+                    FormatPart::Literal("{".to_string()),
+                    placeholder("{let a = 1; a}", ""),
+                    FormatPart::Literal("} ".to_string()),
+                    placeholder(r#"upper "}""#, ""),
+                ]
+            })
+        );
+    }
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(
+            FormatString::new(&r#""{x}""#.into()),
+            Err(Error::FormatString(FormatStringError::MissingPrefix))
+        );
+        assert_eq!(
+            FormatString::new(&r#"f "{x}""#.into()),
+            Err(Error::FormatString(FormatStringError::MissingPrefix))
+        );
+        assert_eq!(
+            FormatString::new(&r#"f"{x""#.into()),
+            Err(Error::FormatString(FormatStringError::MissingClosingBrace))
+        );
+        assert_eq!(
+            FormatString::new(&r#"f"x}""#.into()),
+            Err(Error::FormatString(
+                FormatStringError::UnmatchedClosingBrace
+            ))
+        );
+        assert_eq!(
+            FormatString::new(&r#"f"{ }""#.into()),
+            Err(Error::FormatString(FormatStringError::EmptyPlaceholder))
+        );
+        assert_eq!(
+            FormatString::new(&r#"f"{x:?}""#.into()),
+            Err(Error::FormatString(FormatStringError::InvalidSpec(
+                "?".to_string()
+            )))
+        );
+        assert_eq!(
+            FormatString::new(&r#"f"{upper "a"}""#.into()),
+            Err(Error::FormatString(FormatStringError::UnescapedQuote))
+        );
+        assert_eq!(
+            FormatString::new(&r#"f"{x"#.into()),
+            Err(Error::StringLiteral(
+                StringLiteralError::MissingClosingQuote
+            ))
+        );
+    }
+    #[test]
+    fn eval() {
+        let env = &mut Environment::default();
+        BindingDef::new(&"let x = 41".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        BindingDef::new(&r#"let name = "mask""#.into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        assert_eq!(
+            FormatString::new(&r#"f"{name:<6}= {x + 1:#06x} ({x + 1})""#.into())
+                .unwrap()
+                .eval(env),
+            Ok(Value::Str("mask  = 0x002a (42)".to_string()))
+        );
+    }
+    #[test]
+    fn eval_with_error() {
+        let env = &Environment::default();
+        assert_eq!(
+            FormatString::new(&r#"f"{y}""#.into()).unwrap().eval(env),
+            Err(Error::Binding(BindingError::NotFound))
+        );
+        assert_eq!(
+            FormatString::new(&r#"f"{\"y\":x}""#.into())
+                .unwrap()
+                .eval(env),
+            Err(Error::Value(ValueError::TypeMismatch {
+                expected: "Int",
+                found: "Str"
+            }))
+        );
+    }
+}
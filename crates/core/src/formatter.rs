@@ -0,0 +1,140 @@
+use crate::internal::prelude::*;
+use std::fmt::Write;
+
+/// Write a program in the canonical layout, keeping its comments.
+///
+/// Each statement starts on its own line, and a run of blank lines between two of them
+/// becomes one. A statement with a comment inside is kept as it is written.
+pub struct Formatter<'a> {
+    source: &'a str,
+    comments: std::iter::Peekable<std::vec::IntoIter<Comment<'a>>>,
+    output: String,
+    /// Where the last statement or comment written ends in the source.
+    end: usize,
+}
+impl<'a> Formatter<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            comments: Comment::find(source).into_iter().peekable(),
+            output: String::new(),
+            end: 0,
+        }
+    }
+    /// # Errors
+    ///
+    /// This function will return an error with the line of a statement which cannot be parsed.
+    pub fn format(mut self) -> Result<String, String> {
+        let code = Comment::strip(self.source);
+        for (start, s) in Block::split_statements(&code) {
+            let start = start + s.len() - s.trim_start().len();
+            let end = start + s.trim().len();
+            let line = code[..start].matches('\n').count() + 1;
+            let statement =
+                Statement::new(&s.into()).map_err(|err| format!("line {line}: {err}"))?;
+            self.write_comments_before(start);
+            self.write_gap(start);
+            if self
+                .comments
+                .peek()
+                .is_some_and(|comment| comment.start < end)
+            {
+                self.output.push_str(&self.source[start..end]);
+                while self
+                    .comments
+                    .next_if(|comment| comment.start < end)
+                    .is_some()
+                {}
+            } else {
+                // infallible, as writing to a `String` cannot fail
+                let _ = write!(self.output, "{statement:#}");
+                // a definition ends with `;` like the other statements
+                if matches!(statement, Statement::FunctionDef(_) | Statement::EnumDef(_)) {
+                    self.output.push(';');
+                }
+            }
+            self.end = end;
+            // a comment on the same line stays after the statement
+            if let Some(comment) = self.comments.next_if(|comment| {
+                let between = &code[end..comment.start];
+                between.trim().is_empty() && !between.contains('\n')
+            }) {
+                self.output.push(' ');
+                self.output.push_str(comment.text.trim_end());
+                self.end = comment.end();
+            }
+            self.output.push('\n');
+        }
+        self.write_comments_before(self.source.len());
+        Ok(self.output)
+    }
+    fn write_comments_before(&mut self, start: usize) {
+        while let Some(comment) = self.comments.next_if(|comment| comment.start < start) {
+            self.write_gap(comment.start);
+            self.output.push_str(comment.text.trim_end());
+            self.output.push('\n');
+            self.end = comment.end();
+        }
+    }
+    /// Keep a blank line before what starts at `start`, if there is one in the source.
+    fn write_gap(&mut self, start: usize) {
+        if !self.output.is_empty() && self.source[self.end..start].matches('\n').count() > 1 {
+            self.output.push('\n');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn format(source: &str) -> String {
+        Formatter::new(source).format().unwrap()
+    }
+    #[test]
+    fn format_statements() {
+        assert_eq!(
+            format("let  x=1;fn f x=>{let y = x*2; y+1};\nf   x"),
+            "let x = 1;\nfn f x => {\n    let y = x * 2;\n    y + 1\n};\nf x\n"
+        );
+        assert_eq!(
+            format("fn sign x => match x { 0 => 0, _ => { return 1; } }"),
+            "fn sign x => match x {\n    0 => 0,\n    _ => { return 1; },\n};\n"
+        );
+        assert_eq!(format(""), "");
+    }
+    #[test]
+    fn format_with_comments() {
+        let source = "// the answer\n\n\n\nlet x = 42; // not 41\n\n// done\nx\n// end";
+        assert_eq!(
+            format(source),
+            "// the answer\n\nlet x = 42; // not 41\n\n// done\nx\n// end\n"
+        );
+        // a comment inside a statement keeps it as written
+        let source = "let s = \"//\";\nfn f x => {\n  x // same\n}";
+        assert_eq!(format(source), source.to_string() + "\n");
+    }
+    #[test]
+    fn format_is_idempotent() {
+        let source = "enum Shape { Circle(r), Empty };
+            fn area s => match s { Circle(r) => 3 * r * r, Empty => 0 }; // rough
+            let mut total = 0; for s in [Circle 1, Empty] { total += area s; if_big total };
+            while total == 0 { total = 1; break }; f\"{total:>4}\"";
+        let formatted = format(source);
+        assert_eq!(format(&formatted), formatted);
+        assert_eq!(
+            Block::new(
+                &format!("{{{}}}", Comment::strip(&formatted))
+                    .as_str()
+                    .into()
+            ),
+            Block::new(&format!("{{{}}}", Comment::strip(source)).as_str().into())
+        );
+    }
+    #[test]
+    fn format_invalid_program() {
+        assert_eq!(
+            Formatter::new("let x = 1;\nlet = 2;").format(),
+            Err("line 2: Invalid statement".to_string())
+        );
+    }
+}
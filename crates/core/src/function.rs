@@ -0,0 +1,266 @@
+use crate::internal::prelude::*;
+/// A function as a value, which can be passed to another function like `map`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Function {
+    Builtin(Builtin),
+    Constructor(Constructor),
+    Defined {
+        name: Identifier,
+        parameters: Vec<Pattern>,
+        output: Option<Type>,
+        body: Expression,
+        captured: Vec<(Identifier, NamedValue)>,
+    },
+}
+
+impl Function {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Builtin(builtin) => builtin.name(),
+            Self::Constructor(constructor) => constructor.name().as_str(),
+            Self::Defined { name, .. } => name.as_str(),
+        }
+    }
+    /// The number of the parameters, which is not fixed for a builtin.
+    pub fn arity(&self) -> Option<usize> {
+        match self {
+            Self::Builtin(_) => None,
+            Self::Constructor(constructor) => Some(constructor.arity()),
+            Self::Defined { parameters, .. } => Some(parameters.len()),
+        }
+    }
+    pub fn call(&self, arguments: Vec<Value>, env: &Environment) -> Result<Value, Error> {
+        let (name, parameters, output, body, captured) = match self {
+            Self::Builtin(builtin) => return builtin.call(arguments, env),
+            Self::Constructor(constructor) => return constructor.call(arguments),
+            Self::Defined {
+                name,
+                parameters,
+                output,
+                body,
+                captured,
+            } => (name, parameters, output, body, captured),
+        };
+        if parameters.len() != arguments.len() {
+            return Err(FunctionCallError::WrongParameterCount {
+                expected: parameters.len(),
+                got: arguments.len(),
+            })?;
+        }
+        // the body sees the names where the function is defined, rather than where it is called
+        let local = &mut env.create_child();
+        local.insert_captured(captured);
+        local.insert_function(
+            name.clone(),
+            parameters.clone(),
+            output.clone(),
+            body.clone(),
+            captured.clone(),
+        );
+        for (parameter, argument) in parameters.iter().zip(arguments) {
+            parameter.bind(argument, local)?;
+        }
+        env.enter_call(name.as_str())?;
+        let value = body.eval(local);
+        env.leave_call();
+        let value = match value {
+            // a loop which the function is called in is not left by the function
+            Err(Error::ControlFlow(ControlFlowError::Break(_))) => {
+                return Err(ControlFlowError::OutsideLoop(Keyword::Break.to_string()))?;
+            }
+            Err(Error::ControlFlow(ControlFlowError::Continue)) => {
+                return Err(ControlFlowError::OutsideLoop(Keyword::Continue.to_string()))?;
+            }
+            Err(Error::ControlFlow(ControlFlowError::Return(value))) => *value,
+            value => value?,
+        };
+        if let Some(output) = output
+            && !value.is_of_type(output)
+        {
+            return Err(AnnotationError::OutputTypeMismatch {
+                function: name.to_string(),
+                expected: output.to_string(),
+                found: value.type_name().to_string(),
+            })?;
+        }
+        Ok(value)
+    }
+}
+impl std::fmt::Display for Function {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<fn {}>", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn call_builtin() {
+        assert_eq!(
+            Function::Builtin(Builtin::Upper)
+                .call(vec![Value::Str("a".to_string())], &Environment::default()),
+            Ok(Value::Str("A".to_string()))
+        );
+    }
+    #[test]
+    fn call_defined() {
+        let function = Function::Defined {
+            name: "add".try_into().unwrap(),
+            parameters: vec!["x".try_into().unwrap(), "y".try_into().unwrap()],
+            output: None,
+            body: Expression::new(&"x + y".into()).unwrap(),
+            captured: vec![],
+        };
+        assert_eq!(
+            function.call(
+                vec![
+                    Value::Number(Number::from_i32(1)),
+                    Value::Number(Number::from_i32(2))
+                ],
+                &Environment::default()
+            ),
+            Ok(Value::Number(Number::from_i32(3)))
+        );
+        assert_eq!(
+            function.call(vec![], &Environment::default()),
+            Err(Error::FunctionCall(
+                FunctionCallError::WrongParameterCount {
+                    expected: 2,
+                    got: 0
+                }
+            ))
+        );
+    }
+    #[test]
+    fn call_with_tuple_parameter() {
+        let function = Function::Defined {
+            name: "sub".try_into().unwrap(),
+            parameters: vec![Pattern::new(&"(x, y)".into()).unwrap()],
+            output: None,
+            body: Expression::new(&"x - y".into()).unwrap(),
+            captured: vec![],
+        };
+        assert_eq!(
+            function.call(
+                vec![Value::Tuple(vec![
+                    Value::Number(Number::from_i32(3)),
+                    Value::Number(Number::from_i32(2))
+                ])],
+                &Environment::default()
+            ),
+            Ok(Value::Number(Number::from_i32(1)))
+        );
+        assert_eq!(
+            function.call(
+                vec![Value::Number(Number::from_i32(3))],
+                &Environment::default()
+            ),
+            Err(Error::Value(ValueError::TypeMismatch {
+                expected: "Tuple",
+                found: "Int"
+            }))
+        );
+    }
+    #[test]
+    fn call_with_output_type() {
+        let function = Function::Defined {
+            name: "half".try_into().unwrap(),
+            parameters: vec!["x".try_into().unwrap()],
+            output: Some(Type::Int),
+            body: Expression::new(&"x / 2".into()).unwrap(),
+            captured: vec![],
+        };
+        assert_eq!(
+            function.call(
+                vec![Value::Number(Number::from_i32(4))],
+                &Environment::default()
+            ),
+            Ok(Value::Number(Number::from_i32(2)))
+        );
+        let function = Function::Defined {
+            name: "half".try_into().unwrap(),
+            parameters: vec!["x".try_into().unwrap()],
+            output: Some(Type::Int),
+            body: Expression::new(&"f\"{x}\"".into()).unwrap(),
+            captured: vec![],
+        };
+        assert_eq!(
+            function.call(
+                vec![Value::Number(Number::from_i32(4))],
+                &Environment::default()
+            ),
+            Err(Error::Annotation(AnnotationError::OutputTypeMismatch {
+                function: "half".to_string(),
+                expected: "Int".to_string(),
+                found: "Str".to_string()
+            }))
+        );
+    }
+    #[test]
+    fn call_with_break() {
+        let function = Function::Defined {
+            name: "stop".try_into().unwrap(),
+            parameters: vec![],
+            output: None,
+            body: Expression::new(&"break 1".into()).unwrap(),
+            captured: vec![],
+        };
+        assert_eq!(
+            function.call(vec![], &Environment::default()),
+            Err(Error::ControlFlow(ControlFlowError::OutsideLoop(
+                "break".to_string()
+            )))
+        );
+    }
+    #[test]
+    fn call_with_return() {
+        let function = Function::Defined {
+            name: "double".try_into().unwrap(),
+            parameters: vec!["x".try_into().unwrap()],
+            output: Some(Type::Int),
+            body: Expression::new(&"{ let y = x * 2; return y; 0 }".into()).unwrap(),
+            captured: vec![],
+        };
+        assert_eq!(
+            function.call(
+                vec![Value::Number(Number::from_i32(2))],
+                &Environment::default()
+            ),
+            Ok(Value::Number(Number::from_i32(4)))
+        );
+    }
+    #[test]
+    fn call_with_return_from_nested_block() {
+        let function = Function::Defined {
+            name: "first_even".try_into().unwrap(),
+            parameters: vec!["xs".try_into().unwrap()],
+            output: None,
+            body: Expression::new(
+                &"{ for x in xs { match x & 1 { 0 => { return x; }, _ => 0 }; }; -1 }".into(),
+            )
+            .unwrap(),
+            captured: vec![],
+        };
+        let numbers = |values: &[i32]| {
+            Value::List(
+                values
+                    .iter()
+                    .map(|&value| Value::Number(Number::from_i32(value)))
+                    .collect(),
+            )
+        };
+        assert_eq!(
+            function.call(vec![numbers(&[1, 4, 6])], &Environment::default()),
+            Ok(Value::Number(Number::from_i32(4)))
+        );
+        assert_eq!(
+            function.call(vec![numbers(&[1])], &Environment::default()),
+            Ok(Value::Number(Number::from_i32(-1)))
+        );
+    }
+    #[test]
+    fn display() {
+        assert_eq!(Function::Builtin(Builtin::Len).to_string(), "<fn len>");
+    }
+}
@@ -7,36 +7,74 @@ pub struct FunctionCall {
 
 impl FunctionCall {
     pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
-        let mut parts = s.split_whitespace();
-        let name = parts.next().ok_or(FunctionCallError::Empty)?.try_into()?;
+        let mut parts = s
+            .split_top_level(char::is_whitespace)
+            .into_iter()
+            .filter(|s| !s.is_empty());
+        let name = Identifier::new(&parts.next().ok_or(FunctionCallError::Empty)?)?;
 
         let parameters = parts
-            .map(|s| Expression::new(&s.into()))
+            .map(|s| Expression::new(&s))
             .collect::<Result<_, _>>()?;
         Ok(Self { name, parameters })
     }
-    pub fn try_get_expression_from(
-        &self,
-        local: &mut Environment,
-    ) -> Result<Expression, FunctionCallError> {
-        let Some(NamedValue::Function { parameters, body }) =
-            local.get_from_self_and_parent(&self.name)
-        else {
-            return Err(FunctionCallError::NotFound);
-        };
-        if parameters.len() != self.parameters.len() {
-            return Err(FunctionCallError::WrongParameterCount {
-                expected: parameters.len(),
-                got: self.parameters.len(),
-            });
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        resolver.lookup(&self.name)?;
+        for parameter in &self.parameters {
+            parameter.resolve(resolver)?;
         }
-        for (param, value) in parameters.iter().zip(&self.parameters) {
-            local.insert_binding(param.clone(), value.clone());
+        Ok(())
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        linter.lookup(&self.name);
+        for parameter in &self.parameters {
+            parameter.lint(linter);
         }
-        Ok(body)
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<Type, Error> {
+        let function = checker.lookup(&self.name)?;
+        let arguments = self
+            .parameters
+            .iter()
+            .map(|parameter| parameter.infer(checker))
+            .collect::<Result<_, _>>()?;
+        let output = checker.fresh();
+        checker.unify(&function, &Type::function(arguments, output.clone()))?;
+        Ok(output)
+    }
+    pub fn eval(&self, env: &Environment) -> Result<Value, Error> {
+        let Ok(Value::Function(function)) = self.name.try_get_value_from(env) else {
+            return Err(FunctionCallError::NotFound)?;
+        };
+        function.call(self.eval_arguments(env)?, env)
+    }
+    /// Evaluate a call to `range` to its bounds, and nothing else, which is not evaluated.
+    pub fn eval_range(&self, env: &Environment) -> Result<Option<std::ops::Range<i32>>, Error> {
+        let Ok(Value::Function(Function::Builtin(Builtin::Range))) =
+            self.name.try_get_value_from(env)
+        else {
+            return Ok(None);
+        };
+        Builtin::range_bounds(self.eval_arguments(env)?).map(Some)
+    }
+    fn eval_arguments(&self, env: &Environment) -> Result<Vec<Value>, Error> {
+        self.parameters
+            .iter()
+            .map(|parameter| parameter.eval(env))
+            .collect()
     }
 }
 
+impl std::fmt::Display for FunctionCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        for parameter in &self.parameters {
+            write!(f, " ")?;
+            parameter.fmt_term(f)?;
+        }
+        Ok(())
+    }
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -71,91 +109,145 @@ mod tests {
         );
     }
     #[test]
-    fn try_get_expression_with_no_parameters() {
+    fn parse_function_call_with_grouped_parameters() {
+        assert_eq!(
+            FunctionCall::new(&"add (x + 1) { y }".into()),
+            Ok(FunctionCall {
+                name: "add".try_into().unwrap(),
+                parameters: vec![
+                    Expression::Operation(Operation::new(&"x + 1".into()).unwrap()),
+                    Expression::Block(Block::new(&"{ y }".into()).unwrap())
+                ]
+            })
+        );
+    }
+    #[test]
+    fn eval_with_no_parameters() {
         let env = &mut Environment::default();
         FunctionDef::new(&"fn homo_number => 114".into())
             .unwrap()
-            .store(env);
+            .store(env)
+            .unwrap();
         assert_eq!(
-            FunctionCall::new(&"homo_number".into())
-                .unwrap()
-                .try_get_expression_from(env),
-            Ok(Expression::Number(Number::from_i32(114)))
+            FunctionCall::new(&"homo_number".into()).unwrap().eval(env),
+            Ok(Value::Number(Number::from_i32(114)))
         );
     }
     #[test]
-    fn try_get_expression_with_parameters() {
+    fn eval_with_parameters() {
         let env = &mut Environment::default();
         FunctionDef::new(&"fn add x y => x + y".into())
             .unwrap()
-            .store(env);
+            .store(env)
+            .unwrap();
         assert_eq!(
-            FunctionCall::new(&"add 1 2".into())
-                .unwrap()
-                .try_get_expression_from(env),
-            Ok(Expression::Operation(
-                Operation::new(&"x+y".into()).unwrap()
-            ))
-        );
-        assert_eq!(
-            env.get_from_self_and_parent(&"x".try_into().unwrap()),
-            Some(NamedValue::Binding(Expression::Number(Number::from_i32(1))))
+            FunctionCall::new(&"add 1 2".into()).unwrap().eval(env),
+            Ok(Value::Number(Number::from_i32(1 + 2)))
         );
+        assert_eq!(env.get_from_self_and_parent(&"x".try_into().unwrap()), None);
+    }
+    #[test]
+    fn eval_with_parameters_named_as_arguments() {
+        let env = &mut Environment::default();
+        FunctionDef::new(&"fn sub x y => x - y".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        BindingDef::new(&"let x = 114".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        BindingDef::new(&"let y = 514".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
         assert_eq!(
-            env.get_from_self_and_parent(&"y".try_into().unwrap()),
-            Some(NamedValue::Binding(Expression::Number(Number::from_i32(2))))
+            FunctionCall::new(&"sub y x".into()).unwrap().eval(env),
+            Ok(Value::Number(Number::from_i32(514 - 114)))
         );
     }
     #[test]
-    fn try_get_expression_with_non_existing_identifier() {
+    fn eval_with_wrong_parameter_count() {
         let env = &mut Environment::default();
+        FunctionDef::new(&"fn add x y => x + y".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
         assert_eq!(
-            FunctionCall::new(&"non_existing".into())
-                .unwrap()
-                .try_get_expression_from(env),
-            Err(FunctionCallError::NotFound)
+            FunctionCall::new(&"add 1".into()).unwrap().eval(env),
+            Err(Error::FunctionCall(
+                FunctionCallError::WrongParameterCount {
+                    expected: 2,
+                    got: 1
+                }
+            ))
         );
     }
     #[test]
-    #[should_panic]
-    fn try_get_expression_from_outer_scope() {
+    fn eval_with_function_parameter() {
         let env = &mut Environment::default();
-        let invalid_function_def = FunctionDef::new(&"fn sub => x - y".into()).unwrap();
-        invalid_function_def.store(env);
-        BindingDef::new(&"let x = 114".into()).unwrap().store(env);
-        BindingDef::new(&"let y = 514".into()).unwrap().store(env);
-        let call_invalid_function = FunctionCall::new(&"sub".into()).unwrap();
-        // TODO: fix this because sub is a invalid function definition which use bindings from outer scope
+        FunctionDef::new(&"fn twice f x => f (f x)".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        FunctionDef::new(&"fn add_one x => x + 1".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
         assert_eq!(
-            call_invalid_function.try_get_expression_from(env),
-            Ok(Expression::Operation(
-                Operation::new(&"x - y".into()).unwrap()
-            ))
+            FunctionCall::new(&"twice add_one 1".into())
+                .unwrap()
+                .eval(env),
+            Ok(Value::Number(Number::from_i32(3)))
         );
         assert_eq!(
-            call_invalid_function
-                .try_get_expression_from(env)
+            FunctionCall::new(&r#"twice upper "a""#.into())
                 .unwrap()
                 .eval(env),
-            Err(Error::Binding(BindingError::NotFound))
+            Ok(Value::Str("A".to_string()))
+        );
+    }
+    #[test]
+    fn eval_with_non_existing_identifier() {
+        let env = &mut Environment::default();
+        assert_eq!(
+            FunctionCall::new(&"non_existing".into()).unwrap().eval(env),
+            Err(Error::FunctionCall(FunctionCallError::NotFound))
         );
-        FunctionDef::new(&"fn sub x - y => x - y".into())
+        BindingDef::new(&"let x = 1".into())
             .unwrap()
-            .store(env);
+            .store(env)
+            .unwrap();
         assert_eq!(
-            FunctionCall::new(&"sub".into())
-                .unwrap()
-                .try_get_expression_from(env),
-            Ok(Expression::Operation(
-                Operation::new(&"x - y".into()).unwrap()
-            ))
+            FunctionCall::new(&"x 1".into()).unwrap().eval(env),
+            Err(Error::FunctionCall(FunctionCallError::NotFound))
         );
+    }
+    #[test]
+    fn eval_from_outer_scope() {
+        let env = &mut Environment::default();
         assert_eq!(
-            FunctionCall::new(&"sub y x".into())
+            FunctionDef::new(&"fn sub => x - y".into())
                 .unwrap()
-                .try_get_expression_from(env)
-                .unwrap()
-                .eval(env),
+                .store(env),
+            Err(Error::Resolve(ResolveError::UnknownIdentifier(
+                "x".to_string()
+            )))
+        );
+        BindingDef::new(&"let x = 114".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        BindingDef::new(&"let y = 514".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        FunctionDef::new(&"fn sub => y - x".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        assert_eq!(
+            FunctionCall::new(&"sub".into()).unwrap().eval(env),
             Ok(Value::Number(Number::from_i32(514 - 114)))
         );
     }
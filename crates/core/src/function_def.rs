@@ -1,34 +1,153 @@
 use crate::internal::prelude::*;
+use std::collections::HashSet;
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FunctionDef {
     name: Identifier,
-    parameters: Vec<Identifier>,
+    parameters: Vec<Pattern>,
+    output: Option<Type>,
     body: Expression,
 }
 impl FunctionDef {
     pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
-        let Some(s) = s.strip_prefix("fn ") else {
+        let Some(s) = Keyword::Fn.strip(s) else {
             return Err(FunctionDefError::MissingFnKeyword)?;
         };
         let (rest, body) = s.split_once("=>").ok_or(FunctionDefError::MissingArrow)?;
-        let (name, parameters) = rest.trim().split_once(' ').unwrap_or((rest, ""));
-        let parameters = parameters
-            .split_whitespace()
-            .filter_map(|s| s.try_into().ok())
-            .collect();
+        // the output type is annotated like `fn add (x: Int) (y: Int) -> Int => x + y`
+        let arrow = TopLevelChars::new(rest).find(|&(nth, _)| rest[nth..].starts_with("->"));
+        let (rest, output) = match arrow {
+            Some((nth, _)) => (
+                &rest[..nth],
+                Some(Type::new(&rest[nth + "->".len()..].into())?),
+            ),
+            None => (rest, None),
+        };
+        let rest = TrimmedStr::new(rest);
+        // a tuple parameter like `(a, b)` contains whitespace
+        let mut parts = rest
+            .split_top_level(char::is_whitespace)
+            .into_iter()
+            .filter(|s| !s.is_empty());
+        let name = Identifier::new(&parts.next().unwrap_or(rest))?;
+        let parameters = parts.map(|s| Pattern::new(&s)).collect::<Result<_, _>>()?;
         let body = Expression::new(&body.into())?;
         Ok(Self {
-            name: name.try_into()?,
+            name,
             parameters,
+            output,
             body,
         })
     }
-    pub fn store(&self, env: &mut Environment) {
+    pub const fn from_parts(
+        name: Identifier,
+        parameters: Vec<Pattern>,
+        output: Option<Type>,
+        body: Expression,
+    ) -> Self {
+        Self {
+            name,
+            parameters,
+            output,
+            body,
+        }
+    }
+    pub const fn name(&self) -> &Identifier {
+        &self.name
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<(), Error> {
+        let ty = checker.scoped(|checker| {
+            let mut parameters = Vec::new();
+            for parameter in &self.parameters {
+                let ty = checker.fresh();
+                for (name, ty) in parameter.infer(checker, &ty)? {
+                    checker.bind(name, ty);
+                }
+                parameters.push(ty);
+            }
+            let output = checker.fresh();
+            let ty = Type::function(parameters, output.clone());
+            // the function may call itself, but only with the same types
+            checker.bind(self.name.clone(), ty.clone());
+            let body = checker.in_function(output.clone(), |checker| self.body.infer(checker))?;
+            if let Some(expected) = &self.output {
+                checker.unify(expected, &body).map_err(|_| {
+                    AnnotationError::OutputTypeMismatch {
+                        function: self.name.to_string(),
+                        expected: expected.to_string(),
+                        found: checker.resolve(&body).normalize().to_string(),
+                    }
+                })?;
+            }
+            checker.unify(&output, &body)?;
+            Ok(ty)
+        })?;
+        checker.bind_generalized(self.name.clone(), &ty);
+        Ok(())
+    }
+    /// Check the parameters are distinct, and the body only uses names defined so far.
+    ///
+    /// The function itself is declared first, so it can call itself.
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        let mut names = HashSet::new();
+        for parameter in &self.parameters {
+            for name in parameter.identifiers() {
+                if !names.insert(name) {
+                    return Err(ResolveError::DuplicateParameter(name.to_string()))?;
+                }
+            }
+        }
+        resolver.declare(self.name.clone());
+        resolver.scoped(|resolver| {
+            for parameter in &self.parameters {
+                parameter.resolve(resolver)?;
+            }
+            self.body.resolve(resolver)
+        })
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        linter.declare_function(&self.name);
+        linter.scoped(|linter| {
+            for parameter in &self.parameters {
+                parameter.lint(linter, Rule::UnusedParameter);
+            }
+            self.body.lint(linter);
+        });
+    }
+    pub fn store(&self, env: &mut Environment) -> Result<(), Error> {
+        let resolver = &mut Resolver::new(env);
+        self.resolve(resolver)?;
+        // a mutable binding is still looked up when the function is called, to see what is assigned to it
+        let captured = resolver
+            .free()
+            .iter()
+            .filter(|name| !env.is_mutable(name))
+            .filter_map(|name| Some((name.clone(), env.get_from_self_and_parent(name)?)))
+            .collect();
         env.insert_function(
             self.name.clone(),
             self.parameters.clone(),
+            self.output.clone(),
             self.body.clone(),
+            captured,
         );
+        Ok(())
+    }
+}
+impl std::fmt::Display for FunctionDef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", Keyword::Fn, self.name)?;
+        for parameter in &self.parameters {
+            // an annotated parameter contains whitespace, like `(x: Int)`
+            match parameter {
+                Pattern::Annotated { .. } => write!(f, " ({parameter})")?,
+                parameter => write!(f, " {parameter}")?,
+            }
+        }
+        if let Some(output) = &self.output {
+            write!(f, " -> {output}")?;
+        }
+        write!(f, " => ")?;
+        self.body.fmt(f)
     }
 }
 #[cfg(test)]
@@ -41,6 +160,7 @@ mod tests {
             Ok(FunctionDef {
                 name: "nothing".try_into().unwrap(),
                 parameters: vec![],
+                output: None,
                 body: Expression::Block(Block::new(&"{}".into()).unwrap()),
             })
         );
@@ -52,6 +172,7 @@ mod tests {
             Ok(FunctionDef {
                 name: "foo".try_into().unwrap(),
                 parameters: vec!["x".try_into().unwrap()],
+                output: None,
                 body: Expression::Binding(Identifier::new(&"x".into()).unwrap()),
             })
         );
@@ -63,11 +184,78 @@ mod tests {
             Ok(FunctionDef {
                 name: "add".try_into().unwrap(),
                 parameters: vec!["x".try_into().unwrap(), "y".try_into().unwrap()],
+                output: None,
                 body: Expression::Operation(Operation::new(&"x + y".into()).unwrap()),
             })
         );
     }
     #[test]
+    fn parse_function_def_with_tuple_parameter() {
+        assert_eq!(
+            FunctionDef::new(&"fn swap (a, b) => (b, a)".into()),
+            Ok(FunctionDef {
+                name: "swap".try_into().unwrap(),
+                parameters: vec![Pattern::new(&"(a, b)".into()).unwrap()],
+                output: None,
+                body: Expression::Tuple(Tuple::new(&"(b, a)".into()).unwrap()),
+            })
+        );
+    }
+    #[test]
+    fn parse_function_def_with_annotations() {
+        assert_eq!(
+            FunctionDef::new(&"fn add (x: Int) (y: Int) -> Int => x + y".into()),
+            Ok(FunctionDef {
+                name: "add".try_into().unwrap(),
+                parameters: vec![
+                    Pattern::new(&"x: Int".into()).unwrap(),
+                    Pattern::new(&"y: Int".into()).unwrap()
+                ],
+                output: Some(Type::Int),
+                body: Expression::Operation(Operation::new(&"x + y".into()).unwrap()),
+            })
+        );
+        assert_eq!(
+            FunctionDef::new(&"fn apply (f: Fn(Int) -> Int) x => f x".into()),
+            Ok(FunctionDef {
+                name: "apply".try_into().unwrap(),
+                parameters: vec![
+                    Pattern::new(&"f: Fn(Int) -> Int".into()).unwrap(),
+                    Pattern::new(&"x".into()).unwrap()
+                ],
+                output: None,
+                body: Expression::FunctionCall(FunctionCall::new(&"f x".into()).unwrap()),
+            })
+        );
+    }
+    #[test]
+    fn infer_with_annotations() {
+        let checker = &mut TypeChecker::default();
+        FunctionDef::new(&"fn add (x: Int) y -> Int => x + y".into())
+            .unwrap()
+            .infer(checker)
+            .unwrap();
+        assert_eq!(
+            FunctionDef::new(&"fn name (x: Int) -> Str => x".into())
+                .unwrap()
+                .infer(checker),
+            Err(Error::Annotation(AnnotationError::OutputTypeMismatch {
+                function: "name".to_string(),
+                expected: "Str".to_string(),
+                found: "Int".to_string()
+            }))
+        );
+        assert_eq!(
+            FunctionDef::new(&"fn len_of (s: Str) => len s + s".into())
+                .unwrap()
+                .infer(checker),
+            Err(Error::Type(TypeError::Mismatch {
+                expected: "Int".to_string(),
+                found: "Str".to_string()
+            }))
+        );
+    }
+    #[test]
     fn parse_invalid_function_def() {
         assert_eq!(
             FunctionDef::new(&"fn invalid".into()),
@@ -77,10 +265,65 @@ mod tests {
             FunctionDef::new(&"invalid fn x => x".into()),
             Err(Error::FunctionDef(FunctionDefError::MissingFnKeyword))
         );
-        // TODO: fix it because x is undefined
         assert_eq!(
             FunctionDef::new(&"invalid fn => x".into()),
             Err(Error::FunctionDef(FunctionDefError::MissingFnKeyword))
         );
+        assert_eq!(
+            FunctionDef::new(&"fn sub x - y => x - y".into()),
+            Err(Error::Identifier(IdentifierError::StartWithNonLetter))
+        );
+    }
+    #[test]
+    fn store_with_undefined_identifier() {
+        let env = &mut Environment::default();
+        assert_eq!(
+            FunctionDef::new(&"fn f => x".into()).unwrap().store(env),
+            Err(Error::Resolve(ResolveError::UnknownIdentifier(
+                "x".to_string()
+            )))
+        );
+        assert_eq!(env.get_from_self_and_parent(&"f".try_into().unwrap()), None);
+        env.insert_binding("x".try_into().unwrap(), Value::Number(Number::from_i32(1)));
+        assert_eq!(
+            FunctionDef::new(&"fn f => x".into()).unwrap().store(env),
+            Ok(())
+        );
+    }
+    #[test]
+    fn store_with_duplicate_parameters() {
+        let env = &mut Environment::default();
+        assert_eq!(
+            FunctionDef::new(&"fn f x (y, x) => x".into())
+                .unwrap()
+                .store(env),
+            Err(Error::Resolve(ResolveError::DuplicateParameter(
+                "x".to_string()
+            )))
+        );
+    }
+    #[test]
+    fn store_recursive_and_local() {
+        let env = &mut Environment::default();
+        FunctionDef::new(&"fn fact n => match n { 0 => 1, n => n * fact (n - 1) }".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        FunctionDef::new(&"fn outer x => { let y = x; y + 1 }".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        FunctionDef::new(&"fn outer x => { fn inner y => x + y; inner 1 }".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        assert_eq!(
+            FunctionDef::new(&"fn outer x => { let y = x; y + z }".into())
+                .unwrap()
+                .store(env),
+            Err(Error::Resolve(ResolveError::UnknownIdentifier(
+                "z".to_string()
+            )))
+        );
     }
 }
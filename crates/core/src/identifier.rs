@@ -8,24 +8,57 @@ pub struct Identifier {
 }
 
 impl Identifier {
+    /// Parse an identifier, where a raw one like `r#let` may be a keyword.
     pub fn new(s: &TrimmedStr) -> Result<Self, IdentifierError> {
-        if s.is_empty() {
+        let (name, raw) = match s.strip_prefix("r#") {
+            Some(name) => (name, true),
+            None => (&**s, false),
+        };
+        if name.is_empty() {
             return Err(IdentifierError::Empty);
         }
-        if !s.starts_with(unicode_ident::is_xid_start) {
+        if !name.starts_with(unicode_ident::is_xid_start) {
             return Err(IdentifierError::StartWithNonLetter);
         }
-        if s.chars().all(unicode_ident::is_xid_continue) {
-            Ok(Self { name: s.into() })
-        } else {
-            Err(IdentifierError::ContainSpecialCharacters)
+        if !name.chars().all(unicode_ident::is_xid_continue) {
+            return Err(IdentifierError::ContainSpecialCharacters);
+        }
+        if !raw && Keyword::new(name).is_some() {
+            return Err(IdentifierError::ReservedKeyword(name.to_string()));
+        }
+        Ok(Self { name: name.into() })
+    }
+    pub fn as_str(&self) -> &str {
+        &self.name.0
+    }
+    /// Get the value bound to the name, where a function is got as a `Value::Function`.
+    pub fn try_get_value_from(&self, env: &Environment) -> Result<Value, BindingError> {
+        match env.get_from_self_and_parent(self) {
+            Some(NamedValue::Binding(value)) => Ok(value),
+            Some(NamedValue::Function {
+                parameters,
+                output,
+                body,
+                captured,
+            }) => Ok(Value::Function(Function::Defined {
+                name: self.clone(),
+                parameters,
+                output,
+                body,
+                captured,
+            })),
+            None => Builtin::new(self)
+                .map(|builtin| Value::Function(Function::Builtin(builtin)))
+                .ok_or(BindingError::NotFound),
         }
-        // TODO: more checks like ops, keywords, etc.
     }
-    pub fn try_get_expression_from(&self, env: &Environment) -> Result<Expression, BindingError> {
-        env.get_from_self_and_parent(self)
-            .and_then(NamedValue::into_expression)
-            .ok_or(BindingError::NotFound)
+}
+impl std::fmt::Display for Identifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if Keyword::new(self.as_str()).is_some() {
+            write!(f, "r#")?;
+        }
+        write!(f, "{}", self.as_str())
     }
 }
 impl FromStr for Identifier {
@@ -76,6 +109,31 @@ mod tests {
         );
     }
     #[test]
+    fn parse_keyword() {
+        assert_eq!(
+            Identifier::new(&"let".into()),
+            Err(IdentifierError::ReservedKeyword("let".to_string()))
+        );
+        assert_eq!(
+            Identifier::new(&"letter".into()),
+            Ok(Identifier {
+                name: "letter".into()
+            })
+        );
+    }
+    #[test]
+    fn parse_raw_identifier() {
+        let identifier = Identifier::new(&"r#match".into()).unwrap();
+        assert_eq!(identifier.as_str(), "match");
+        assert_eq!(identifier.to_string(), "r#match");
+        assert_eq!(Identifier::new(&"r#foo".into()).unwrap().to_string(), "foo");
+        assert_eq!(Identifier::new(&"r#".into()), Err(IdentifierError::Empty));
+        assert_eq!(
+            Identifier::new(&"r#1".into()),
+            Err(IdentifierError::StartWithNonLetter)
+        );
+    }
+    #[test]
     fn parse_start_with_number() {
         assert_eq!(
             Identifier::new(&"123foo".into()),
@@ -116,26 +174,53 @@ mod tests {
     }
     // Test from binding
     #[test]
-    fn get_expression_with_existing_identifier() {
+    fn get_value_with_existing_identifier() {
         let env = &mut Environment::default();
         BindingDef::new(&"let foo = 11451".into())
             .unwrap()
-            .store(env);
+            .store(env)
+            .unwrap();
         assert_eq!(
             Identifier::new(&"foo".into())
                 .unwrap()
-                .try_get_expression_from(env),
-            Ok(Expression::Number(Number::from_i32(11451)))
+                .try_get_value_from(env),
+            Ok(Value::Number(Number::from_i32(11451)))
         );
     }
     #[test]
-    fn get_expression_with_non_existing_identifier() {
+    fn get_value_with_non_existing_identifier() {
         let env = Environment::default();
         assert_eq!(
             Identifier::new(&"foo".into())
                 .unwrap()
-                .try_get_expression_from(&env),
+                .try_get_value_from(&env),
             Err(BindingError::NotFound)
         );
     }
+    #[test]
+    fn get_function_value() {
+        let env = &mut Environment::default();
+        FunctionDef::new(&"fn foo x => x".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        assert_eq!(
+            Identifier::new(&"foo".into())
+                .unwrap()
+                .try_get_value_from(env),
+            Ok(Value::Function(Function::Defined {
+                name: "foo".try_into().unwrap(),
+                parameters: vec!["x".try_into().unwrap()],
+                output: None,
+                body: Expression::Binding("x".try_into().unwrap()),
+                captured: vec![],
+            }))
+        );
+        assert_eq!(
+            Identifier::new(&"len".into())
+                .unwrap()
+                .try_get_value_from(env),
+            Ok(Value::Function(Function::Builtin(Builtin::Len)))
+        );
+    }
 }
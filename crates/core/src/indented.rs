@@ -0,0 +1,27 @@
+/// A writer that indents every line it writes, used by the alternate form of `Display`.
+pub struct Indented<'a, 'b> {
+    f: &'a mut std::fmt::Formatter<'b>,
+    at_line_start: bool,
+}
+impl<'a, 'b> Indented<'a, 'b> {
+    pub const INDENT: &'static str = "    ";
+    pub fn new(f: &'a mut std::fmt::Formatter<'b>) -> Self {
+        Self {
+            f,
+            at_line_start: false,
+        }
+    }
+}
+impl std::fmt::Write for Indented<'_, '_> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        for line in s.split_inclusive('\n') {
+            // blank lines are not indented
+            if self.at_line_start && line != "\n" {
+                self.f.write_str(Self::INDENT)?;
+            }
+            self.f.write_str(line)?;
+            self.at_line_start = line.ends_with('\n');
+        }
+        Ok(())
+    }
+}
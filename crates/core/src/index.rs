@@ -0,0 +1,332 @@
+use crate::internal::prelude::*;
+use std::ops::Range;
+
+/// Indexing like `xs[i]` or slicing like `xs[1..]` of a list or a string.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Index {
+    target: Box<Expression>,
+    subscript: Subscript,
+}
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Subscript {
+    At(Box<Expression>),
+    Slice {
+        start: Option<Box<Expression>>,
+        end: Option<Box<Expression>>,
+    },
+}
+impl Index {
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        let Some((nth, _)) = TopLevelChars::new(s).filter(|&(_, c)| c == '[').last() else {
+            return Err(IndexError::NotFound)?;
+        };
+        let (target, subscript) = s.split_at(nth);
+        // `f [1]` is a function call, and so is `f xs[1]` whose argument is indexed
+        if target.is_empty() || TopLevelChars::new(target).any(|(_, c)| c.is_whitespace()) {
+            return Err(IndexError::NotFound)?;
+        }
+        let Some(subscript) = TrimmedStr::new(subscript).strip_wrapping('[', ']') else {
+            return Err(ListError::MissingClosingBracket)?;
+        };
+        Ok(Self {
+            target: Expression::new(&target.into())?.into(),
+            subscript: Subscript::new(&subscript)?,
+        })
+    }
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        self.target.resolve(resolver)?;
+        self.subscript.resolve(resolver)
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        self.target.lint(linter);
+        self.subscript.lint(linter);
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<Type, Error> {
+        let target = self.target.infer(checker)?;
+        self.subscript.infer(checker)?;
+        if checker.resolve(&target) == Type::Str {
+            return Ok(Type::Str);
+        }
+        let element = checker.fresh();
+        checker.unify(&Type::list(element.clone()), &target)?;
+        match self.subscript {
+            Subscript::At(_) => Ok(element),
+            Subscript::Slice { .. } => Ok(Type::list(element)),
+        }
+    }
+    pub fn eval(&self, env: &Environment) -> Result<Value, Error> {
+        let is_slice = matches!(self.subscript, Subscript::Slice { .. });
+        let value = match self.target.eval(env)? {
+            Value::List(mut list) => {
+                let range = self.subscript.range(list.len(), env)?;
+                if is_slice {
+                    Value::List(list.drain(range).collect())
+                } else {
+                    list.swap_remove(range.start)
+                }
+            }
+            // a string is indexed by chars, and a char is a string
+            Value::Str(s) => {
+                let range = self.subscript.range(s.chars().count(), env)?;
+                Value::Str(s.chars().skip(range.start).take(range.len()).collect())
+            }
+            value => Err(ValueError::TypeMismatch {
+                expected: "List",
+                found: value.type_name(),
+            })?,
+        };
+        Ok(value)
+    }
+}
+impl Subscript {
+    fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        let range = TopLevelChars::new(s).find(|&(nth, _)| s[nth..].starts_with(".."));
+        let Some((nth, _)) = range else {
+            return match Expression::new(s)? {
+                Expression::Empty => Err(IndexError::EmptyIndex)?,
+                index => Ok(Self::At(index.into())),
+            };
+        };
+        let bound = |s: &str| -> Result<_, Error> {
+            match Expression::new(&s.into())? {
+                Expression::Empty => Ok(None),
+                bound => Ok(Some(bound.into())),
+            }
+        };
+        Ok(Self::Slice {
+            start: bound(&s[..nth])?,
+            end: bound(&s[nth + "..".len()..])?,
+        })
+    }
+    fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        match self {
+            Self::At(index) => index.resolve(resolver),
+            Self::Slice { start, end } => {
+                for bound in start.iter().chain(end) {
+                    bound.resolve(resolver)?;
+                }
+                Ok(())
+            }
+        }
+    }
+    fn lint(&self, linter: &mut Linter) {
+        match self {
+            Self::At(index) => index.lint(linter),
+            Self::Slice { start, end } => {
+                for bound in start.iter().chain(end) {
+                    bound.lint(linter);
+                }
+            }
+        }
+    }
+    fn infer(&self, checker: &mut TypeChecker) -> Result<(), Error> {
+        let bounds = match self {
+            Self::At(index) => vec![index],
+            Self::Slice { start, end } => start.iter().chain(end).collect(),
+        };
+        for bound in bounds {
+            let ty = bound.infer(checker)?;
+            checker.unify(&Type::Int, &ty)?;
+        }
+        Ok(())
+    }
+    /// Get the range of the elements to take, out of `len` elements.
+    fn range(&self, len: usize, env: &Environment) -> Result<Range<usize>, Error> {
+        let eval = |index: &Expression| -> Result<_, Error> {
+            Ok(index.eval(env)?.try_into_number()?.inner())
+        };
+        let out_of_range = |index| ValueError::IndexOutOfRange { index, len };
+        let range = match self {
+            Self::At(index) => {
+                let index = eval(index)?;
+                let nth = usize::try_from(index)
+                    .ok()
+                    .filter(|&nth| nth < len)
+                    .ok_or(out_of_range(index))?;
+                nth..nth + 1
+            }
+            Self::Slice { start, end } => {
+                let bound = |index: &Option<Box<Expression>>, default| -> Result<_, Error> {
+                    let Some(index) = index else {
+                        return Ok(default);
+                    };
+                    let index = eval(index)?;
+                    Ok(usize::try_from(index)
+                        .ok()
+                        .filter(|&nth| nth <= len)
+                        .ok_or(out_of_range(index))?)
+                };
+                let (start, end) = (bound(start, 0)?, bound(end, len)?);
+                if start > end {
+                    return Err(ValueError::ReversedSlice { start, end })?;
+                }
+                start..end
+            }
+        };
+        Ok(range)
+    }
+}
+impl std::fmt::Display for Index {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.target.fmt_term(f)?;
+        write!(f, "[")?;
+        self.subscript.fmt(f)?;
+        write!(f, "]")
+    }
+}
+impl std::fmt::Display for Subscript {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::At(index) => index.fmt(f),
+            Self::Slice { start, end } => {
+                if let Some(start) = start {
+                    start.fmt(f)?;
+                }
+                write!(f, "..")?;
+                if let Some(end) = end {
+                    end.fmt(f)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn eval(s: &str) -> Result<Value, Error> {
+        let env = &mut Environment::default();
+        BindingDef::new(&"let xs = [10, 20, 30]".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        Index::new(&s.into()).unwrap().eval(env)
+    }
+    #[test]
+    fn parse_index() {
+        assert_eq!(
+            Index::new(&"xs[i + 1]".into()),
+            Ok(Index {
+                target: Expression::Binding("xs".try_into().unwrap()).into(),
+                subscript: Subscript::At(
+                    Expression::Operation(Operation::new(&"i + 1".into()).unwrap()).into()
+                )
+            })
+        );
+    }
+    #[test]
+    fn parse_nested_index() {
+        assert_eq!(
+            Index::new(&"xs[0][1]".into()),
+            Ok(Index {
+                target: Expression::Index(Index::new(&"xs[0]".into()).unwrap()).into(),
+                subscript: Subscript::At(Expression::Number(Number::from_i32(1)).into())
+            })
+        );
+    }
+    #[test]
+    fn parse_slice() {
+        assert_eq!(
+            Index::new(&"xs[1..]".into()),
+            Ok(Index {
+                target: Expression::Binding("xs".try_into().unwrap()).into(),
+                subscript: Subscript::Slice {
+                    start: Some(Expression::Number(Number::from_i32(1)).into()),
+                    end: None
+                }
+            })
+        );
+        assert_eq!(
+            Index::new(&"xs[..]".into()),
+            Ok(Index {
+                target: Expression::Binding("xs".try_into().unwrap()).into(),
+                subscript: Subscript::Slice {
+                    start: None,
+                    end: None
+                }
+            })
+        );
+    }
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(
+            Index::new(&"xs".into()),
+            Err(Error::Index(IndexError::NotFound))
+        );
+        assert_eq!(
+            Index::new(&"[1]".into()),
+            Err(Error::Index(IndexError::NotFound))
+        );
+        assert_eq!(
+            Index::new(&"f xs[1]".into()),
+            Err(Error::Index(IndexError::NotFound))
+        );
+        assert_eq!(
+            Index::new(&"xs[]".into()),
+            Err(Error::Index(IndexError::EmptyIndex))
+        );
+        assert_eq!(
+            Index::new(&"xs[1".into()),
+            Err(Error::List(ListError::MissingClosingBracket))
+        );
+    }
+    #[test]
+    fn eval_index() {
+        assert_eq!(eval("xs[0]"), Ok(Value::from(10)));
+        assert_eq!(eval("xs[1 + 1]"), Ok(Value::from(30)));
+        assert_eq!(eval("[[1, 2], [3]][0][1]"), Ok(Value::from(2)));
+        assert_eq!(eval(r#""中文"[1]"#), Ok(Value::Str("文".to_string())));
+    }
+    #[test]
+    fn eval_index_out_of_range() {
+        assert_eq!(
+            eval("xs[3]"),
+            Err(Error::Value(ValueError::IndexOutOfRange {
+                index: 3,
+                len: 3
+            }))
+        );
+        assert_eq!(
+            eval("xs[-1]"),
+            Err(Error::Value(ValueError::IndexOutOfRange {
+                index: -1,
+                len: 3
+            }))
+        );
+    }
+    #[test]
+    fn eval_slice() {
+        assert_eq!(
+            eval("xs[1..]"),
+            Ok(Value::List(vec![Value::from(20), Value::from(30)]))
+        );
+        assert_eq!(eval("xs[..1]"), Ok(Value::List(vec![Value::from(10)])));
+        assert_eq!(eval("xs[1..1]"), Ok(Value::List(vec![])));
+        assert_eq!(eval("xs[0..3]"), eval("xs[..]"));
+        assert_eq!(eval(r#""hello"[1..3]"#), Ok(Value::Str("el".to_string())));
+    }
+    #[test]
+    fn eval_slice_out_of_range() {
+        assert_eq!(
+            eval("xs[2..1]"),
+            Err(Error::Value(ValueError::ReversedSlice { start: 2, end: 1 }))
+        );
+        assert_eq!(
+            eval("xs[..4]"),
+            Err(Error::Value(ValueError::IndexOutOfRange {
+                index: 4,
+                len: 3
+            }))
+        );
+    }
+    #[test]
+    fn eval_non_list() {
+        assert_eq!(
+            eval("1[0]"),
+            Err(Error::Value(ValueError::TypeMismatch {
+                expected: "List",
+                found: "Int"
+            }))
+        );
+    }
+}
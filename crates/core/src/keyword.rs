@@ -0,0 +1,77 @@
+/// Words of the syntax, which are not identifiers unless escaped like `r#let`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Keyword {
+    Let,
+    Mut,
+    Fn,
+    Enum,
+    Match,
+    If,
+    While,
+    For,
+    In,
+    Break,
+    Continue,
+    Return,
+}
+impl Keyword {
+    pub const ALL: [Self; 12] = [
+        Self::Let,
+        Self::Mut,
+        Self::Fn,
+        Self::Enum,
+        Self::Match,
+        Self::If,
+        Self::While,
+        Self::For,
+        Self::In,
+        Self::Break,
+        Self::Continue,
+        Self::Return,
+    ];
+    pub fn new(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|keyword| keyword.as_str() == s)
+    }
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Let => "let",
+            Self::Mut => "mut",
+            Self::Fn => "fn",
+            Self::Enum => "enum",
+            Self::Match => "match",
+            Self::If => "if",
+            Self::While => "while",
+            Self::For => "for",
+            Self::In => "in",
+            Self::Break => "break",
+            Self::Continue => "continue",
+            Self::Return => "return",
+        }
+    }
+    /// Get what follows the keyword, if `s` starts with it and then whitespace.
+    pub fn strip(self, s: &str) -> Option<&str> {
+        s.strip_prefix(self.as_str())
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+    }
+}
+impl std::fmt::Display for Keyword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn new() {
+        assert_eq!(Keyword::new("match"), Some(Keyword::Match));
+        assert_eq!(Keyword::new("matches"), None);
+    }
+    #[test]
+    fn strip() {
+        assert_eq!(Keyword::Let.strip("let x = 1"), Some(" x = 1"));
+        assert_eq!(Keyword::Let.strip("let\tx = 1"), Some("\tx = 1"));
+        assert_eq!(Keyword::Let.strip("letter"), None);
+        assert_eq!(Keyword::Fn.strip("fn"), None);
+    }
+}
@@ -1,29 +1,99 @@
+mod assignment;
 mod binding_def;
 mod block;
+mod builtin;
+mod comment;
+mod control_flow;
+mod enum_def;
 mod environment;
 mod error;
 mod expression;
+mod field_access;
+mod for_loop;
+mod format_spec;
+mod format_string;
+mod formatter;
+mod function;
 mod function_call;
 mod function_def;
 mod identifier;
+mod indented;
+mod index;
+mod keyword;
+mod linter;
+mod list;
+mod match_expression;
 mod named_value;
 mod number;
 mod operation;
 mod operator;
 pub mod parser;
+mod pattern;
+mod record;
+mod resolver;
 mod statement;
+mod string_literal;
+mod symbol;
+mod top_level_chars;
 mod trimmed_str;
+mod tuple;
+mod type_checker;
+mod unary_operation;
+mod unary_operator;
 mod value;
+mod value_type;
+mod while_loop;
 
-pub use parser::Parser;
+pub use linter::{Level, Lint, Linter, Rule};
+pub use parser::{Diagnostic, Evaluation, Parser};
+pub use symbol::{Symbol, SymbolKind};
 
 mod internal {
     pub mod prelude {
         pub use crate::{
-            binding_def::BindingDef, block::Block, environment::Environment, error::*,
-            expression::Expression, function_call::FunctionCall, function_def::FunctionDef,
-            identifier::Identifier, named_value::NamedValue, number::Number, operation::Operation,
-            operator::Operator, statement::Statement, trimmed_str::TrimmedStr, value::Value,
+            assignment::Assignment,
+            binding_def::BindingDef,
+            block::Block,
+            builtin::Builtin,
+            comment::Comment,
+            control_flow::ControlFlow,
+            enum_def::{Constructor, EnumDef},
+            environment::Environment,
+            error::*,
+            expression::Expression,
+            field_access::FieldAccess,
+            for_loop::ForLoop,
+            format_spec::FormatSpec,
+            format_string::FormatString,
+            formatter::Formatter,
+            function::Function,
+            function_call::FunctionCall,
+            function_def::FunctionDef,
+            identifier::Identifier,
+            indented::Indented,
+            index::Index,
+            keyword::Keyword,
+            linter::{Linter, Rule},
+            list::List,
+            match_expression::MatchExpression,
+            named_value::NamedValue,
+            number::Number,
+            operation::Operation,
+            operator::Operator,
+            pattern::Pattern,
+            record::Record,
+            resolver::Resolver,
+            statement::Statement,
+            string_literal::StringLiteral,
+            top_level_chars::TopLevelChars,
+            trimmed_str::TrimmedStr,
+            tuple::Tuple,
+            type_checker::TypeChecker,
+            unary_operation::UnaryOperation,
+            unary_operator::UnaryOperator,
+            value::Value,
+            value_type::Type,
+            while_loop::WhileLoop,
         };
     }
 }
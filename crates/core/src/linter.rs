@@ -0,0 +1,321 @@
+use crate::internal::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// A rule which the linter checks, like that each binding is used.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Rule {
+    UnusedBinding,
+    UnusedParameter,
+    Shadowing,
+    RedefinedFunction,
+    UnreachableCode,
+}
+impl Rule {
+    pub const ALL: [Self; 5] = [
+        Self::UnusedBinding,
+        Self::UnusedParameter,
+        Self::Shadowing,
+        Self::RedefinedFunction,
+        Self::UnreachableCode,
+    ];
+    /// Parse the name of a rule, like `unused-binding`.
+    #[must_use]
+    pub fn new(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rule| rule.name() == name)
+    }
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::UnusedBinding => "unused-binding",
+            Self::UnusedParameter => "unused-parameter",
+            Self::Shadowing => "shadowing",
+            Self::RedefinedFunction => "redefined-function",
+            Self::UnreachableCode => "unreachable-code",
+        }
+    }
+}
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// What the linter does with the lints of a rule, which are warnings by default.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum Level {
+    Allow,
+    #[default]
+    Warn,
+    Deny,
+}
+impl std::fmt::Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Allow => write!(f, "allowed"),
+            Self::Warn => write!(f, "warning"),
+            Self::Deny => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem found by the linter, at the line of the statement it is in.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Lint {
+    pub line: usize,
+    pub rule: Rule,
+    pub level: Level,
+    pub message: String,
+}
+impl std::fmt::Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}: {}: {} [{}]",
+            self.line, self.level, self.message, self.rule
+        )
+    }
+}
+
+/// A name bound in a scope, which is reported when the scope ends if it is never used.
+struct Declared {
+    name: Identifier,
+    rule: Rule,
+    line: usize,
+    used: bool,
+}
+#[derive(Default)]
+struct Scope {
+    bindings: Vec<Declared>,
+    functions: HashSet<Identifier>,
+}
+
+/// Find code which runs but is likely a mistake, like a binding which is never used.
+///
+/// Unlike `Resolver`, it goes through a whole program without an environment,
+/// so the lints point to the line of the top-level statement they are in.
+pub struct Linter {
+    levels: HashMap<Rule, Level>,
+    scopes: Vec<Scope>,
+    /// The variants of the enums defined, which a pattern like `Empty` matches rather than binds.
+    variants: HashSet<Identifier>,
+    line: usize,
+    lints: Vec<Lint>,
+}
+impl Default for Linter {
+    fn default() -> Self {
+        Self {
+            levels: HashMap::new(),
+            scopes: vec![Scope::default()],
+            variants: HashSet::new(),
+            line: 1,
+            lints: Vec::new(),
+        }
+    }
+}
+impl Linter {
+    pub fn set_level(&mut self, rule: Rule, level: Level) {
+        self.levels.insert(rule, level);
+    }
+    #[must_use]
+    pub fn level(&self, rule: Rule) -> Level {
+        self.levels.get(&rule).copied().unwrap_or_default()
+    }
+    /// Lint a program of statements separated by `;`, without running it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error with the line of a statement which cannot be parsed.
+    pub fn lint(mut self, program: &str) -> Result<Vec<Lint>, String> {
+        let program = &Comment::strip(program);
+        let mut statements = Vec::new();
+        for (start, s) in Block::split_statements(program) {
+            let start = start + s.len() - s.trim_start().len();
+            let line = program[..start].matches('\n').count() + 1;
+            let statement =
+                Statement::new(&s.into()).map_err(|err| format!("line {line}: {err}"))?;
+            statements.push((line, statement));
+        }
+        let mut returned = false;
+        for (line, statement) in &statements {
+            self.line = *line;
+            if returned {
+                self.report(Rule::UnreachableCode, "Code after `return` is unreachable");
+            }
+            statement.lint(&mut self);
+            returned = matches!(statement, Statement::Return(_));
+        }
+        self.end_scope();
+        self.lints.sort_by_key(|lint| lint.line);
+        Ok(self.lints)
+    }
+    /// Bind a name in the current scope, where `rule` is the one reported if it is never used.
+    pub fn declare(&mut self, name: &Identifier, rule: Rule) {
+        if self.variants.contains(name) {
+            return;
+        }
+        let bound = self
+            .scopes
+            .iter()
+            .flat_map(|scope| &scope.bindings)
+            .any(|declared| &declared.name == name);
+        if bound {
+            self.report(
+                Rule::Shadowing,
+                &format!("Binding `{name}` shadows an earlier binding"),
+            );
+        }
+        let declared = Declared {
+            name: name.clone(),
+            rule,
+            line: self.line,
+            used: false,
+        };
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.bindings.push(declared);
+        }
+    }
+    pub fn declare_function(&mut self, name: &Identifier) {
+        let defined = self
+            .scopes
+            .last_mut()
+            .is_some_and(|scope| !scope.functions.insert(name.clone()));
+        if defined {
+            self.report(
+                Rule::RedefinedFunction,
+                &format!("Function `{name}` is defined again in the same scope"),
+            );
+        }
+    }
+    pub fn declare_variant(&mut self, name: &Identifier) {
+        self.variants.insert(name.clone());
+    }
+    /// Mark the innermost binding of a name as used.
+    pub fn lookup(&mut self, name: &Identifier) {
+        let declared = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .flat_map(|scope| scope.bindings.iter_mut().rev())
+            .find(|declared| &declared.name == name);
+        if let Some(declared) = declared {
+            declared.used = true;
+        }
+    }
+    /// Run `f` in a new scope, and report the names it binds but never uses.
+    pub fn scoped(&mut self, f: impl FnOnce(&mut Self)) {
+        self.scopes.push(Scope::default());
+        f(self);
+        self.end_scope();
+    }
+    /// Lint a sequence of statements, reporting the one right after a `return`.
+    pub fn lint_statements(&mut self, statements: &[Statement]) {
+        let mut returned = false;
+        for statement in statements {
+            if returned {
+                self.report(Rule::UnreachableCode, "Code after `return` is unreachable");
+            }
+            statement.lint(self);
+            returned = matches!(statement, Statement::Return(_));
+        }
+    }
+    pub fn report(&mut self, rule: Rule, message: &str) {
+        self.report_at(self.line, rule, message);
+    }
+    fn report_at(&mut self, line: usize, rule: Rule, message: &str) {
+        let level = self.level(rule);
+        if level != Level::Allow {
+            self.lints.push(Lint {
+                line,
+                rule,
+                level,
+                message: message.to_string(),
+            });
+        }
+    }
+    fn end_scope(&mut self) {
+        let Some(scope) = self.scopes.pop() else {
+            return;
+        };
+        for declared in scope.bindings.iter().filter(|declared| !declared.used) {
+            let kind = match declared.rule {
+                Rule::UnusedParameter => "Parameter",
+                _ => "Binding",
+            };
+            let message = format!("{kind} `{}` is never used", declared.name);
+            self.report_at(declared.line, declared.rule, &message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn lint(program: &str) -> Vec<String> {
+        Linter::default()
+            .lint(program)
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+    #[test]
+    fn lint_unused_names() {
+        assert_eq!(
+            lint("let x = 1;\nfn f y z => z;\nf 1 2"),
+            vec![
+                "line 1: warning: Binding `x` is never used [unused-binding]",
+                "line 2: warning: Parameter `y` is never used [unused-parameter]",
+            ]
+        );
+        assert_eq!(
+            lint("enum Shape { Circle(r), Empty };\nmatch Empty { Circle(r) => 1, Empty => 0 }"),
+            vec!["line 2: warning: Binding `r` is never used [unused-binding]"]
+        );
+        assert_eq!(
+            lint("let (a, _) = (1, 2); fn g b => b; for x in [a] { g x }"),
+            Vec::<String>::new()
+        );
+    }
+    #[test]
+    fn lint_shadowing_and_redefinition() {
+        assert_eq!(
+            lint("let x = 1;\n{ let x = 2; x } + x"),
+            vec!["line 2: warning: Binding `x` shadows an earlier binding [shadowing]"]
+        );
+        assert_eq!(
+            lint("fn f => 1;\nfn f => 2;\n{ fn f => 3; f }"),
+            vec![
+                "line 2: warning: Function `f` is defined again in the same scope [redefined-function]"
+            ]
+        );
+    }
+    #[test]
+    fn lint_unreachable_code() {
+        assert_eq!(
+            lint("fn f x => {\n    return x;\n    x + 1;\n    x\n};\nf 1"),
+            vec!["line 1: warning: Code after `return` is unreachable [unreachable-code]"]
+        );
+        assert_eq!(lint("fn f x => { return x; }; f 1"), Vec::<String>::new());
+    }
+    #[test]
+    fn configure_levels() {
+        let mut linter = Linter::default();
+        linter.set_level(Rule::UnusedBinding, Level::Allow);
+        linter.set_level(Rule::Shadowing, Level::Deny);
+        assert_eq!(
+            linter.lint("let x = 1; let x = 2;"),
+            Ok(vec![Lint {
+                line: 1,
+                rule: Rule::Shadowing,
+                level: Level::Deny,
+                message: "Binding `x` shadows an earlier binding".to_string(),
+            }])
+        );
+        assert_eq!(Rule::new("unused-parameter"), Some(Rule::UnusedParameter));
+        assert_eq!(Rule::new("unused"), None);
+        assert_eq!(
+            Linter::default().lint("let x = 1;\nlet = 2;"),
+            Err("line 2: Invalid statement".to_string())
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use crate::internal::prelude::*;
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct List {
+    elements: Vec<Expression>,
+}
+impl List {
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        if !s.starts_with('[') {
+            return Err(ListError::MissingOpeningBracket)?;
+        }
+        let Some(s) = s.strip_wrapping('[', ']') else {
+            return Err(ListError::MissingClosingBracket)?;
+        };
+        let elements = s
+            .split_list()
+            .iter()
+            .map(|s| match Expression::new(s)? {
+                Expression::Empty => Err(ListError::EmptyElement)?,
+                expression => Ok(expression),
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Self { elements })
+    }
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        for element in &self.elements {
+            element.resolve(resolver)?;
+        }
+        Ok(())
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        for element in &self.elements {
+            element.lint(linter);
+        }
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<Type, Error> {
+        let element = checker.fresh();
+        for expression in &self.elements {
+            let ty = expression.infer(checker)?;
+            checker.unify(&element, &ty)?;
+        }
+        Ok(Type::list(element))
+    }
+    pub fn eval(&self, env: &Environment) -> Result<Value, Error> {
+        let values = self
+            .elements
+            .iter()
+            .map(|expression| expression.eval(env))
+            .collect::<Result<_, _>>()?;
+        Ok(Value::List(values))
+    }
+}
+impl std::fmt::Display for List {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        for (i, element) in self.elements.iter().enumerate() {
+            write!(f, "{}", if i > 0 { ", " } else { "" })?;
+            element.fmt(f)?;
+        }
+        write!(f, "]")
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parse_empty() {
+        assert_eq!(List::new(&"[ ]".into()), Ok(List { elements: vec![] }));
+    }
+    #[test]
+    fn parse_elements() {
+        assert_eq!(
+            List::new(&"[1, x + 1, [2, 3],]".into()),
+            Ok(List {
+                elements: vec![
+                    Expression::Number(Number::from_i32(1)),
+                    Expression::Operation(Operation::new(&"x + 1".into()).unwrap()),
+                    Expression::List(List::new(&"[2, 3]".into()).unwrap()),
+                ]
+            })
+        );
+    }
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(
+            List::new(&"1, 2]".into()),
+            Err(Error::List(ListError::MissingOpeningBracket))
+        );
+        assert_eq!(
+            List::new(&"[1, 2".into()),
+            Err(Error::List(ListError::MissingClosingBracket))
+        );
+        assert_eq!(
+            List::new(&"[1] + [2]".into()),
+            Err(Error::List(ListError::MissingClosingBracket))
+        );
+        assert_eq!(
+            List::new(&"[1, , 2]".into()),
+            Err(Error::List(ListError::EmptyElement))
+        );
+    }
+    #[test]
+    fn eval() {
+        let env = &mut Environment::default();
+        BindingDef::new(&"let x = 1".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        assert_eq!(
+            List::new(&r#"[x, x + 1, "a"]"#.into()).unwrap().eval(env),
+            Ok(Value::List(vec![
+                Value::Number(Number::from_i32(1)),
+                Value::Number(Number::from_i32(2)),
+                Value::Str("a".to_string())
+            ]))
+        );
+    }
+}
@@ -0,0 +1,340 @@
+use crate::internal::prelude::*;
+use std::fmt::Write;
+
+/// Like `match shape { Circle(r) => 3 * r * r, Rect(w, h) if w == h => w * w, _ => 0 }`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MatchExpression {
+    scrutinee: Box<Expression>,
+    arms: Vec<Arm>,
+}
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Arm {
+    pattern: Pattern,
+    guard: Option<Expression>,
+    body: Expression,
+}
+impl MatchExpression {
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        let Some(s) = Keyword::Match.strip(s) else {
+            return Err(MatchError::MissingMatchKeyword)?;
+        };
+        // the arms are the last group, so the scrutinee may be a block or a record
+        let Some((nth, _)) = TopLevelChars::new(s).filter(|&(_, c)| c == '{').last() else {
+            return Err(MatchError::MissingArms)?;
+        };
+        let Some(arms) = TrimmedStr::new(&s[nth..]).strip_wrapping('{', '}') else {
+            return Err(MatchError::MissingArms)?;
+        };
+        let scrutinee = match Expression::new(&s[..nth].into())? {
+            Expression::Empty => Err(MatchError::MissingScrutinee)?,
+            scrutinee => scrutinee,
+        };
+        Ok(Self {
+            scrutinee: scrutinee.into(),
+            arms: arms
+                .split_list()
+                .iter()
+                .map(Arm::new)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        self.scrutinee.resolve(resolver)?;
+        for arm in &self.arms {
+            resolver.scoped(|resolver| {
+                arm.pattern.resolve(resolver)?;
+                if let Some(guard) = &arm.guard {
+                    guard.resolve(resolver)?;
+                }
+                arm.body.resolve(resolver)
+            })?;
+        }
+        match self.check_exhaustive(resolver.env()) {
+            // an enum defined in the same block is declared, but not known yet
+            Err(Error::Pattern(PatternError::UnknownConstructor(_))) => Ok(()),
+            result => result,
+        }
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        self.scrutinee.lint(linter);
+        for arm in &self.arms {
+            linter.scoped(|linter| {
+                arm.pattern.lint(linter, Rule::UnusedBinding);
+                if let Some(guard) = &arm.guard {
+                    guard.lint(linter);
+                }
+                arm.body.lint(linter);
+            });
+        }
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<Type, Error> {
+        let scrutinee = self.scrutinee.infer(checker)?;
+        let output = checker.fresh();
+        for arm in &self.arms {
+            checker.scoped(|checker| {
+                for (name, ty) in arm.pattern.infer(checker, &scrutinee)? {
+                    checker.bind(name, ty);
+                }
+                if let Some(guard) = &arm.guard {
+                    let guard = guard.infer(checker)?;
+                    checker.unify(&Type::Int, &guard)?;
+                }
+                let body = arm.body.infer(checker)?;
+                Ok(checker.unify(&output, &body)?)
+            })?;
+        }
+        Ok(output)
+    }
+    pub fn eval(&self, env: &Environment) -> Result<Value, Error> {
+        let value = self.scrutinee.eval(env)?;
+        for arm in &self.arms {
+            let Some(bindings) = arm.pattern.try_match(value.clone(), env)? else {
+                continue;
+            };
+            let local = &mut env.create_child();
+            for (name, value) in bindings {
+                local.insert_binding(name, value);
+            }
+            if let Some(guard) = &arm.guard
+                && !guard.eval(local)?.try_into_condition()?
+            {
+                continue;
+            }
+            return arm.body.eval(local);
+        }
+        Err(MatchError::NonExhaustive(value.to_string()))?
+    }
+    /// Check that the arms without guards cover every value, or every variant of an enum.
+    fn check_exhaustive(&self, env: &Environment) -> Result<(), Error> {
+        let mut covered = Vec::new();
+        for arm in self.arms.iter().filter(|arm| arm.guard.is_none()) {
+            if arm.pattern.is_irrefutable(env) {
+                return Ok(());
+            }
+            if let Some(constructor) = arm.pattern.covered_variant(env)? {
+                covered.push(constructor);
+            }
+        }
+        let Some(first) = covered.first() else {
+            return Err(MatchError::NonExhaustive("_".to_string()))?;
+        };
+        let missing = first
+            .siblings()
+            .filter(|variant| !covered.iter().any(|covered| covered.name() == *variant))
+            .map(Identifier::as_str)
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(MatchError::NonExhaustive(missing.join(", ")))?
+        }
+    }
+}
+impl Arm {
+    fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        let Some((arrow, _)) = TopLevelChars::new(s).find(|&(nth, _)| s[nth..].starts_with("=>"))
+        else {
+            return Err(MatchError::MissingArrow)?;
+        };
+        let (head, body) = (&s[..arrow], &s[arrow + "=>".len()..]);
+        // the guard follows a top-level `if`, like `n if n > 0`
+        let guard_at = |nth: usize| {
+            let guard = head[nth..]
+                .trim_start()
+                .strip_prefix(Keyword::If.as_str())?;
+            guard
+                .starts_with(char::is_whitespace)
+                .then_some((nth, guard))
+        };
+        let guard = TopLevelChars::new(head)
+            .filter(|(_, c)| c.is_whitespace())
+            .find_map(|(nth, _)| guard_at(nth));
+        let (pattern, guard) = match guard {
+            Some((nth, guard)) => (&head[..nth], Some(Expression::new(&guard.into())?)),
+            None => (head, None),
+        };
+        Ok(Self {
+            pattern: Pattern::new(&pattern.into())?,
+            guard,
+            body: Expression::new(&body.into())?,
+        })
+    }
+}
+impl std::fmt::Display for MatchExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ", Keyword::Match)?;
+        self.scrutinee.fmt(f)?;
+        write!(f, " {{")?;
+        // the alternate form puts each arm on its own line
+        if f.alternate() && self.arms.len() > 1 {
+            let mut indented = Indented::new(f);
+            for arm in &self.arms {
+                write!(indented, "\n{arm:#},")?;
+            }
+            return write!(f, "\n}}");
+        }
+        for (i, arm) in self.arms.iter().enumerate() {
+            write!(f, "{} ", if i > 0 { "," } else { "" })?;
+            arm.fmt(f)?;
+        }
+        write!(f, " }}")
+    }
+}
+impl std::fmt::Display for Arm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.pattern)?;
+        if let Some(guard) = &self.guard {
+            write!(f, " {} ", Keyword::If)?;
+            guard.fmt(f)?;
+        }
+        write!(f, " => ")?;
+        self.body.fmt(f)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn shapes() -> Environment<'static> {
+        let mut env = Environment::default();
+        EnumDef::new(&"enum Shape { Circle(r), Rect(w, h), Empty }".into())
+            .unwrap()
+            .store(&mut env);
+        env
+    }
+    fn eval(s: &str, env: &Environment) -> Result<Value, Error> {
+        MatchExpression::new(&s.into())?.eval(env)
+    }
+    fn resolve(s: &str, env: &Environment) -> Result<(), Error> {
+        MatchExpression::new(&s.into())?.resolve(&mut Resolver::new(env))
+    }
+    #[test]
+    fn parse_match() {
+        assert_eq!(
+            MatchExpression::new(&"match x { 0 => 1, n if n & 1 => 2, _ => 3, }".into()),
+            Ok(MatchExpression {
+                scrutinee: Expression::Binding("x".try_into().unwrap()).into(),
+                arms: vec![
+                    Arm {
+                        pattern: Pattern::Literal(Value::from(0)),
+                        guard: None,
+                        body: Expression::Number(Number::from_i32(1)),
+                    },
+                    Arm {
+                        pattern: Pattern::Binding("n".try_into().unwrap()),
+                        guard: Some(Expression::new(&"n & 1".into()).unwrap()),
+                        body: Expression::Number(Number::from_i32(2)),
+                    },
+                    Arm {
+                        pattern: Pattern::Wildcard,
+                        guard: None,
+                        body: Expression::Number(Number::from_i32(3)),
+                    },
+                ]
+            })
+        );
+    }
+    #[test]
+    fn parse_invalid_match() {
+        assert_eq!(
+            MatchExpression::new(&"matches x { _ => 1 }".into()),
+            Err(Error::Match(MatchError::MissingMatchKeyword))
+        );
+        assert_eq!(
+            MatchExpression::new(&"match x".into()),
+            Err(Error::Match(MatchError::MissingArms))
+        );
+        assert_eq!(
+            MatchExpression::new(&"match { _ => 1 }".into()),
+            Err(Error::Match(MatchError::MissingScrutinee))
+        );
+        assert_eq!(
+            MatchExpression::new(&"match x { _ }".into()),
+            Err(Error::Match(MatchError::MissingArrow))
+        );
+    }
+    #[test]
+    fn eval_literals() {
+        let env = &Environment::default();
+        let s = r#"match x { 1 => "one", 2 => "two", _ => "many" }"#;
+        assert_eq!(
+            eval(&s.replace('x', "2"), env),
+            Ok(Value::Str("two".to_string()))
+        );
+        assert_eq!(
+            eval(&s.replace('x', "3"), env),
+            Ok(Value::Str("many".to_string()))
+        );
+    }
+    #[test]
+    fn eval_variants() {
+        let env = &shapes();
+        let area = "match s { Circle(r) => 3 * r * r, Rect(w, h) => w * h, Empty => 0 }";
+        assert_eq!(
+            eval(&area.replace("s {", "(Circle 2) {"), env),
+            Ok(Value::from(12))
+        );
+        assert_eq!(
+            eval(&area.replace("s {", "(Rect 2 3) {"), env),
+            Ok(Value::from(6))
+        );
+        assert_eq!(
+            eval(&area.replace("s {", "Empty {"), env),
+            Ok(Value::from(0))
+        );
+    }
+    #[test]
+    fn eval_with_guard() {
+        let env = &shapes();
+        let s = "match Rect 2 x { Rect(w, h) if w == h => 1, Rect(_, _) => 2, _ => 3 }";
+        assert_eq!(eval(&s.replace('x', "2"), env), Ok(Value::from(1)));
+        assert_eq!(eval(&s.replace('x', "3"), env), Ok(Value::from(2)));
+    }
+    #[test]
+    fn eval_bindings_in_arm() {
+        let env = &Environment::default();
+        assert_eq!(
+            eval("match (1, 2) { (a, b) => a - b }", env),
+            Ok(Value::from(-1))
+        );
+    }
+    #[test]
+    fn resolve_non_exhaustive() {
+        let env = &shapes();
+        assert_eq!(
+            resolve("match Empty { Circle(r) => r, Empty => 0 }", env),
+            Err(Error::Match(MatchError::NonExhaustive("Rect".to_string())))
+        );
+        assert_eq!(
+            resolve(
+                "match Empty { Circle(r) => r, Rect(1, h) => h, Empty => 0 }",
+                env
+            ),
+            Err(Error::Match(MatchError::NonExhaustive("Rect".to_string())))
+        );
+        assert_eq!(
+            resolve(
+                "match Empty { Circle(r) if r => r, Rect(w, h) => w, Empty => 0 }",
+                env
+            ),
+            Err(Error::Match(MatchError::NonExhaustive(
+                "Circle".to_string()
+            )))
+        );
+        assert_eq!(
+            resolve("match 1 { 1 => 1 }", env),
+            Err(Error::Match(MatchError::NonExhaustive("_".to_string())))
+        );
+        // a variant of an enum defined in the same block is not known until it runs
+        let block =
+            Statement::new(&"{ enum Bit { Zero, One }; match One { One => 1 } }".into()).unwrap();
+        assert_eq!(block.resolve(&mut Resolver::new(env)), Ok(()));
+    }
+    #[test]
+    fn eval_without_matching_arm() {
+        // the arms are checked to cover every value when the match is resolved
+        assert_eq!(
+            eval("match 2 { 1 => 1 }", &Environment::default()),
+            Err(Error::Match(MatchError::NonExhaustive("2".to_string())))
+        );
+    }
+}
@@ -2,8 +2,34 @@ use crate::internal::prelude::*;
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub struct Number(i32);
 impl Number {
+    const RADIX_PREFIXES: [(&str, u32); 3] = [("0x", 16), ("0b", 2), ("0o", 8)];
+    /// Parse a decimal, hexadecimal (`0x`), binary (`0b`) or octal (`0o`) literal,
+    /// whose digits can be separated by `_`.
+    ///
+    /// A non-decimal literal is a bit pattern, so `0xFFFF_FFFF` is `-1`.
     pub fn new(s: &TrimmedStr) -> Result<Self, NumberError> {
-        Ok(Self(s.parse()?))
+        let (sign, unsigned) = s.strip_prefix('-').map_or(("", &**s), |s| ("-", s));
+        let Some((radix, digits)) = Self::RADIX_PREFIXES
+            .into_iter()
+            .find_map(|(prefix, radix)| unsigned.strip_prefix(prefix).map(|s| (radix, s)))
+        else {
+            let digits = Self::remove_separators(unsigned);
+            return Ok(Self(format!("{sign}{digits}").parse()?));
+        };
+        let value = u32::from_str_radix(&Self::remove_separators(digits), radix)?.cast_signed();
+        if sign.is_empty() {
+            Ok(Self(value))
+        } else {
+            Ok(Self(value.wrapping_neg()))
+        }
+    }
+    fn remove_separators(digits: &str) -> String {
+        // `_` leading the digits is kept, so `_1` is rejected like other invalid digits.
+        if digits.starts_with('_') {
+            digits.to_owned()
+        } else {
+            digits.replace('_', "")
+        }
     }
     pub const fn inner(self) -> i32 {
         self.0
@@ -12,6 +38,11 @@ impl Number {
         Self(value)
     }
 }
+impl std::fmt::Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -21,11 +52,42 @@ mod tests {
         assert_eq!(Number::new(&"123".into()), Ok(Number(123)));
     }
 
+    #[test]
+    fn parse_negative_number() {
+        assert_eq!(Number::new(&"-123".into()), Ok(Number(-123)));
+        assert_eq!(Number::new(&"-2147483648".into()), Ok(Number(i32::MIN)));
+    }
+
+    #[test]
+    fn parse_with_radix_prefix() {
+        assert_eq!(Number::new(&"0xFF".into()), Ok(Number(0xFF)));
+        assert_eq!(Number::new(&"0b1010".into()), Ok(Number(0b1010)));
+        assert_eq!(Number::new(&"0o17".into()), Ok(Number(0o17)));
+        assert_eq!(Number::new(&"-0x10".into()), Ok(Number(-0x10)));
+        assert_eq!(Number::new(&"0xFFFF_FFFF".into()), Ok(Number(-1)));
+    }
+
+    #[test]
+    fn parse_with_separators() {
+        assert_eq!(Number::new(&"1_000_000".into()), Ok(Number(1_000_000)));
+        assert_eq!(Number::new(&"0b1111_0000".into()), Ok(Number(0b1111_0000)));
+        assert!(Number::new(&"_1".into()).is_err());
+        assert!(Number::new(&"0x_".into()).is_err());
+    }
+
     #[test]
     fn parse_non_number() {
         assert!(matches!(
             Number::new(&"non-number".into()),
             Err(NumberError::InvalidNumber(_))
         ));
+        assert!(matches!(
+            Number::new(&"0b102".into()),
+            Err(NumberError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            Number::new(&"0x1_0000_0000".into()),
+            Err(NumberError::InvalidNumber(_))
+        ));
     }
 }
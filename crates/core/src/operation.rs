@@ -8,12 +8,9 @@ pub struct Operation {
 
 impl Operation {
     pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
-        // find and parse is not the best, but it's simple
-        let nth = s
-            .find(Operator::OP_CHAR_LIST)
-            .ok_or(OperationError::NotFound)?;
+        let (nth, op) = Self::find_operator(s).ok_or(OperationError::NotFound)?;
         let (lhs, s) = s.split_at(nth);
-        let (op, rhs) = s.split_at(Operator::LEN_OF_OP);
+        let (op, rhs) = s.split_at(op.symbol().len());
         let lhs = Expression::new(&lhs.into())?;
         if lhs.is_empty() {
             return Err(OperationError::InvalidLhs)?;
@@ -28,23 +25,132 @@ impl Operation {
             op: Operator::new(&op.into())?,
         })
     }
+    pub fn from_operands(lhs: Expression, op: Operator, rhs: Expression) -> Self {
+        Self {
+            lhs: lhs.into(),
+            rhs: rhs.into(),
+            op,
+        }
+    }
+    /// Find the top-level binary operator to split at.
+    ///
+    /// It is the rightmost one with the lowest precedence, so operators are left-associative.
+    /// An operator which does not follow an operand is unary, like `-` in `1 * -2`.
+    fn find_operator(s: &str) -> Option<(usize, Operator)> {
+        let mut found: Option<(usize, Operator)> = None;
+        let mut after_operand = false;
+        let mut next_index = 0;
+        for (index, c) in TopLevelChars::new(s) {
+            if index < next_index || c.is_whitespace() {
+                continue;
+            }
+            let Some(op) = Operator::from_prefix(&s[index..]) else {
+                after_operand = true;
+                continue;
+            };
+            if after_operand && found.is_none_or(|(_, found)| op.precedence() <= found.precedence())
+            {
+                found = Some((index, op));
+            }
+            after_operand = false;
+            next_index = index + op.symbol().len();
+        }
+        found
+    }
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        self.lhs.resolve(resolver)?;
+        self.rhs.resolve(resolver)
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        self.lhs.lint(linter);
+        self.rhs.lint(linter);
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<Type, Error> {
+        let lhs = self.lhs.infer(checker)?;
+        let rhs = self.rhs.infer(checker)?;
+        match self.op {
+            Operator::Eq | Operator::Ne => checker.unify(&lhs, &rhs)?,
+            // `+` adds numbers or concatenates strings, which may be known only later
+            Operator::Add => {
+                checker.unify_addable(&lhs, &rhs)?;
+                return Ok(lhs);
+            }
+            _ => {
+                checker.unify(&Type::Int, &lhs)?;
+                checker.unify(&Type::Int, &rhs)?;
+            }
+        }
+        Ok(Type::Int)
+    }
     pub fn eval(&self, env: &Environment) -> Result<Value, Error> {
-        let Value::Number(lhs) = self.lhs.eval(env)? else {
-            return Err(OperationError::InvalidLhs)?;
+        // any two values can be compared, and equal ones are `1` as they are in C
+        if matches!(self.op, Operator::Eq | Operator::Ne) {
+            let equal = self.lhs.eval(env)? == self.rhs.eval(env)?;
+            let value = i32::from(equal == (self.op == Operator::Eq));
+            return Ok(Value::Number(Number::from_i32(value)));
+        }
+        let lhs = match self.lhs.eval(env)? {
+            Value::Str(lhs) if self.op == Operator::Add => {
+                let Value::Str(rhs) = self.rhs.eval(env)? else {
+                    return Err(OperationError::InvalidConcat)?;
+                };
+                return Ok(Value::Str(lhs + &rhs));
+            }
+            Value::Number(lhs) => lhs,
+            _ => return Err(OperationError::InvalidLhs)?,
         };
         let Value::Number(rhs) = self.rhs.eval(env)? else {
             return Err(OperationError::InvalidRhs)?;
         };
         let (lhs, rhs) = (lhs.inner(), rhs.inner());
         let value = match self.op {
-            Operator::Add => lhs + rhs,
-            Operator::Sub => lhs - rhs,
-            Operator::Mul => lhs * rhs,
-            Operator::Div => lhs / rhs,
+            Operator::Add => lhs.checked_add(rhs).ok_or(OperationError::Overflow)?,
+            Operator::Sub => lhs.checked_sub(rhs).ok_or(OperationError::Overflow)?,
+            Operator::Mul => lhs.checked_mul(rhs).ok_or(OperationError::Overflow)?,
+            Operator::Div if rhs == 0 => return Err(OperationError::DivisionByZero)?,
+            Operator::Div => lhs.checked_div(rhs).ok_or(OperationError::Overflow)?,
+            Operator::BitAnd => lhs & rhs,
+            Operator::BitOr => lhs | rhs,
+            Operator::BitXor => lhs ^ rhs,
+            Operator::Shl => u32::try_from(rhs)
+                .ok()
+                .and_then(|rhs| lhs.checked_shl(rhs))
+                .ok_or(OperationError::InvalidShift(rhs))?,
+            Operator::Shr => u32::try_from(rhs)
+                .ok()
+                .and_then(|rhs| lhs.checked_shr(rhs))
+                .ok_or(OperationError::InvalidShift(rhs))?,
+            Operator::Eq | Operator::Ne => unreachable!("equality is evaluated for any values"),
         };
         Ok(Value::Number(Number::from_i32(value)))
     }
 }
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // operators of the same precedence are left-associative
+        let precedence = self.op.precedence();
+        let fmt_parenthesized = |operation: &Self, f: &mut std::fmt::Formatter<'_>| {
+            write!(f, "(")?;
+            operation.fmt(f)?;
+            write!(f, ")")
+        };
+        match &*self.lhs {
+            Expression::Operation(lhs) if lhs.op.precedence() < precedence => {
+                fmt_parenthesized(lhs, f)
+            }
+            Expression::Operation(lhs) => lhs.fmt(f),
+            lhs => lhs.fmt_operand(f),
+        }?;
+        write!(f, " {} ", self.op)?;
+        match &*self.rhs {
+            Expression::Operation(rhs) if rhs.op.precedence() <= precedence => {
+                fmt_parenthesized(rhs, f)
+            }
+            Expression::Operation(rhs) => rhs.fmt(f),
+            rhs => rhs.fmt_operand(f),
+        }
+    }
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,9 +162,7 @@ mod tests {
         );
     }
     #[test]
-    #[should_panic]
     fn parse_block() {
-        // TODO: fix it because now expression cannot parse symbols in block
         assert_eq!(
             Operation::new(&"{let x = 0; x + 1} + 2".into()),
             Ok(Operation {
@@ -109,6 +213,66 @@ mod tests {
         );
     }
     #[test]
+    fn parse_with_precedence() {
+        assert_eq!(
+            Operation::new(&"1 + 2 * 3".into()),
+            Ok(Operation {
+                lhs: Expression::Number(Number::from_i32(1)).into(),
+                rhs: Expression::Operation(Operation::new(&"2 * 3".into()).unwrap()).into(),
+                op: Operator::Add
+            })
+        );
+        assert_eq!(
+            Operation::new(&"1 << 2 | 3 & 4".into()),
+            Ok(Operation {
+                lhs: Expression::Operation(Operation::new(&"1 << 2".into()).unwrap()).into(),
+                rhs: Expression::Operation(Operation::new(&"3 & 4".into()).unwrap()).into(),
+                op: Operator::BitOr
+            })
+        );
+    }
+    #[test]
+    fn parse_left_associative() {
+        assert_eq!(
+            Operation::new(&"8 - 4 - 2".into()),
+            Ok(Operation {
+                lhs: Expression::Operation(Operation::new(&"8 - 4".into()).unwrap()).into(),
+                rhs: Expression::Number(Number::from_i32(2)).into(),
+                op: Operator::Sub
+            })
+        );
+    }
+    #[test]
+    fn parse_with_unary_operand() {
+        assert_eq!(
+            Operation::new(&"1 * -2".into()),
+            Ok(Operation {
+                lhs: Expression::Number(Number::from_i32(1)).into(),
+                rhs: Expression::Number(Number::from_i32(-2)).into(),
+                op: Operator::Mul
+            })
+        );
+        assert_eq!(
+            Operation::new(&"~x & 1".into()),
+            Ok(Operation {
+                lhs: Expression::UnaryOperation(UnaryOperation::new(&"~x".into()).unwrap()).into(),
+                rhs: Expression::Number(Number::from_i32(1)).into(),
+                op: Operator::BitAnd
+            })
+        );
+    }
+    #[test]
+    fn parse_with_parentheses() {
+        assert_eq!(
+            Operation::new(&"(1 + 2) * 3".into()),
+            Ok(Operation {
+                lhs: Expression::Operation(Operation::new(&"1 + 2".into()).unwrap()).into(),
+                rhs: Expression::Number(Number::from_i32(3)).into(),
+                op: Operator::Mul
+            })
+        );
+    }
+    #[test]
     fn eval_add() {
         assert_eq!(
             Operation::new(&"1+2".into())
@@ -145,14 +309,176 @@ mod tests {
         );
     }
     #[test]
+    fn eval_equality() {
+        let env = &mut Environment::default();
+        BindingDef::new(&"let r = { width: 10, height: 20 }".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        let eval = |s: &str| Operation::new(&s.into()).unwrap().eval(env);
+        assert_eq!(eval("1 + 1 == 2"), Ok(Value::Number(Number::from_i32(1))));
+        assert_eq!(
+            eval(r#""a" != "a""#),
+            Ok(Value::Number(Number::from_i32(0)))
+        );
+        assert_eq!(
+            eval("r == { height: 20, width: 5 + 5 }"),
+            Ok(Value::Number(Number::from_i32(1)))
+        );
+        assert_eq!(
+            eval("{ ..r, width: 5 } == r"),
+            Ok(Value::Number(Number::from_i32(0)))
+        );
+        assert_eq!(eval("1 == (1,)"), Ok(Value::Number(Number::from_i32(0))));
+    }
+    #[test]
+    fn eval_div_by_zero() {
+        assert_eq!(
+            Operation::new(&"1 / (1 - 1)".into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Err(Error::Operation(OperationError::DivisionByZero))
+        );
+    }
+    #[test]
+    fn eval_overflow() {
+        let eval = |s: &str| {
+            Operation::new(&s.into())
+                .unwrap()
+                .eval(&Environment::default())
+        };
+        let overflow = Err(Error::Operation(OperationError::Overflow));
+        assert_eq!(eval("2147483647 + 1"), overflow);
+        assert_eq!(eval("-2147483648 - 1"), overflow);
+        assert_eq!(eval("65536 * 65536"), overflow);
+        assert_eq!(eval("-2147483648 / -1"), overflow);
+        assert_eq!(
+            eval("2147483647 + -2147483648"),
+            Ok(Value::Number(Number::from_i32(-1)))
+        );
+    }
+    #[test]
+    fn eval_with_precedence() {
+        assert_eq!(
+            Operation::new(&"2 * 3 + 4 * 5".into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Ok(Value::Number(Number::from_i32(2 * 3 + 4 * 5)))
+        );
+        assert_eq!(
+            Operation::new(&"10 - 4 - 3".into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Ok(Value::Number(Number::from_i32(10 - 4 - 3)))
+        );
+    }
+    #[test]
+    fn eval_bitwise() {
+        assert_eq!(
+            Operation::new(&"0b1100 & 0b1010".into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Ok(Value::Number(Number::from_i32(0b1000)))
+        );
+        assert_eq!(
+            Operation::new(&"0b1100 | 0b1010".into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Ok(Value::Number(Number::from_i32(0b1110)))
+        );
+        assert_eq!(
+            Operation::new(&"0b1100 ^ 0b1010".into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Ok(Value::Number(Number::from_i32(0b0110)))
+        );
+        assert_eq!(
+            Operation::new(&"0xFF & ~0x0F".into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Ok(Value::Number(Number::from_i32(0xF0)))
+        );
+    }
+    #[test]
+    fn eval_shift() {
+        assert_eq!(
+            Operation::new(&"1 << 4 | 1 << 0".into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Ok(Value::Number(Number::from_i32(0b1_0001)))
+        );
+        assert_eq!(
+            Operation::new(&"-16 >> 2".into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Ok(Value::Number(Number::from_i32(-4)))
+        );
+        assert_eq!(
+            Operation::new(&"1 << 32".into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Err(Error::Operation(OperationError::InvalidShift(32)))
+        );
+        assert_eq!(
+            Operation::new(&"1 >> -1".into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Err(Error::Operation(OperationError::InvalidShift(-1)))
+        );
+    }
+    #[test]
+    fn eval_concat() {
+        assert_eq!(
+            Operation::new(&r#""con" + "cat" + "!""#.into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Ok(Value::Str("concat!".to_string()))
+        );
+        assert_eq!(
+            Operation::new(&r#""a" + 1"#.into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Err(Error::Operation(OperationError::InvalidConcat))
+        );
+        assert_eq!(
+            Operation::new(&r#""a" - "b""#.into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Err(Error::Operation(OperationError::InvalidLhs))
+        );
+        assert_eq!(
+            Operation::new(&r#"1 + "b""#.into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Err(Error::Operation(OperationError::InvalidRhs))
+        );
+    }
+    #[test]
+    fn parse_with_operator_in_str() {
+        assert_eq!(
+            Operation::new(&r#""1 + 2" + "3""#.into()),
+            Ok(Operation {
+                lhs: Expression::Str(StringLiteral::new(&r#""1 + 2""#.into()).unwrap()).into(),
+                rhs: Expression::Str(StringLiteral::new(&r#""3""#.into()).unwrap()).into(),
+                op: Operator::Add
+            })
+        );
+    }
+    #[test]
     fn eval_binding() {
         let local = &mut Environment::default();
-        BindingDef::new(&"let x = 5".into()).unwrap().store(local);
+        BindingDef::new(&"let x = 5".into())
+            .unwrap()
+            .store(local)
+            .unwrap();
         assert_eq!(
             Operation::new(&"x+2".into()).unwrap().eval(local),
             Ok(Value::Number(Number::from_i32(5 + 2)))
         );
-        BindingDef::new(&"let y = 3".into()).unwrap().store(local);
+        BindingDef::new(&"let y = 3".into())
+            .unwrap()
+            .store(local)
+            .unwrap();
         assert_eq!(
             Operation::new(&"x*y".into()).unwrap().eval(local),
             Ok(Value::Number(Number::from_i32(5 * 3)))
@@ -163,9 +489,7 @@ mod tests {
         );
     }
     #[test]
-    #[should_panic]
     fn eval_block_without_last_expression() {
-        // TODO: fix it because now expression cannot parse symbols in block
         assert_eq!(
             Operation::new(&"{1+1;}+1".into())
                 .unwrap()
@@ -174,9 +498,7 @@ mod tests {
         );
     }
     #[test]
-    #[should_panic]
     fn eval_block_with_last_expression() {
-        // TODO: fix it because now expression cannot parse symbols in block
         assert_eq!(
             Operation::new(&"{let x = 1; x + 2} + 3".into())
                 .unwrap()
@@ -1,25 +1,85 @@
 use crate::internal::prelude::*;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Operator {
     Add,
     Sub,
     Mul,
     Div,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+    Eq,
+    Ne,
 }
 impl Operator {
-    pub const OP_CHAR_LIST: [char; 4] = ['+', '-', '*', '/'];
-    pub const LEN_OF_OP: usize = '+'.len_utf8();
+    /// Longer symbols come first, so that `<<` is not matched by a shorter one.
+    const ALL: [Self; 11] = [
+        Self::Shl,
+        Self::Shr,
+        Self::Eq,
+        Self::Ne,
+        Self::Add,
+        Self::Sub,
+        Self::Mul,
+        Self::Div,
+        Self::BitAnd,
+        Self::BitOr,
+        Self::BitXor,
+    ];
     pub fn new(s: &TrimmedStr) -> Result<Self, OperatorError> {
-        match s.as_ref() {
-            "+" => Ok(Self::Add),
-            "-" => Ok(Self::Sub),
-            "*" => Ok(Self::Mul),
-            "/" => Ok(Self::Div),
-            _ => Err(OperatorError::InvalidOperator),
+        Self::ALL
+            .into_iter()
+            .find(|op| op.symbol() == &**s)
+            .ok_or(OperatorError::InvalidOperator)
+    }
+    /// Find the operator which `s` starts with.
+    pub fn from_prefix(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|op| s.starts_with(op.symbol()))
+    }
+    /// Find the operator which `s` ends with.
+    pub fn from_suffix(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|op| s.ends_with(op.symbol()))
+    }
+    /// Whether the operator computes a number, so it has a compound assignment like `+=`.
+    pub const fn is_arithmetic(self) -> bool {
+        !matches!(self, Self::Eq | Self::Ne)
+    }
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::BitAnd => "&",
+            Self::BitOr => "|",
+            Self::BitXor => "^",
+            Self::Shl => "<<",
+            Self::Shr => ">>",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+        }
+    }
+    /// Operators with higher precedence bind tighter, as they do in C.
+    pub const fn precedence(self) -> u8 {
+        match self {
+            Self::BitOr => 3,
+            Self::BitXor => 4,
+            Self::BitAnd => 5,
+            Self::Eq | Self::Ne => 6,
+            Self::Shl | Self::Shr => 8,
+            Self::Add | Self::Sub => 9,
+            Self::Mul | Self::Div => 10,
         }
     }
 }
+impl std::fmt::Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
 #[cfg(test)]
 mod op_tests {
     use super::*;
@@ -40,10 +100,47 @@ mod op_tests {
         assert_eq!(Operator::new(&"/".into()), Ok(Operator::Div));
     }
     #[test]
+    fn parse_bitwise_op() {
+        assert_eq!(Operator::new(&"&".into()), Ok(Operator::BitAnd));
+        assert_eq!(Operator::new(&"|".into()), Ok(Operator::BitOr));
+        assert_eq!(Operator::new(&"^".into()), Ok(Operator::BitXor));
+    }
+    #[test]
+    fn parse_shift_op() {
+        assert_eq!(Operator::new(&"<<".into()), Ok(Operator::Shl));
+        assert_eq!(Operator::new(&">>".into()), Ok(Operator::Shr));
+    }
+    #[test]
+    fn parse_equality_op() {
+        assert_eq!(Operator::new(&"==".into()), Ok(Operator::Eq));
+        assert_eq!(Operator::new(&"!=".into()), Ok(Operator::Ne));
+    }
+    #[test]
     fn parse_invalid_operator() {
         assert_eq!(
             Operator::new(&"s".into()),
             Err(OperatorError::InvalidOperator)
         );
+        assert_eq!(
+            Operator::new(&"<".into()),
+            Err(OperatorError::InvalidOperator)
+        );
+    }
+    #[test]
+    fn from_prefix() {
+        assert_eq!(Operator::from_prefix("<< 2"), Some(Operator::Shl));
+        assert_eq!(Operator::from_prefix("- 2"), Some(Operator::Sub));
+        assert_eq!(Operator::from_prefix("=> 2"), None);
+        assert_eq!(Operator::from_prefix(""), None);
+    }
+    #[test]
+    fn precedence() {
+        assert!(Operator::Mul.precedence() > Operator::Add.precedence());
+        assert!(Operator::Add.precedence() > Operator::Shl.precedence());
+        assert!(Operator::Shr.precedence() > Operator::BitAnd.precedence());
+        assert!(Operator::BitAnd.precedence() > Operator::BitXor.precedence());
+        assert!(Operator::BitXor.precedence() > Operator::BitOr.precedence());
+        assert!(Operator::Shl.precedence() > Operator::Eq.precedence());
+        assert!(Operator::Ne.precedence() > Operator::BitAnd.precedence());
     }
 }
@@ -1,19 +1,635 @@
 use crate::internal::prelude::*;
-#[derive(Default)]
 pub struct Parser<'a> {
     environment: Environment<'a>,
+    /// The types of what the session defines, which `type_of` looks up even without the type check.
+    checker: TypeChecker,
+    /// Whether a statement which is not well-typed is rejected before it runs.
+    type_check: bool,
+    step_budget: Option<usize>,
+    /// The names defined by the programs `diagnose` checks without running, with their definitions.
+    declared: Vec<(Identifier, String)>,
+}
+impl Default for Parser<'_> {
+    fn default() -> Self {
+        Self {
+            environment: Environment::default(),
+            checker: TypeChecker::default(),
+            type_check: false,
+            step_budget: Some(Self::DEFAULT_STEP_BUDGET),
+            declared: Vec::new(),
+        }
+    }
+}
+
+/// The value of a statement typed in the REPL, which is shown like `it: Int = 3`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Evaluation {
+    /// The value written as source, like `"a"` for a string.
+    pub value: String,
+    pub ty: String,
+}
+impl std::fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} = {}", Parser::LAST_VALUE, self.ty, self.value)
+    }
+}
+
+/// A problem with a statement of a program, found by `Parser::diagnose`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Where the statement is in the program.
+    pub span: std::ops::Range<usize>,
+    pub message: String,
 }
 
 impl Parser<'_> {
+    /// The name which `evaluate` binds the value to.
+    pub const LAST_VALUE: &'static str = "it";
+    /// The loop iterations each statement may run, which stops an infinite loop.
+    pub const DEFAULT_STEP_BUDGET: usize = 1_000_000;
+    /// Check the types of statements before running them, and show the type of each value.
+    #[must_use]
+    pub fn with_type_check() -> Self {
+        Self {
+            type_check: true,
+            ..Default::default()
+        }
+    }
+    /// Set the loop iterations each statement may run, where `None` is unlimited.
+    pub const fn set_step_budget(&mut self, steps: Option<usize>) {
+        self.step_budget = steps;
+    }
     /// # Errors
     ///
     /// This function will return an error if s cannot be parsed to a valid statement.
     pub fn parse(&mut self, s: &str) -> Result<String, String> {
-        let value = Statement::new(&s.into())
-            .map_err(|err| err.to_string())?
+        let statement =
+            Statement::new(&Comment::strip(s).as_str().into()).map_err(|err| err.to_string())?;
+        self.execute(&statement)
+    }
+    /// Whether `s` is the start of a statement which goes on in the next line,
+    /// like with an unclosed bracket or string, or a trailing operator.
+    #[must_use]
+    pub fn needs_more_input(s: &str) -> bool {
+        let s = Comment::strip(s);
+        let s = s.trim_end();
+        !TopLevelChars::new(s).is_closed()
+            || Operator::from_suffix(s).is_some()
+            || s.ends_with(['=', ','])
+            || s.ends_with("=>")
+    }
+    /// Run a program of statements separated by `;`, and show the value of the last one.
+    ///
+    /// Nothing runs if a statement cannot be parsed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error with the line of the statement which fails.
+    pub fn run(&mut self, program: &str) -> Result<String, String> {
+        let program = &Comment::strip(program);
+        let statements = Block::split_statements(program)
+            .into_iter()
+            .map(|(start, s)| {
+                let start = start + s.len() - s.trim_start().len();
+                let line = program[..start].matches('\n').count() + 1;
+                Statement::new(&s.into())
+                    .map(|statement| (line, statement))
+                    .map_err(|err| format!("line {line}: {err}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut output = String::new();
+        for (line, statement) in statements {
+            output = self
+                .execute(&statement)
+                .map_err(|err| format!("line {line}: {err}"))?;
+        }
+        Ok(output)
+    }
+    /// Find the problems with each statement of a program, going on after one fails.
+    ///
+    /// The statements are parsed, type-checked and resolved, but not run, so a program
+    /// which never ends or runs out of memory cannot take the caller down with it.
+    pub fn diagnose(&mut self, program: &str) -> Vec<Diagnostic> {
+        let program = &Comment::strip(program);
+        let mut diagnostics = Vec::new();
+        for (start, s) in Block::split_statements(program) {
+            let start = start + s.len() - s.trim_start().len();
+            let span = start..start + s.trim().len();
+            let result = Statement::new(&s.into()).and_then(|statement| {
+                self.check_statement(&statement)?;
+                Ok(statement)
+            });
+            match result {
+                Ok(statement) => self.declare(&statement),
+                Err(err) => diagnostics.push(Diagnostic {
+                    // an annotation which is not met is pointed at, rather than its statement
+                    span: Self::annotation_span(&err, &program[span.clone()])
+                        .map_or(span, |inner| start + inner.start..start + inner.end),
+                    message: err.to_string(),
+                }),
+            }
+        }
+        diagnostics
+    }
+    /// Find where the annotation of the error is written in the statement, like `x: Int`
+    /// or `-> Int`.
+    fn annotation_span(err: &Error, s: &str) -> Option<std::ops::Range<usize>> {
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+        match err {
+            Error::Annotation(AnnotationError::TypeMismatch { name, .. }) => {
+                s.match_indices(name.as_str()).find_map(|(start, _)| {
+                    let end = start + name.len();
+                    let annotated = !s[..start].ends_with(is_word)
+                        && !s[end..].starts_with(is_word)
+                        && s[end..].trim_start().starts_with(':');
+                    annotated.then_some(start..end)
+                })
+            }
+            Error::Annotation(AnnotationError::OutputTypeMismatch { function, .. }) => {
+                let name = s.find(&format!("{} {function}", Keyword::Fn))?;
+                let arrow = name + s[name..].find("->")?;
+                let end = arrow + s[arrow..].find("=>")?;
+                Some(arrow..arrow + s[arrow..end].trim_end().len())
+            }
+            _ => None,
+        }
+    }
+    /// Check a statement without running it, where only the names declared before are known.
+    fn check_statement(&mut self, statement: &Statement) -> Result<(), Error> {
+        if self.type_check {
+            self.checker.check(statement)?;
+        }
+        let resolver = &mut Resolver::new(&self.environment);
+        for (name, _) in &self.declared {
+            resolver.declare(name.clone());
+        }
+        statement.resolve(resolver)
+    }
+    fn declare(&mut self, statement: &Statement) {
+        // an enum only defines its variants, so it is stored for matches to be checked against
+        if let Statement::EnumDef(enum_def) = statement {
+            enum_def.store(&mut self.environment);
+        }
+        // a definition ends with `;` like those bound by running, even a `fn` or an `enum`
+        let mut definition = statement.to_string();
+        if !definition.ends_with(';') {
+            definition.push(';');
+        }
+        for name in statement.defined_names() {
+            self.declared.push((name.clone(), definition.clone()));
+        }
+    }
+    fn execute(&mut self, statement: &Statement) -> Result<String, String> {
+        let (value, ty) = self.execute_typed(statement)?;
+        Ok(match ty {
+            Some(ty) if value != Value::Empty => format!("{value}: {ty}"),
+            _ => value.to_string(),
+        })
+    }
+    fn execute_typed(&mut self, statement: &Statement) -> Result<(Value, Option<Type>), String> {
+        self.environment.set_step_budget(self.step_budget);
+        let checked = self.checker.check(statement);
+        let ty = if self.type_check {
+            Some(checked.map_err(|err| err.to_string())?)
+        } else {
+            // a statement which is not well-typed still runs, and what it defines has no known type
+            if checked.is_err() {
+                for name in statement.defined_names() {
+                    let unknown = self.checker.fresh();
+                    self.checker.bind(name.clone(), unknown);
+                }
+            }
+            None
+        };
+        // the names and the exhaustiveness of matches are checked before anything runs
+        statement
+            .resolve(&mut Resolver::new(&self.environment))
+            .map_err(|err| err.to_string())?;
+        let value = statement
             .get_expression_in(&mut self.environment)
-            .eval(&self.environment)
+            .and_then(|expression| expression.eval(&self.environment))
             .map_err(|err| err.to_string())?;
-        Ok(value.to_string())
+        Ok((value, ty))
+    }
+    /// Run a statement typed in the REPL, and bind its value to `it` for the next one.
+    ///
+    /// The type is the inferred one with the type check, and the kind of the value otherwise.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if s cannot be parsed to a valid statement.
+    pub fn evaluate(&mut self, s: &str) -> Result<Option<Evaluation>, String> {
+        let statement =
+            Statement::new(&Comment::strip(s).as_str().into()).map_err(|err| err.to_string())?;
+        let (value, ty) = self.execute_typed(&statement)?;
+        if value == Value::Empty {
+            return Ok(None);
+        }
+        let it = Identifier::new(&Self::LAST_VALUE.into()).map_err(|err| err.to_string())?;
+        if let Statement::Expression(expression) = statement {
+            let binding_def = BindingDef::from_parts(Pattern::Binding(it.clone()), expression);
+            let checked = self.checker.check(&Statement::BindingDef(binding_def));
+            if self.type_check {
+                checked.map_err(|err| err.to_string())?;
+            }
+        }
+        let evaluation = Evaluation {
+            value: value.to_source(),
+            ty: ty.map_or_else(|| value.type_name().to_string(), |ty| ty.to_string()),
+        };
+        self.environment.insert_binding(it, value);
+        Ok(Some(evaluation))
+    }
+    /// The words which can be typed next, which are the bound names, builtins and keywords.
+    #[must_use]
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self
+            .environment
+            .names()
+            .iter()
+            .chain(self.declared.iter().map(|(name, _)| name))
+            .map(ToString::to_string)
+            .chain(Builtin::ALL.map(|builtin| builtin.name().to_string()))
+            .chain(Keyword::ALL.map(|keyword| keyword.as_str().to_string()))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+    /// The definitions of the names bound so far, like `let x = 1;`, sorted by name.
+    #[must_use]
+    pub fn definitions(&self) -> Vec<String> {
+        self.environment.definitions()
+    }
+    /// The definition of a name bound so far, like `let x = 1;`.
+    #[must_use]
+    pub fn definition(&self, name: &str) -> Option<String> {
+        let declared = || {
+            self.declared
+                .iter()
+                .rev()
+                .find(|(declared, _)| declared.as_str() == name)
+                .map(|(_, definition)| definition.clone())
+        };
+        self.environment.definition(name).or_else(declared)
+    }
+    /// Write the definitions as a program, which defines the same names when it runs.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a value cannot be written as source,
+    /// like a function which has been redefined since it was bound to another name.
+    pub fn save(&self) -> Result<String, String> {
+        // `it` is bound by the REPL itself, rather than defined by the session
+        self.environment
+            .to_source(&[Self::LAST_VALUE])
+            .map_err(|err| err.to_string())
+    }
+    /// Forget everything defined, keeping the type check and the step budget.
+    pub fn reset(&mut self) {
+        self.environment = Environment::default();
+        self.declared.clear();
+        self.checker = TypeChecker::default();
+    }
+    /// Show the tree of an expression as it is parsed, without running it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if s is not a valid expression.
+    pub fn ast(s: &str) -> Result<String, String> {
+        Expression::new(&Comment::strip(s).as_str().into())
+            .map(|expression| format!("{expression:#?}"))
+            .map_err(|err| err.to_string())
+    }
+    /// Write a program in the canonical layout, like `fmt` does, keeping its comments.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error with the line of a statement which cannot be parsed.
+    pub fn format(source: &str) -> Result<String, String> {
+        Formatter::new(source).format()
+    }
+    /// Whether the word is a keyword like `let`, which is highlighted in the REPL.
+    #[must_use]
+    pub fn is_keyword(word: &str) -> bool {
+        Keyword::new(word).is_some()
+    }
+    /// Whether the word names a builtin function like `len`.
+    #[must_use]
+    pub fn is_builtin(word: &str) -> bool {
+        Builtin::ALL.iter().any(|builtin| builtin.name() == word)
+    }
+    /// Infer the type of an expression, without running it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if s is not a valid or well-typed expression.
+    pub fn type_of(&mut self, s: &str) -> Result<String, String> {
+        let expression =
+            Expression::new(&Comment::strip(s).as_str().into()).map_err(|err| err.to_string())?;
+        self.checker
+            .check(&Statement::Expression(expression))
+            .map(|ty| ty.to_string())
+            .map_err(|err| err.to_string())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parse_with_type_check() {
+        let parser = &mut Parser::with_type_check();
+        assert_eq!(parser.parse("fn add x y => x + y"), Ok(String::new()));
+        assert_eq!(parser.parse("add 1 2"), Ok("3: Int".to_string()));
+        assert_eq!(
+            parser.parse(r#"add 1 "a""#),
+            Err("Expect a value of type `Int`, found `Str`".to_string())
+        );
+        assert_eq!(parser.type_of("add"), Ok("Fn(Int, Int) -> Int".to_string()));
+    }
+    #[test]
+    fn parse_without_type_check() {
+        let parser = &mut Parser::default();
+        assert_eq!(parser.parse("let x = 1;"), Ok(String::new()));
+        assert_eq!(parser.parse("x"), Ok("1".to_string()));
+        assert_eq!(parser.type_of("[1]"), Ok("[Int]".to_string()));
+        assert_eq!(parser.parse("let y = x;"), Ok(String::new()));
+        // the types of what is defined are known without the type check too
+        assert_eq!(parser.parse("fn f x => [x]"), Ok(String::new()));
+        assert_eq!(parser.type_of("f 2 // a list"), Ok("[Int]".to_string()));
+        assert_eq!(parser.parse(r#"let x = [1, "a"];"#), Ok(String::new()));
+        assert_eq!(parser.type_of("x"), Ok("'a".to_string()));
+    }
+    #[test]
+    fn detect_incomplete_input() {
+        assert!(Parser::needs_more_input("fn f x => {"));
+        assert!(Parser::needs_more_input("fn f x =>"));
+        assert!(Parser::needs_more_input("let x = (1 +\n 2"));
+        assert!(Parser::needs_more_input("1 *  "));
+        assert!(Parser::needs_more_input(r#"let s = "a"#));
+        assert!(Parser::needs_more_input("[1,"));
+        assert!(!Parser::needs_more_input("fn f x => {\n x\n}"));
+        assert!(!Parser::needs_more_input("let x = 1;"));
+        assert!(!Parser::needs_more_input(r#""(""#));
+    }
+    #[test]
+    fn list_definitions() {
+        let parser = &mut Parser::default();
+        parser
+            .run("enum Shape { Circle(r), Empty }; let mut n = Circle (-1); fn f (x: Int) => [x, n]; let s = \"a\";")
+            .unwrap();
+        assert_eq!(
+            parser.definitions(),
+            [
+                "enum Shape { Circle(r), Empty };",
+                "fn f (x: Int) => [x, n];",
+                "let mut n = Circle (-1);",
+                r#"let s = "a";"#,
+            ]
+        );
+        let saved = parser.save().unwrap();
+        parser.reset();
+        assert_eq!(parser.definitions(), Vec::<String>::new());
+        assert_eq!(parser.run(&saved), Ok(String::new()));
+        assert_eq!(parser.parse("f 1"), Ok("[1, Circle(-1)]".to_string()));
+        parser.reset();
+        assert!(parser.parse("s").is_err());
+    }
+    #[test]
+    fn evaluate_and_bind_it() {
+        let parser = &mut Parser::default();
+        assert_eq!(parser.evaluate("let x = 1;"), Ok(None));
+        assert_eq!(
+            parser
+                .evaluate("[x]")
+                .map(|evaluation| evaluation.unwrap().to_string()),
+            Ok("it: List = [1]".to_string())
+        );
+        assert_eq!(parser.parse("it"), Ok("[1]".to_string()));
+        // `it` is only saved when something defined uses it
+        assert_eq!(parser.save(), Ok("let x = 1;".to_string()));
+        parser.parse("fn f y => it").unwrap();
+        assert_eq!(
+            parser.save(),
+            Ok("let it = [1];\nfn f y => it;\nlet x = 1;".to_string())
+        );
+        let parser = &mut Parser::with_type_check();
+        assert_eq!(
+            parser.evaluate(r#"("a", 1)"#),
+            Ok(Some(Evaluation {
+                value: r#"("a", 1)"#.to_string(),
+                ty: "(Str, Int)".to_string()
+            }))
+        );
+        assert_eq!(
+            parser
+                .evaluate("it")
+                .map(|evaluation| evaluation.unwrap().to_string()),
+            Ok(r#"it: (Str, Int) = ("a", 1)"#.to_string())
+        );
+        assert_eq!(parser.type_of("it"), Ok("(Str, Int)".to_string()));
+    }
+    #[test]
+    fn list_names() {
+        let parser = &mut Parser::default();
+        parser.parse("let mut total = 1;").unwrap();
+        parser.parse("fn len s => 0").unwrap();
+        let names = parser.names();
+        assert!(names.iter().any(|name| name == "total"));
+        assert!(names.iter().any(|name| name == "while"));
+        assert_eq!(names.iter().filter(|name| *name == "len").count(), 1);
+    }
+    #[test]
+    fn run_program() {
+        let parser = &mut Parser::default();
+        let program = "
+            fn fact n => match n { 0 => 1, n => n * fact (n - 1) };
+            let mut sum = 0;
+            for i in range 1 4 { sum += fact i; };
+            sum
+        ";
+        assert_eq!(parser.run(program), Ok("9".to_string()));
+        assert_eq!(parser.run(""), Ok(String::new()));
+        assert_eq!(
+            parser.run("let x = 1;\nlet y = x / 0;\ny"),
+            Err("line 2: Cannot divide by zero".to_string())
+        );
+        assert_eq!(
+            parser.run("let z = 1;\n\n  let = 2;"),
+            Err("line 3: Invalid statement".to_string())
+        );
+        assert_eq!(
+            parser.run("let let = 1;"),
+            Err(
+                "line 1: `let` is a keyword, which can be used as an identifier like `r#let`"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            parser.run("fn let x => x"),
+            Err(
+                "line 1: `let` is a keyword, which can be used as an identifier like `r#let`"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            parser.run("z"),
+            Err("line 1: Identifier `z` is not defined".to_string())
+        );
+    }
+    #[test]
+    fn call_in_scope_of_definition() {
+        let parser = &mut Parser::with_type_check();
+        assert_eq!(
+            parser.run("fn outer x => { fn inner y => x + y; inner };\nlet g = outer 1;\ng 2"),
+            Ok("3: Int".to_string())
+        );
+        assert_eq!(
+            parser.run(r#"let x = 1; fn f y => x + y; { let x = "s"; f 1 }"#),
+            Ok("2: Int".to_string())
+        );
+        assert_eq!(
+            parser.run("let mut n = 1; fn add y => n + y; n = 2; add 1"),
+            Ok("3: Int".to_string())
+        );
+    }
+    #[test]
+    fn run_unbounded_recursion() {
+        let parser = &mut Parser::default();
+        assert_eq!(
+            parser.run("fn f x => f x;\nf 1"),
+            Err("line 2: Stop calling `f`, which is nested more than 200 calls deep".to_string())
+        );
+        // the calls stopped by the error are not counted anymore
+        assert_eq!(
+            parser.run("fn count n => match n { 0 => 0, n => 1 + count (n - 1) };\ncount 20"),
+            Ok("20".to_string())
+        );
+    }
+    #[test]
+    fn run_with_comments() {
+        let mut parser = Parser::default();
+        assert_eq!(
+            parser.run("// greet\nlet s = \"//\"; // not a comment in a string\nlen s // 2"),
+            Ok("2".to_string())
+        );
+        assert!(Parser::needs_more_input("let x = // later"));
+        assert_eq!(
+            Parser::format("let x=1;//one\nx"),
+            Ok("let x = 1; //one\nx\n".to_string())
+        );
+    }
+    #[test]
+    fn diagnose_each_statement() {
+        let mut parser = Parser::with_type_check();
+        let program = "let x = 1;\nlet = 2;\nx + \"a\";\n// fine\nfn f y => y";
+        let diagnostics = parser.diagnose(program);
+        let spans: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| &program[diagnostic.span.clone()])
+            .collect();
+        assert_eq!(spans, vec!["let = 2;", "x + \"a\";"]);
+        assert_eq!(diagnostics[0].message, "Invalid statement");
+        assert_eq!(parser.definition("x"), Some("let x = 1;".to_string()));
+        assert_eq!(parser.definition("f"), Some("fn f y => y;".to_string()));
+        assert_eq!(parser.definition("y"), None);
+    }
+    #[test]
+    fn diagnose_annotations() {
+        let mut parser = Parser::with_type_check();
+        let program = "let x: Str = 1;\nfn f (y: Int) -> Str => y;\nf \"a\"";
+        let spans: Vec<_> = parser
+            .diagnose(program)
+            .iter()
+            .map(|diagnostic| &program[diagnostic.span.clone()])
+            .collect();
+        assert_eq!(spans, vec!["x", "-> Str", "f \"a\""]);
+    }
+    #[test]
+    fn diagnose_without_running() {
+        let mut parser = Parser::with_type_check();
+        let program = "fn f x => f x;\nf 1;\nwhile 1 { };\nfor i in range 0 2000000000 { i }";
+        assert_eq!(parser.diagnose(program), vec![]);
+        let program = "enum Bit { Zero, One };\nmatch One { One => 1 }";
+        let diagnostics = parser.diagnose(program);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Patterns not covered: Zero");
+        assert_eq!(
+            parser.definition("Bit"),
+            Some("enum Bit { Zero, One };".to_string())
+        );
+    }
+    #[test]
+    fn parse_loops() {
+        let parser = &mut Parser::with_type_check();
+        assert_eq!(parser.parse("let mut n = 0;"), Ok(String::new()));
+        assert_eq!(
+            parser.parse("for i in range 0 4 { n += i; }"),
+            Ok(String::new())
+        );
+        assert_eq!(
+            parser.parse("while 1 { n -= 1; match n { 0 => break n, _ => continue } }"),
+            Ok("0: Int".to_string())
+        );
+        // a loop which may run out gives `Empty`, so `break` cannot give anything else
+        assert_eq!(
+            parser.run("let r = while 0 { break 1; };\nr + 1"),
+            Err("line 1: Expect a value of type `Empty`, found `Int`".to_string())
+        );
+        assert_eq!(
+            parser.parse("for i in [1, 2] { break i }"),
+            Err("Expect a value of type `Empty`, found `Int`".to_string())
+        );
+        assert_eq!(
+            parser.parse("break"),
+            Err("`break` outside of a loop".to_string())
+        );
+        parser.set_step_budget(Some(10));
+        assert_eq!(
+            parser.parse("while 1 { }"),
+            Err("Stop the loop, which runs out of the step budget".to_string())
+        );
+    }
+    #[test]
+    fn parse_return() {
+        let parser = &mut Parser::with_type_check();
+        assert_eq!(
+            parser.parse("fn first_odd xs => { for x in xs { match x & 1 { 1 => { return x; }, _ => 0 } }; return 0; }"),
+            Ok(String::new())
+        );
+        assert_eq!(
+            parser.parse("fn sign x => { return match x { 0 => 0, _ => 1 }; -1 }"),
+            Ok(String::new())
+        );
+        assert_eq!(parser.parse("sign 5"), Ok("1: Int".to_string()));
+        assert_eq!(
+            parser.parse(r#"fn name x => { return "x"; 1 }"#),
+            Err("Expect a value of type `Str`, found `Int`".to_string())
+        );
+        assert_eq!(
+            parser.parse("return 1;"),
+            Err("`return` outside of a function".to_string())
+        );
+        let parser = &mut Parser::default();
+        assert_eq!(
+            parser.parse("return 1;"),
+            Err("`return` outside of a function".to_string())
+        );
+    }
+    #[test]
+    fn parse_assignment() {
+        let parser = &mut Parser::with_type_check();
+        assert_eq!(parser.parse("let mut n = 0;"), Ok(String::new()));
+        assert_eq!(parser.parse("{ n += 2; n *= 3; }"), Ok(String::new()));
+        assert_eq!(parser.parse("n"), Ok("6: Int".to_string()));
+        assert_eq!(
+            parser.parse(r#"n = "a";"#),
+            Err("Expect a value of type `Int`, found `Str`".to_string())
+        );
+        assert_eq!(parser.parse("let m = n;"), Ok(String::new()));
+        assert_eq!(
+            parser.parse("m = 1;"),
+            Err("Cannot assign twice to `m`, which is not declared with `let mut`".to_string())
+        );
     }
 }
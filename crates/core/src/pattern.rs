@@ -0,0 +1,628 @@
+use crate::internal::prelude::*;
+/// The left-hand side of a `let`, a parameter of a function, or an arm of a `match`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Pattern {
+    /// An identifier, unless it names a variant without fields like `None`.
+    Binding(Identifier),
+    Tuple(Vec<Pattern>),
+    Literal(Value),
+    Constructor {
+        name: Identifier,
+        fields: Vec<Pattern>,
+    },
+    Wildcard,
+    /// A pattern with a type annotation like `x: Int`.
+    Annotated {
+        pattern: Box<Pattern>,
+        ty: Type,
+    },
+}
+impl Pattern {
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        if let Some((colon, _)) = TopLevelChars::new(s).find(|&(_, c)| c == ':') {
+            return Ok(Self::Annotated {
+                pattern: Self::new(&s[..colon].into())?.into(),
+                ty: Type::new(&s[colon + ':'.len_utf8()..].into())?,
+            });
+        }
+        if &**s == "_" {
+            return Ok(Self::Wildcard);
+        }
+        if let Ok(number) = Number::new(s) {
+            return Ok(Self::Literal(Value::Number(number)));
+        }
+        if s.starts_with('"') {
+            let s = StringLiteral::new(s)?;
+            return Ok(Self::Literal(Value::Str(s.inner().to_string())));
+        }
+        if let Some(inner) = s.strip_wrapping('(', ')') {
+            if !TopLevelChars::new(&inner).any(|(_, c)| c == ',') {
+                return Self::new(&inner);
+            }
+            return Ok(Self::Tuple(Self::new_list(&inner)?));
+        }
+        if let Some(nth) = s.find('(') {
+            let Some(fields) = TrimmedStr::new(&s[nth..]).strip_wrapping('(', ')') else {
+                return Err(PatternError::InvalidPattern)?;
+            };
+            return Ok(Self::Constructor {
+                name: Identifier::new(&s[..nth].into())?,
+                fields: Self::new_list(&fields)?,
+            });
+        }
+        Ok(Self::Binding(Identifier::new(s)?))
+    }
+    fn new_list(s: &TrimmedStr) -> Result<Vec<Self>, Error> {
+        s.split_list().iter().map(Self::new).collect()
+    }
+    /// Declare the bound names in `resolver`, where the constructors must be defined.
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        match self {
+            Self::Binding(name) => {
+                if Self::nullary(name, resolver.env()).is_none() {
+                    resolver.declare(name.clone());
+                }
+            }
+            Self::Wildcard | Self::Literal(_) => (),
+            Self::Tuple(patterns) => {
+                for pattern in patterns {
+                    pattern.resolve(resolver)?;
+                }
+            }
+            Self::Annotated { pattern, .. } => pattern.resolve(resolver)?,
+            Self::Constructor { name, fields } => {
+                resolver.lookup(name)?;
+                for field in fields {
+                    field.resolve(resolver)?;
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Bind the names in the pattern, where `rule` is the one reported if a name is never used.
+    pub fn lint(&self, linter: &mut Linter, rule: Rule) {
+        match self {
+            Self::Binding(name) => linter.declare(name, rule),
+            Self::Wildcard | Self::Literal(_) => (),
+            Self::Tuple(patterns) => {
+                for pattern in patterns {
+                    pattern.lint(linter, rule);
+                }
+            }
+            Self::Annotated { pattern, .. } => pattern.lint(linter, rule),
+            Self::Constructor { fields, .. } => {
+                for field in fields {
+                    field.lint(linter, rule);
+                }
+            }
+        }
+    }
+    /// The identifiers in the pattern, which are the names it binds unless one names a variant.
+    pub fn identifiers(&self) -> Vec<&Identifier> {
+        match self {
+            Self::Binding(name) => vec![name],
+            Self::Wildcard | Self::Literal(_) => vec![],
+            Self::Tuple(patterns)
+            | Self::Constructor {
+                fields: patterns, ..
+            } => patterns.iter().flat_map(Self::identifiers).collect(),
+            Self::Annotated { pattern, .. } => pattern.identifiers(),
+        }
+    }
+    /// Check the pattern against the type of the values to match, and get the types of the bound names.
+    pub fn infer(
+        &self,
+        checker: &mut TypeChecker,
+        ty: &Type,
+    ) -> Result<Vec<(Identifier, Type)>, Error> {
+        let mut bindings = Vec::new();
+        self.infer_into(checker, ty, &mut bindings)?;
+        Ok(bindings)
+    }
+    fn infer_into(
+        &self,
+        checker: &mut TypeChecker,
+        ty: &Type,
+        bindings: &mut Vec<(Identifier, Type)>,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Binding(name) => match checker.constructor(name) {
+                Some((fields, constructed)) if fields.is_empty() => {
+                    checker.unify(ty, &constructed)?;
+                }
+                _ => bindings.push((name.clone(), ty.clone())),
+            },
+            Self::Wildcard => (),
+            Self::Literal(Value::Str(_)) => checker.unify(ty, &Type::Str)?,
+            Self::Literal(_) => checker.unify(ty, &Type::Int)?,
+            Self::Tuple(patterns) => {
+                let types = patterns.iter().map(|_| checker.fresh()).collect::<Vec<_>>();
+                checker.unify(ty, &Type::Tuple(types.clone()))?;
+                for (pattern, ty) in patterns.iter().zip(&types) {
+                    pattern.infer_into(checker, ty, bindings)?;
+                }
+            }
+            Self::Annotated {
+                pattern,
+                ty: expected,
+            } => {
+                checker
+                    .unify(expected, ty)
+                    .map_err(|_| AnnotationError::TypeMismatch {
+                        name: pattern.to_string(),
+                        expected: expected.to_string(),
+                        found: checker.resolve(ty).normalize().to_string(),
+                    })?;
+                pattern.infer_into(checker, expected, bindings)?;
+            }
+            Self::Constructor { name, fields } => {
+                let Some((types, constructed)) = checker.constructor(name) else {
+                    return Err(PatternError::UnknownConstructor(name.to_string()))?;
+                };
+                if types.len() != fields.len() {
+                    return Err(PatternError::WrongFieldCount {
+                        expected: types.len(),
+                        found: fields.len(),
+                    })?;
+                }
+                checker.unify(ty, &constructed)?;
+                for (field, ty) in fields.iter().zip(&types) {
+                    field.infer_into(checker, ty, bindings)?;
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Match the value and bind what the identifiers match in `env`.
+    ///
+    /// Nothing is bound if the value does not match.
+    pub fn bind(&self, value: Value, env: &mut Environment) -> Result<(), Error> {
+        let mut bindings = Vec::new();
+        self.destructure(value, env, &mut bindings)?;
+        for (name, value) in bindings {
+            env.insert_binding(name, value);
+        }
+        Ok(())
+    }
+    /// Like `bind`, but the bound names can be assigned to later.
+    pub fn bind_mutable(&self, value: Value, env: &mut Environment) -> Result<(), Error> {
+        let mut bindings = Vec::new();
+        self.destructure(value, env, &mut bindings)?;
+        for (name, value) in bindings {
+            env.insert_mutable_binding(name, value);
+        }
+        Ok(())
+    }
+    /// Get the bindings if the value matches, where an error means the pattern itself is invalid.
+    pub fn try_match(
+        &self,
+        value: Value,
+        env: &Environment,
+    ) -> Result<Option<Vec<(Identifier, Value)>>, Error> {
+        let mut bindings = Vec::new();
+        match self.destructure(value, env, &mut bindings) {
+            Ok(()) => Ok(Some(bindings)),
+            Err(
+                Error::Pattern(PatternError::TupleLengthMismatch { .. } | PatternError::NoMatch(_))
+                | Error::Value(ValueError::TypeMismatch { .. }),
+            ) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+    pub fn contains_literal(&self) -> bool {
+        match self {
+            Self::Literal(_) => true,
+            Self::Tuple(patterns)
+            | Self::Constructor {
+                fields: patterns, ..
+            } => patterns.iter().any(Self::contains_literal),
+            Self::Annotated { pattern, .. } => pattern.contains_literal(),
+            Self::Binding(_) | Self::Wildcard => false,
+        }
+    }
+    /// Whether the pattern matches any value of the shape it expects.
+    pub fn is_irrefutable(&self, env: &Environment) -> bool {
+        match self {
+            Self::Wildcard => true,
+            Self::Binding(name) => Self::nullary(name, env).is_none(),
+            Self::Tuple(patterns) => patterns.iter().all(|pattern| pattern.is_irrefutable(env)),
+            Self::Annotated { pattern, .. } => pattern.is_irrefutable(env),
+            Self::Literal(_) | Self::Constructor { .. } => false,
+        }
+    }
+    /// Get the constructor if the pattern matches every value of its variant.
+    pub fn covered_variant(&self, env: &Environment) -> Result<Option<Constructor>, Error> {
+        match self {
+            Self::Binding(name) => Ok(Self::nullary(name, env)),
+            Self::Constructor { name, fields } => {
+                let constructor = Self::constructor(name, fields.len(), env)?;
+                let covered = fields.iter().all(|field| field.is_irrefutable(env));
+                Ok(covered.then_some(constructor))
+            }
+            Self::Annotated { pattern, .. } => pattern.covered_variant(env),
+            _ => Ok(None),
+        }
+    }
+    fn destructure(
+        &self,
+        value: Value,
+        env: &Environment,
+        bindings: &mut Vec<(Identifier, Value)>,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Binding(name) => match Self::nullary(name, env) {
+                Some(constructor) => {
+                    Self::destructure_variant(&constructor, &[], value, env, bindings)?;
+                }
+                None => bindings.push((name.clone(), value)),
+            },
+            Self::Wildcard => (),
+            Self::Literal(literal) if *literal == value => (),
+            Self::Literal(_) => return Err(PatternError::NoMatch(value.to_string()))?,
+            Self::Tuple(patterns) => {
+                let Value::Tuple(values) = value else {
+                    return Err(ValueError::TypeMismatch {
+                        expected: "Tuple",
+                        found: value.type_name(),
+                    })?;
+                };
+                if patterns.len() != values.len() {
+                    return Err(PatternError::TupleLengthMismatch {
+                        expected: patterns.len(),
+                        found: values.len(),
+                    })?;
+                }
+                for (pattern, value) in patterns.iter().zip(values) {
+                    pattern.destructure(value, env, bindings)?;
+                }
+            }
+            Self::Constructor { name, fields } => {
+                let constructor = Self::constructor(name, fields.len(), env)?;
+                Self::destructure_variant(&constructor, fields, value, env, bindings)?;
+            }
+            Self::Annotated { pattern, ty } => {
+                if !value.is_of_type(ty) {
+                    return Err(AnnotationError::TypeMismatch {
+                        name: pattern.to_string(),
+                        expected: ty.to_string(),
+                        found: value.type_name().to_string(),
+                    })?;
+                }
+                pattern.destructure(value, env, bindings)?;
+            }
+        }
+        Ok(())
+    }
+    fn destructure_variant(
+        constructor: &Constructor,
+        patterns: &[Self],
+        value: Value,
+        env: &Environment,
+        bindings: &mut Vec<(Identifier, Value)>,
+    ) -> Result<(), Error> {
+        let Value::Variant(found, values) = value else {
+            return Err(ValueError::TypeMismatch {
+                expected: "Enum",
+                found: value.type_name(),
+            })?;
+        };
+        if found != *constructor {
+            return Err(PatternError::NoMatch(
+                Value::Variant(found, values).to_string(),
+            ))?;
+        }
+        for (pattern, value) in patterns.iter().zip(values) {
+            pattern.destructure(value, env, bindings)?;
+        }
+        Ok(())
+    }
+    /// Get the constructor of a variant with `arity` fields.
+    fn constructor(
+        name: &Identifier,
+        arity: usize,
+        env: &Environment,
+    ) -> Result<Constructor, Error> {
+        let constructor = match name.try_get_value_from(env) {
+            Ok(Value::Function(Function::Constructor(constructor))) => constructor,
+            _ => match Self::nullary(name, env) {
+                Some(constructor) => constructor,
+                None => return Err(PatternError::UnknownConstructor(name.to_string()))?,
+            },
+        };
+        if constructor.arity() != arity {
+            return Err(PatternError::WrongFieldCount {
+                expected: constructor.arity(),
+                found: arity,
+            })?;
+        }
+        Ok(constructor)
+    }
+    /// Get the constructor if `name` is a variant without fields.
+    fn nullary(name: &Identifier, env: &Environment) -> Option<Constructor> {
+        match name.try_get_value_from(env) {
+            Ok(Value::Variant(constructor, fields))
+                if fields.is_empty() && constructor.name() == name =>
+            {
+                Some(constructor)
+            }
+            _ => None,
+        }
+    }
+}
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fmt_list = |patterns: &[Self], f: &mut std::fmt::Formatter<'_>| {
+            for (i, pattern) in patterns.iter().enumerate() {
+                write!(f, "{}{pattern}", if i > 0 { ", " } else { "" })?;
+            }
+            Ok(())
+        };
+        match self {
+            Self::Binding(name) => write!(f, "{name}"),
+            Self::Tuple(patterns) => {
+                write!(f, "(")?;
+                fmt_list(patterns, f)?;
+                write!(f, "{})", if patterns.len() == 1 { "," } else { "" })
+            }
+            Self::Literal(Value::Str(s)) => write!(f, "{s:?}"),
+            Self::Literal(value) => write!(f, "{value}"),
+            Self::Constructor { name, fields } => {
+                write!(f, "{name}(")?;
+                fmt_list(fields, f)?;
+                write!(f, ")")
+            }
+            Self::Wildcard => write!(f, "_"),
+            Self::Annotated { pattern, ty } => write!(f, "{pattern}: {ty}"),
+        }
+    }
+}
+impl TryFrom<&str> for Pattern {
+    type Error = Error;
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::new(&s.into())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn shapes() -> Environment<'static> {
+        let mut env = Environment::default();
+        EnumDef::new(&"enum Shape { Circle(r), Rect(w, h), Empty }".into())
+            .unwrap()
+            .store(&mut env);
+        env
+    }
+    fn value(s: &str, env: &Environment) -> Value {
+        Expression::new(&s.into()).unwrap().eval(env).unwrap()
+    }
+    #[test]
+    fn parse_binding() {
+        assert_eq!(
+            Pattern::new(&"x".into()),
+            Ok(Pattern::Binding("x".try_into().unwrap()))
+        );
+        assert_eq!(
+            Pattern::new(&"(x)".into()),
+            Ok(Pattern::Binding("x".try_into().unwrap()))
+        );
+    }
+    #[test]
+    fn parse_tuple() {
+        assert_eq!(
+            Pattern::new(&"(a, (b, _),)".into()),
+            Ok(Pattern::Tuple(vec![
+                Pattern::Binding("a".try_into().unwrap()),
+                Pattern::Tuple(vec![
+                    Pattern::Binding("b".try_into().unwrap()),
+                    Pattern::Wildcard
+                ])
+            ]))
+        );
+        assert_eq!(
+            Pattern::new(&"(a,)".into()),
+            Ok(Pattern::Tuple(vec![Pattern::Binding(
+                "a".try_into().unwrap()
+            )]))
+        );
+    }
+    #[test]
+    fn parse_literal() {
+        assert_eq!(
+            Pattern::new(&"-1".into()),
+            Ok(Pattern::Literal(Value::from(-1)))
+        );
+        assert_eq!(
+            Pattern::new(&r#""a""#.into()),
+            Ok(Pattern::Literal(Value::Str("a".to_string())))
+        );
+    }
+    #[test]
+    fn parse_constructor() {
+        assert_eq!(
+            Pattern::new(&"Rect(w, 0)".into()),
+            Ok(Pattern::Constructor {
+                name: "Rect".try_into().unwrap(),
+                fields: vec![
+                    Pattern::Binding("w".try_into().unwrap()),
+                    Pattern::Literal(Value::from(0))
+                ]
+            })
+        );
+    }
+    #[test]
+    fn parse_annotated() {
+        assert_eq!(
+            Pattern::new(&"(a, b): (Int, [Str])".into()),
+            Ok(Pattern::Annotated {
+                pattern: Pattern::new(&"(a, b)".into()).unwrap().into(),
+                ty: Type::Tuple(vec![Type::Int, Type::list(Type::Str)])
+            })
+        );
+        assert_eq!(
+            Pattern::new(&"(x: Int)".into()),
+            Ok(Pattern::Annotated {
+                pattern: Pattern::new(&"x".into()).unwrap().into(),
+                ty: Type::Int
+            })
+        );
+    }
+    #[test]
+    fn display() {
+        for s in [r#"(a, (b,), _, "s", -1)"#, "Rect(w, h): Shape"] {
+            assert_eq!(Pattern::new(&s.into()).unwrap().to_string(), s);
+        }
+    }
+    #[test]
+    fn bind_annotated() {
+        let env = &mut Environment::default();
+        assert_eq!(
+            Pattern::new(&"(a: Int, b)".into())
+                .unwrap()
+                .bind(Value::Tuple(vec![Value::from(1), Value::from(2)]), env),
+            Ok(())
+        );
+        assert_eq!(
+            Pattern::new(&"(a: Str, b)".into())
+                .unwrap()
+                .bind(Value::Tuple(vec![Value::from(1), Value::from(2)]), env),
+            Err(Error::Annotation(AnnotationError::TypeMismatch {
+                name: "a".to_string(),
+                expected: "Str".to_string(),
+                found: "Int".to_string()
+            }))
+        );
+    }
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(
+            Pattern::new(&"1 + 1".into()),
+            Err(Error::Identifier(IdentifierError::StartWithNonLetter))
+        );
+        assert_eq!(
+            Pattern::new(&"(a, , b)".into()),
+            Err(Error::Identifier(IdentifierError::Empty))
+        );
+        assert_eq!(
+            Pattern::new(&"()".into()),
+            Err(Error::Identifier(IdentifierError::Empty))
+        );
+        assert_eq!(
+            Pattern::new(&"Rect(w, h".into()),
+            Err(Error::Pattern(PatternError::InvalidPattern))
+        );
+    }
+    #[test]
+    fn bind() {
+        let env = &mut Environment::default();
+        Pattern::new(&"(a, (b, _))".into())
+            .unwrap()
+            .bind(
+                Value::Tuple(vec![
+                    Value::from(1),
+                    Value::Tuple(vec![Value::from(2), Value::from(3)]),
+                ]),
+                env,
+            )
+            .unwrap();
+        assert_eq!(
+            env.get_from_self_and_parent(&"a".try_into().unwrap()),
+            Some(NamedValue::Binding(Value::from(1)))
+        );
+        assert_eq!(
+            env.get_from_self_and_parent(&"b".try_into().unwrap()),
+            Some(NamedValue::Binding(Value::from(2)))
+        );
+    }
+    #[test]
+    fn bind_mismatched_shape() {
+        let env = &mut Environment::default();
+        assert_eq!(
+            Pattern::new(&"(a, b)".into())
+                .unwrap()
+                .bind(Value::Tuple(vec![Value::from(1)]), env),
+            Err(Error::Pattern(PatternError::TupleLengthMismatch {
+                expected: 2,
+                found: 1
+            }))
+        );
+        assert_eq!(
+            Pattern::new(&"(a, (b, c))".into())
+                .unwrap()
+                .bind(Value::Tuple(vec![Value::from(1), Value::from(2)]), env),
+            Err(Error::Value(ValueError::TypeMismatch {
+                expected: "Tuple",
+                found: "Int"
+            }))
+        );
+        assert_eq!(env.get_from_self_and_parent(&"a".try_into().unwrap()), None);
+    }
+    #[test]
+    fn try_match() {
+        let env = &shapes();
+        let rect = value("Rect 2 3", env);
+        assert_eq!(
+            Pattern::new(&"Rect(w, _)".into())
+                .unwrap()
+                .try_match(rect.clone(), env),
+            Ok(Some(vec![("w".try_into().unwrap(), Value::from(2))]))
+        );
+        assert_eq!(
+            Pattern::new(&"Circle(r)".into())
+                .unwrap()
+                .try_match(rect.clone(), env),
+            Ok(None)
+        );
+        assert_eq!(
+            Pattern::new(&"Rect(2, 4)".into())
+                .unwrap()
+                .try_match(rect.clone(), env),
+            Ok(None)
+        );
+        assert_eq!(
+            Pattern::new(&"Empty".into())
+                .unwrap()
+                .try_match(value("Empty", env), env),
+            Ok(Some(vec![]))
+        );
+        assert_eq!(
+            Pattern::new(&"Empty".into()).unwrap().try_match(rect, env),
+            Ok(None)
+        );
+        assert_eq!(
+            Pattern::new(&"Circle(r)".into())
+                .unwrap()
+                .try_match(Value::from(1), env),
+            Ok(None)
+        );
+    }
+    #[test]
+    fn try_match_invalid_constructor() {
+        let env = &shapes();
+        assert_eq!(
+            Pattern::new(&"Square(a)".into())
+                .unwrap()
+                .try_match(Value::from(1), env),
+            Err(Error::Pattern(PatternError::UnknownConstructor(
+                "Square".to_string()
+            )))
+        );
+        assert_eq!(
+            Pattern::new(&"Rect(w)".into())
+                .unwrap()
+                .try_match(Value::from(1), env),
+            Err(Error::Pattern(PatternError::WrongFieldCount {
+                expected: 2,
+                found: 1
+            }))
+        );
+    }
+    #[test]
+    fn is_irrefutable() {
+        let env = &shapes();
+        let is_irrefutable = |s: &str| Pattern::new(&s.into()).unwrap().is_irrefutable(env);
+        assert!(is_irrefutable("(a, _)"));
+        assert!(!is_irrefutable("Empty"));
+        assert!(!is_irrefutable("(a, 1)"));
+        assert!(!is_irrefutable("Circle(r)"));
+    }
+}
@@ -0,0 +1,238 @@
+use crate::internal::prelude::*;
+use std::collections::BTreeMap;
+
+/// A record literal like `{ width: 10 }`, or a functional update like `{ ..r, width: 5 }`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Record {
+    base: Option<Box<Expression>>,
+    fields: Vec<(Identifier, Expression)>,
+}
+impl Record {
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        let Some(inner) = s.strip_wrapping('{', '}') else {
+            return Err(RecordError::NotFound)?;
+        };
+        let mut elements = inner.split_list();
+        // `{}` is an empty block rather than an empty record
+        if elements.is_empty() {
+            return Err(RecordError::NotFound)?;
+        }
+        let base = match elements[0].strip_prefix("..") {
+            Some(base) => {
+                let base = Expression::new(&base.into())?;
+                elements.remove(0);
+                Some(base.into())
+            }
+            None => None,
+        };
+        let mut fields: Vec<(Identifier, Expression)> = Vec::new();
+        for element in elements {
+            if element.starts_with("..") {
+                return Err(RecordError::MisplacedBase)?;
+            }
+            let Some((name, value)) = element.split_once(':') else {
+                return Err(RecordError::NotFound)?;
+            };
+            let name = Identifier::new(&name.into())?;
+            if fields.iter().any(|(field, _)| *field == name) {
+                return Err(RecordError::DuplicateField(name.to_string()))?;
+            }
+            let value = match Expression::new(&value.into())? {
+                Expression::Empty => Err(RecordError::MissingValue(name.to_string()))?,
+                value => value,
+            };
+            fields.push((name, value));
+        }
+        Ok(Self { base, fields })
+    }
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        if let Some(base) = &self.base {
+            base.resolve(resolver)?;
+        }
+        for (_, expression) in &self.fields {
+            expression.resolve(resolver)?;
+        }
+        Ok(())
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        if let Some(base) = &self.base {
+            base.lint(linter);
+        }
+        for (_, expression) in &self.fields {
+            expression.lint(linter);
+        }
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<Type, Error> {
+        let mut fields = BTreeMap::new();
+        for (name, expression) in &self.fields {
+            fields.insert(name.to_string(), expression.infer(checker)?);
+        }
+        let Some(base) = &self.base else {
+            return Ok(Type::Record(fields));
+        };
+        let base = base.infer(checker)?;
+        let mut record = match checker.resolve(&base) {
+            Type::Record(record) => record,
+            Type::Var(_) => match fields.keys().next() {
+                // the other fields are unknown, so no record type can be inferred from these
+                Some(name) => Err(TypeError::UnknownRecord(name.clone()))?,
+                None => return Ok(base),
+            },
+            found => Err(TypeError::Mismatch {
+                expected: "Record".to_string(),
+                found: found.normalize().to_string(),
+            })?,
+        };
+        for (name, found) in fields {
+            let expected = record
+                .get(&name)
+                .ok_or_else(|| TypeError::FieldNotFound(name.clone()))?;
+            checker.unify(expected, &found)?;
+            record.insert(name, found);
+        }
+        Ok(Type::Record(record))
+    }
+    pub fn eval(&self, env: &Environment) -> Result<Value, Error> {
+        let mut record = match &self.base {
+            Some(base) => base.eval(env)?.try_into_record()?,
+            None => BTreeMap::new(),
+        };
+        for (name, expression) in &self.fields {
+            // an update replaces the fields of the base, rather than adding new ones
+            if self.base.is_some() && !record.contains_key(name.as_str()) {
+                return Err(RecordError::FieldNotFound(name.to_string()))?;
+            }
+            record.insert(name.to_string(), expression.eval(env)?);
+        }
+        Ok(Value::Record(record))
+    }
+}
+impl std::fmt::Display for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{")?;
+        if let Some(base) = &self.base {
+            write!(f, " ..")?;
+            base.fmt(f)?;
+        }
+        for (i, (name, value)) in self.fields.iter().enumerate() {
+            let separator = if i > 0 || self.base.is_some() {
+                ","
+            } else {
+                ""
+            };
+            write!(f, "{separator} {name}: ")?;
+            value.fmt(f)?;
+        }
+        write!(f, " }}")
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parse_record() {
+        assert_eq!(
+            Record::new(&"{ width: 10, height: x + 1, }".into()),
+            Ok(Record {
+                base: None,
+                fields: vec![
+                    (
+                        "width".try_into().unwrap(),
+                        Expression::Number(Number::from_i32(10))
+                    ),
+                    (
+                        "height".try_into().unwrap(),
+                        Expression::Operation(Operation::new(&"x + 1".into()).unwrap())
+                    ),
+                ]
+            })
+        );
+    }
+    #[test]
+    fn parse_update() {
+        assert_eq!(
+            Record::new(&"{ ..r, width: 5 }".into()),
+            Ok(Record {
+                base: Some(Expression::Binding("r".try_into().unwrap()).into()),
+                fields: vec![(
+                    "width".try_into().unwrap(),
+                    Expression::Number(Number::from_i32(5))
+                )]
+            })
+        );
+        assert_eq!(
+            Record::new(&"{ width: 5, ..r }".into()),
+            Err(Error::Record(RecordError::MisplacedBase))
+        );
+    }
+    #[test]
+    fn parse_non_record() {
+        assert_eq!(
+            Record::new(&"{}".into()),
+            Err(Error::Record(RecordError::NotFound))
+        );
+        assert_eq!(
+            Record::new(&"{ let x = 1; x }".into()),
+            Err(Error::Record(RecordError::NotFound))
+        );
+        assert_eq!(
+            Record::new(&"(a: 1)".into()),
+            Err(Error::Record(RecordError::NotFound))
+        );
+    }
+    #[test]
+    fn parse_invalid_fields() {
+        assert_eq!(
+            Record::new(&"{ a: 1, a: 2 }".into()),
+            Err(Error::Record(RecordError::DuplicateField("a".to_string())))
+        );
+        assert_eq!(
+            Record::new(&"{ a: }".into()),
+            Err(Error::Record(RecordError::MissingValue("a".to_string())))
+        );
+        assert_eq!(
+            Record::new(&"{ 1: 1 }".into()),
+            Err(Error::Identifier(IdentifierError::StartWithNonLetter))
+        );
+    }
+    #[test]
+    fn eval_record() {
+        assert_eq!(
+            Record::new(&"{ b: 1 + 1, a: 1 }".into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Ok(Value::Record(BTreeMap::from([
+                ("a".to_string(), Value::from(1)),
+                ("b".to_string(), Value::from(2))
+            ])))
+        );
+    }
+    #[test]
+    fn eval_update() {
+        let env = &mut Environment::default();
+        BindingDef::new(&"let r = { width: 10, height: 20 }".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        assert_eq!(
+            Record::new(&"{ ..r, width: 5 }".into()).unwrap().eval(env),
+            Ok(Value::Record(BTreeMap::from([
+                ("height".to_string(), Value::from(20)),
+                ("width".to_string(), Value::from(5))
+            ])))
+        );
+        assert_eq!(
+            Record::new(&"{ ..r, depth: 5 }".into()).unwrap().eval(env),
+            Err(Error::Record(RecordError::FieldNotFound(
+                "depth".to_string()
+            )))
+        );
+        assert_eq!(
+            Record::new(&"{ ..1, width: 5 }".into()).unwrap().eval(env),
+            Err(Error::Value(ValueError::TypeMismatch {
+                expected: "Record",
+                found: "Int"
+            }))
+        );
+    }
+}
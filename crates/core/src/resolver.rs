@@ -0,0 +1,92 @@
+use crate::internal::prelude::*;
+use std::collections::HashSet;
+
+/// Check the names used in a function when it is defined, rather than when it is called.
+///
+/// A name is resolved if it is bound in the function, in `env`, or is a builtin.
+pub struct Resolver<'env> {
+    env: &'env Environment<'env>,
+    scopes: Vec<HashSet<Identifier>>,
+    /// The names resolved in `env`, which a function captures when it is defined.
+    free: Vec<Identifier>,
+}
+impl<'env> Resolver<'env> {
+    pub fn new(env: &'env Environment<'env>) -> Self {
+        Self {
+            env,
+            scopes: vec![HashSet::new()],
+            free: Vec::new(),
+        }
+    }
+    pub const fn env(&self) -> &Environment<'env> {
+        self.env
+    }
+    pub fn declare(&mut self, name: Identifier) {
+        self.scopes.last_mut().map(|scope| scope.insert(name));
+    }
+    pub fn lookup(&mut self, name: &Identifier) -> Result<(), ResolveError> {
+        if self.scopes.iter().any(|scope| scope.contains(name)) {
+            return Ok(());
+        }
+        if self.env.get_from_self_and_parent(name).is_some() {
+            if !self.free.contains(name) {
+                self.free.push(name.clone());
+            }
+            return Ok(());
+        }
+        if Builtin::new(name).is_some() {
+            return Ok(());
+        }
+        Err(ResolveError::UnknownIdentifier(name.to_string()))
+    }
+    /// The names which are not declared in what is resolved, but bound in `env`.
+    pub fn free(&self) -> &[Identifier] {
+        &self.free
+    }
+    /// Run `f` in a new scope, where what it declares is dropped after.
+    pub fn scoped<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, Error>) -> Result<T, Error> {
+        self.scopes.push(HashSet::new());
+        let result = f(self);
+        self.scopes.pop();
+        result
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn resolve(s: &str, env: &Environment) -> Result<(), Error> {
+        let statement = Statement::new(&s.into())?;
+        statement.resolve(&mut Resolver::new(env))
+    }
+    #[test]
+    fn resolve_bound_names() {
+        let env = &mut Environment::default();
+        env.insert_binding("x".try_into().unwrap(), Value::Number(Number::from_i32(1)));
+        assert_eq!(resolve("x + len \"a\"", env), Ok(()));
+        assert_eq!(resolve("{ let y = x; y * 2 }", env), Ok(()));
+        assert_eq!(resolve("match x { 1 => x, n => n }", env), Ok(()));
+        assert_eq!(resolve("fn fact n => fact (n - 1)", env), Ok(()));
+    }
+    #[test]
+    fn resolve_unknown_names() {
+        let env = &Environment::default();
+        assert_eq!(
+            resolve("1 + y", env),
+            Err(Error::Resolve(ResolveError::UnknownIdentifier(
+                "y".to_string()
+            )))
+        );
+        assert_eq!(
+            resolve("{ match 1 { y => y }; y }", env),
+            Err(Error::Resolve(ResolveError::UnknownIdentifier(
+                "y".to_string()
+            )))
+        );
+        assert_eq!(
+            resolve("match 1 { Some(x) => x }", env),
+            Err(Error::Resolve(ResolveError::UnknownIdentifier(
+                "Some".to_string()
+            )))
+        );
+    }
+}
@@ -2,12 +2,22 @@ use crate::internal::prelude::*;
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Statement {
     BindingDef(BindingDef),
+    Assignment(Assignment),
     Expression(Expression),
+    /// An expression followed by `;`, which runs for its effects and gives no value.
+    Discarded(Expression),
     FunctionDef(FunctionDef),
+    EnumDef(EnumDef),
+    /// A `return` like `return x;`, which leaves the function being called.
+    Return(Expression),
 }
 
 impl Statement {
     fn pre_parse(s: &TrimmedStr) -> Option<Self> {
+        // `enum Unit { Unit }` would be a function call of `enum` otherwise
+        if let Ok(enum_def) = EnumDef::new(s) {
+            return Some(Self::EnumDef(enum_def));
+        }
         if let Ok(expression) = Expression::new(s) {
             return Some(Self::Expression(expression));
         }
@@ -17,41 +27,182 @@ impl Statement {
         None
     }
     fn parse_after_strip_semicolon(s: &TrimmedStr) -> Option<Self> {
+        if **s == *Keyword::Return.as_str() {
+            return Some(Self::Return(Expression::Empty));
+        }
+        if let Some(value) = Keyword::Return.strip(s) {
+            return Expression::new(&value.into()).ok().map(Self::Return);
+        }
         if let Ok(binding_def) = BindingDef::new(s) {
             return Some(Self::BindingDef(binding_def));
         }
-        if Expression::new(s).is_ok() {
-            return Some(Self::Expression(Expression::Empty));
+        if let Ok(assignment) = Assignment::new(s) {
+            return Some(Self::Assignment(assignment));
+        }
+        if let Ok(expression) = Expression::new(s) {
+            return Some(Self::Discarded(expression));
         }
         None
     }
-    pub fn new(s: &TrimmedStr) -> Result<Self, StatementError> {
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
         if let Some(statement) = Self::pre_parse(s) {
             return Ok(statement);
         }
-        let s = &TrimmedStr::new(
-            s.strip_suffix(';')
-                .ok_or(StatementError::BindingDefMissingSemicolon)?,
-        );
+        let Some(stripped) = s.strip_suffix(';') else {
+            return Err(
+                Self::literal_error(s).unwrap_or(StatementError::BindingDefMissingSemicolon.into())
+            );
+        };
+        let s = &TrimmedStr::new(stripped);
         if let Some(statement) = Self::parse_after_strip_semicolon(s) {
             return Ok(statement);
         }
-        Err(StatementError::InvalidStatement)
+        // a definition may end with `;` in a block, like `{ fn f x => x; f 1 }`
+        match Self::pre_parse(s) {
+            Some(statement @ (Self::FunctionDef(_) | Self::EnumDef(_))) => Ok(statement),
+            _ => Err(Self::literal_error(s).unwrap_or(StatementError::InvalidStatement.into())),
+        }
+    }
+    /// The error of a malformed literal in the statement, like `"unterminated`, which is more
+    /// specific than the statement missing `;` or being invalid.
+    ///
+    /// A definition reports the name or pattern it cannot define too, like `1x` in `fn f 1x => 1`
+    /// or the keyword in `let let = 1;`.
+    fn literal_error(s: &TrimmedStr) -> Option<Error> {
+        let (error, definition) = if let Some(value) = Keyword::Return.strip(s) {
+            (Expression::new(&value.into()).err(), false)
+        } else if Keyword::Let.strip(s).is_some() {
+            (BindingDef::new(s).err(), true)
+        } else if Keyword::Fn.strip(s).is_some() {
+            (FunctionDef::new(s).err(), true)
+        } else {
+            (Expression::new(s).err(), false)
+        };
+        let error = error?;
+        let literal = matches!(
+            error,
+            Error::StringLiteral(_) | Error::FormatString(_) | Error::List(_) | Error::Record(_)
+        );
+        // a definition without a name like `let = 1;` is just invalid
+        let name = matches!(error, Error::Identifier(_) | Error::Pattern(_))
+            && error != IdentifierError::Empty.into();
+        (literal || definition && name).then_some(error)
+    }
+    /// The names defined by the statement, where an enum is defined by its own name.
+    pub fn defined_names(&self) -> Vec<&Identifier> {
+        match self {
+            Self::BindingDef(binding_def) => binding_def.identifiers(),
+            Self::FunctionDef(function_def) => vec![function_def.name()],
+            Self::EnumDef(enum_def) => vec![enum_def.name()],
+            Self::Assignment(_) | Self::Expression(_) | Self::Discarded(_) | Self::Return(_) => {
+                vec![]
+            }
+        }
     }
-    pub fn get_expression_in(&self, local: &mut Environment) -> Expression {
+    /// Check the names used are defined, and declare what the statement defines in `resolver`.
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
         match self {
-            Self::BindingDef(binding_def) => binding_def.store(local),
-            Self::FunctionDef(function_def) => function_def.store(local),
-            Self::Expression(expression) => return expression.clone(),
+            Self::BindingDef(binding_def) => binding_def.resolve(resolver),
+            Self::Assignment(assignment) => assignment.resolve(resolver),
+            Self::FunctionDef(function_def) => function_def.resolve(resolver),
+            Self::EnumDef(enum_def) => {
+                enum_def.resolve(resolver);
+                Ok(())
+            }
+            Self::Expression(expression)
+            | Self::Discarded(expression)
+            | Self::Return(expression) => expression.resolve(resolver),
         }
-        Expression::Empty
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        match self {
+            Self::BindingDef(binding_def) => binding_def.lint(linter),
+            Self::Assignment(assignment) => assignment.lint(linter),
+            Self::FunctionDef(function_def) => function_def.lint(linter),
+            Self::EnumDef(enum_def) => enum_def.lint(linter),
+            Self::Expression(expression)
+            | Self::Discarded(expression)
+            | Self::Return(expression) => expression.lint(linter),
+        }
+    }
+    /// Infer the type of the value of the statement, and bind what it defines in `checker`.
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<Type, Error> {
+        match self {
+            Self::BindingDef(binding_def) => binding_def.infer(checker)?,
+            Self::Assignment(assignment) => assignment.infer(checker)?,
+            Self::FunctionDef(function_def) => function_def.infer(checker)?,
+            Self::EnumDef(enum_def) => enum_def.infer(checker),
+            Self::Discarded(expression) => {
+                expression.infer(checker)?;
+            }
+            Self::Return(expression) => {
+                let Some(output) = checker.function_output() else {
+                    return Err(ControlFlowError::Return(Value::Empty.into()))?;
+                };
+                let value = expression.infer(checker)?;
+                checker.unify(&output, &value)?;
+                // what follows is never reached, so it fits any type
+                return Ok(checker.fresh());
+            }
+            Self::Expression(expression) => return expression.infer(checker),
+        }
+        Ok(Type::Empty)
+    }
+    pub fn get_expression_in(&self, local: &mut Environment) -> Result<Expression, Error> {
+        match self {
+            Self::BindingDef(binding_def) => binding_def.store(local)?,
+            Self::Assignment(assignment) => assignment.eval(local)?,
+            Self::FunctionDef(function_def) => function_def.store(local)?,
+            Self::EnumDef(enum_def) => enum_def.store(local),
+            Self::Discarded(expression) => {
+                expression.eval(local)?;
+            }
+            Self::Return(expression) => {
+                return Err(ControlFlowError::Return(expression.eval(local)?.into()))?;
+            }
+            Self::Expression(expression) => return Ok(expression.clone()),
+        }
+        Ok(Expression::Empty)
     }
 }
 
+impl std::fmt::Display for Statement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BindingDef(binding_def) => binding_def.fmt(f)?,
+            Self::Assignment(assignment) => assignment.fmt(f)?,
+            Self::Expression(expression) => return expression.fmt(f),
+            Self::Discarded(expression) => expression.fmt(f)?,
+            Self::FunctionDef(function_def) => return function_def.fmt(f),
+            Self::EnumDef(enum_def) => return enum_def.fmt(f),
+            Self::Return(Expression::Empty) => write!(f, "{}", Keyword::Return)?,
+            Self::Return(expression) => {
+                write!(f, "{} ", Keyword::Return)?;
+                expression.fmt(f)?;
+            }
+        }
+        write!(f, ";")
+    }
+}
 #[cfg(test)]
 mod tests {
     use super::*;
     #[test]
+    fn display_statements() {
+        for s in [
+            "let mut (a, b) = (1, 2);",
+            "x <<= 1;",
+            "f x;",
+            "return;",
+            "return x + 1;",
+            "fn add (x: Int) y -> Int => x + y",
+            "enum Shape { Circle(r), Rect(w, h), Empty }",
+            "{ 1 }",
+        ] {
+            assert_eq!(Statement::new(&s.into()).unwrap().to_string(), s);
+        }
+    }
+    #[test]
     fn parse_binding_def() {
         assert_eq!(
             Statement::new(&"let x = 5;".into()),
@@ -61,6 +212,36 @@ mod tests {
         );
     }
     #[test]
+    fn parse_assignment() {
+        assert_eq!(
+            Statement::new(&"x += 1;".into()),
+            Ok(Statement::Assignment(
+                Assignment::new(&"x += 1".into()).unwrap()
+            ))
+        );
+        assert_eq!(
+            Statement::new(&"x = 1".into()),
+            Err(Error::Statement(StatementError::BindingDefMissingSemicolon))
+        );
+    }
+    #[test]
+    fn parse_return() {
+        assert_eq!(
+            Statement::new(&"return x + 1;".into()),
+            Ok(Statement::Return(Expression::Operation(
+                Operation::new(&"x + 1".into()).unwrap()
+            )))
+        );
+        assert_eq!(
+            Statement::new(&"return;".into()),
+            Ok(Statement::Return(Expression::Empty))
+        );
+        assert_eq!(
+            Statement::new(&"return 1".into()),
+            Err(Error::Statement(StatementError::BindingDefMissingSemicolon))
+        );
+    }
+    #[test]
     fn parse_function_def() {
         assert_eq!(
             Statement::new(&"fn one_add_one => 1+1".into()),
@@ -74,6 +255,23 @@ mod tests {
                 FunctionDef::new(&"fn add_one x => x + 1".into()).unwrap()
             ))
         );
+        assert_eq!(
+            Statement::new(&"fn f 1x => 1".into()),
+            Err(Error::Identifier(IdentifierError::StartWithNonLetter))
+        );
+        assert_eq!(
+            Statement::new(&"fn f (x, => 1;".into()),
+            Err(Error::Pattern(PatternError::InvalidPattern))
+        );
+    }
+    #[test]
+    fn parse_enum_def() {
+        assert_eq!(
+            Statement::new(&"enum Unit { Unit }".into()),
+            Ok(Statement::EnumDef(
+                EnumDef::new(&"enum Unit { Unit }".into()).unwrap()
+            ))
+        );
     }
     #[test]
     fn parse_expression_without_semicolon() {
@@ -92,20 +290,53 @@ mod tests {
     }
     #[test]
     fn parse_expression_with_semicolon() {
-        // TODO: fix it when expression with semicolon has side-effect.
         assert_eq!(
             Statement::new(&"1+1;".into()),
-            Ok(Statement::Expression(Expression::Empty))
+            Ok(Statement::Discarded(Expression::Operation(
+                Operation::new(&"1+1".into()).unwrap()
+            )))
+        );
+    }
+    #[test]
+    fn get_expression_in_discarded() {
+        let local = &mut Environment::default();
+        assert_eq!(
+            Statement::new(&"1 / 0;".into())
+                .unwrap()
+                .get_expression_in(local),
+            Err(Error::Operation(OperationError::DivisionByZero))
+        );
+        assert_eq!(
+            Statement::new(&"1 + 1;".into())
+                .unwrap()
+                .get_expression_in(local),
+            Ok(Expression::Empty)
         );
     }
     #[test]
     fn parse_invalid() {
         assert_eq!(
             Statement::new(&"let a=a=1;".into()),
-            Err(StatementError::InvalidStatement)
+            Err(Error::Statement(StatementError::InvalidStatement))
         );
     }
     #[test]
+    fn parse_invalid_literal() {
+        let message = |s: &str| Statement::new(&s.into()).unwrap_err().to_string();
+        assert_eq!(message(r#""unterminated"#), "Missing closing quote `\"`");
+        assert_eq!(
+            message(r#"f"{x""#),
+            "Missing closing brace `}` of the placeholder"
+        );
+        assert_eq!(message("[1,,2];"), "Expect an element between `,`");
+        assert_eq!(
+            message("{ a: 1, a: 2 }"),
+            "Field `a` is defined more than once"
+        );
+        assert_eq!(message(r#"let s = "a;"#), "Missing closing quote `\"`");
+        assert_eq!(message("return [,];"), "Expect an element between `,`");
+    }
+    #[test]
     fn pre_parse() {
         assert_eq!(Statement::pre_parse(&"let x = 1+1".into()), None);
         assert_eq!(
@@ -125,7 +356,9 @@ mod tests {
     fn parse_after_strip_semicolon() {
         assert_eq!(
             Statement::parse_after_strip_semicolon(&"1+1".into()),
-            Some(Statement::Expression(Expression::Empty))
+            Some(Statement::Discarded(Expression::Operation(
+                Operation::new(&"1+1".into()).unwrap()
+            )))
         );
         assert_eq!(
             Statement::parse_after_strip_semicolon(&"let x = 1+1".into()),
@@ -137,12 +370,18 @@ mod tests {
             Statement::parse_after_strip_semicolon(&"fn one_add_one => 2".into()),
             None
         );
+        assert_eq!(
+            Statement::new(&"fn one_add_one => 2;".into()),
+            Ok(Statement::FunctionDef(
+                FunctionDef::new(&"fn one_add_one => 2".into()).unwrap()
+            ))
+        );
     }
     #[test]
     fn parse_binding_def_missing_semicolon() {
         assert_eq!(
             Statement::new(&"let a=114".into()),
-            Err(StatementError::BindingDefMissingSemicolon)
+            Err(Error::Statement(StatementError::BindingDefMissingSemicolon))
         );
     }
     #[test]
@@ -158,13 +397,11 @@ mod tests {
         assert_eq!(
             Statement::BindingDef(BindingDef::new(&"let x = 5+6".into()).unwrap())
                 .get_expression_in(local),
-            Expression::Empty
+            Ok(Expression::Empty)
         );
         assert_eq!(
             local.get_from_self_and_parent(&"x".try_into().unwrap()),
-            Some(NamedValue::Binding(Expression::Operation(
-                Operation::new(&"5+6".into()).unwrap()
-            )))
+            Some(NamedValue::Binding(Value::Number(Number::from_i32(5 + 6))))
         );
     }
     #[test]
@@ -174,7 +411,25 @@ mod tests {
             Statement::new(&"114".into())
                 .unwrap()
                 .get_expression_in(local),
-            Expression::Number(Number::from_i32(114))
+            Ok(Expression::Number(Number::from_i32(114)))
+        );
+    }
+    #[test]
+    fn get_expression_in_enum_def() {
+        let local = &mut Environment::default();
+        assert_eq!(
+            Statement::new(&"enum Option { Some(x), None }".into())
+                .unwrap()
+                .get_expression_in(local),
+            Ok(Expression::Empty)
+        );
+        assert_eq!(
+            Statement::new(&"Some 1".into())
+                .unwrap()
+                .get_expression_in(local)
+                .and_then(|expression| expression.eval(local))
+                .map(|value| value.to_string()),
+            Ok("Some(1)".to_string())
         );
     }
     #[test]
@@ -183,7 +438,7 @@ mod tests {
         assert_eq!(
             Statement::FunctionDef(FunctionDef::new(&"fn add_one x => x + 1".into()).unwrap())
                 .get_expression_in(local),
-            Expression::Empty
+            Ok(Expression::Empty)
         );
     }
 }
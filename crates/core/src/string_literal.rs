@@ -0,0 +1,139 @@
+use crate::internal::prelude::*;
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StringLiteral(String);
+impl StringLiteral {
+    /// Parse a double-quoted literal with escape sequences like `\n`, `\"` and `\u{1F600}`.
+    pub fn new(s: &TrimmedStr) -> Result<Self, StringLiteralError> {
+        if !s.starts_with('"') {
+            return Err(StringLiteralError::MissingOpeningQuote);
+        }
+        if s.strip_wrapping('"', '"').is_none() {
+            return Err(StringLiteralError::MissingClosingQuote);
+        }
+        // the whitespace inside the quotes is kept
+        Self::unescape(&s[1..s.len() - 1]).map(Self)
+    }
+    pub fn unescape(s: &str) -> Result<String, StringLiteralError> {
+        let mut unescaped = String::with_capacity(s.len());
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                unescaped.push(c);
+                continue;
+            }
+            let escaped = match chars.next() {
+                Some('n') => '\n',
+                Some('t') => '\t',
+                Some('r') => '\r',
+                Some('0') => '\0',
+                Some('\\') => '\\',
+                Some('"') => '"',
+                Some('u') => Self::unescape_unicode(&mut chars)?,
+                Some(c) => return Err(StringLiteralError::InvalidEscape(c)),
+                None => return Err(StringLiteralError::MissingClosingQuote),
+            };
+            unescaped.push(escaped);
+        }
+        Ok(unescaped)
+    }
+    /// Parse the `{1F600}` part of `\u{1F600}`.
+    fn unescape_unicode(chars: &mut std::str::Chars) -> Result<char, StringLiteralError> {
+        let rest = chars.as_str();
+        let code = rest
+            .strip_prefix('{')
+            .and_then(|rest| rest.split_once('}'))
+            .map(|(code, _)| code)
+            .ok_or(StringLiteralError::InvalidUnicodeEscape)?;
+        let c = u32::from_str_radix(code, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or(StringLiteralError::InvalidUnicodeEscape)?;
+        *chars = rest[code.len() + "{}".len()..].chars();
+        Ok(c)
+    }
+    pub fn inner(&self) -> &str {
+        &self.0
+    }
+}
+impl std::fmt::Display for StringLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parse_string() {
+        assert_eq!(
+            StringLiteral::new(&r#""Hello, world""#.into()),
+            Ok(StringLiteral("Hello, world".to_string()))
+        );
+    }
+    #[test]
+    fn parse_with_whitespace_inside() {
+        assert_eq!(
+            StringLiteral::new(&r#"  " a b " "#.into()),
+            Ok(StringLiteral(" a b ".to_string()))
+        );
+    }
+    #[test]
+    fn parse_empty_string() {
+        assert_eq!(
+            StringLiteral::new(&r#""""#.into()),
+            Ok(StringLiteral(String::new()))
+        );
+    }
+    #[test]
+    fn parse_escapes() {
+        assert_eq!(
+            StringLiteral::new(&r#""a\n\t\"b\"\\\0""#.into()),
+            Ok(StringLiteral("a\n\t\"b\"\\\0".to_string()))
+        );
+    }
+    #[test]
+    fn parse_unicode_escapes() {
+        assert_eq!(
+            StringLiteral::new(&r#""\u{4E2D}\u{6587}!""#.into()),
+            Ok(StringLiteral("中文!".to_string()))
+        );
+        assert_eq!(
+            StringLiteral::new(&r#""\u{110000}""#.into()),
+            Err(StringLiteralError::InvalidUnicodeEscape)
+        );
+        assert_eq!(
+            StringLiteral::new(&r#""\u{4E2D""#.into()),
+            Err(StringLiteralError::InvalidUnicodeEscape)
+        );
+        assert_eq!(
+            StringLiteral::new(&r#""\u4E2D""#.into()),
+            Err(StringLiteralError::InvalidUnicodeEscape)
+        );
+    }
+    #[test]
+    fn parse_invalid_escape() {
+        assert_eq!(
+            StringLiteral::new(&r#""\q""#.into()),
+            Err(StringLiteralError::InvalidEscape('q'))
+        );
+    }
+    #[test]
+    fn parse_without_quotes() {
+        assert_eq!(
+            StringLiteral::new(&"abc".into()),
+            Err(StringLiteralError::MissingOpeningQuote)
+        );
+        assert_eq!(
+            StringLiteral::new(&r#""abc"#.into()),
+            Err(StringLiteralError::MissingClosingQuote)
+        );
+        assert_eq!(
+            StringLiteral::new(&r#""abc\""#.into()),
+            Err(StringLiteralError::MissingClosingQuote)
+        );
+        assert_eq!(
+            StringLiteral::new(&r#""a" + "b""#.into()),
+            Err(StringLiteralError::MissingClosingQuote)
+        );
+    }
+}
@@ -0,0 +1,360 @@
+use crate::internal::prelude::*;
+use std::ops::Range;
+
+/// What kind of name a `Symbol` defines.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SymbolKind {
+    Function,
+    Parameter,
+    Binding,
+    Enum,
+    Variant,
+}
+
+/// A name defined in a program, with where it is defined and where it can be used.
+///
+/// Symbols are found from the words of the source rather than from the parsed statements,
+/// as those do not keep their position, so the names bound by `match` arms are not found.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Where the name is written in the definition.
+    pub start: usize,
+    /// The part of the source where the name can be used.
+    pub scope: Range<usize>,
+    /// The definition in short, like `fn add x y` or `let mut total`.
+    pub detail: String,
+    /// Whether it is defined at the top level, where it stays bound after the program runs.
+    pub top_level: bool,
+}
+
+/// A word or an operator of the source, with where it starts.
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    start: usize,
+    text: &'a str,
+    /// The end of the brackets it is in, or of the source at the top level.
+    enclosing_end: usize,
+    depth: usize,
+}
+impl Token<'_> {
+    fn is_word(&self) -> bool {
+        self.text.starts_with(unicode_ident::is_xid_start)
+    }
+    const fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+}
+
+impl Symbol {
+    /// Find the names defined by `fn`, `let`, `for` and `enum` in a program.
+    pub fn find(source: &str) -> Vec<Self> {
+        let code = Comment::strip(source);
+        let tokens = Self::tokenize(&code);
+        let mut symbols = Vec::new();
+        for (i, token) in tokens.iter().enumerate() {
+            let rest = &tokens[i + 1..];
+            match token.text {
+                "fn" => Self::find_function(&code, token, rest, &mut symbols),
+                "let" => {
+                    let rest = match rest.first() {
+                        Some(next) if next.text == "mut" => &rest[1..],
+                        _ => rest,
+                    };
+                    let Some(end) = rest
+                        .iter()
+                        .find(|next| next.text == "=" && next.depth == token.depth)
+                    else {
+                        continue;
+                    };
+                    let detail = Self::detail(&code[token.start..end.start]);
+                    let names = Self::pattern_names(rest, "=");
+                    for name in names {
+                        symbols.push(Self::new(
+                            &name,
+                            SymbolKind::Binding,
+                            name.start..token.enclosing_end,
+                            detail.clone(),
+                        ));
+                    }
+                }
+                "for" => {
+                    let end = Self::statement_end(token, rest);
+                    for name in Self::pattern_names(rest, "in") {
+                        let detail = format!("{} {}", Keyword::For, name.text);
+                        symbols.push(Self {
+                            top_level: false,
+                            ..Self::new(&name, SymbolKind::Binding, name.start..end, detail)
+                        });
+                    }
+                }
+                "enum" => Self::find_enum(token, rest, &mut symbols),
+                _ => (),
+            }
+        }
+        symbols
+    }
+    /// Find the symbol which the name at `offset` refers to, which is the innermost one in scope.
+    pub fn lookup<'a>(symbols: &'a [Self], name: &str, offset: usize) -> Option<&'a Self> {
+        Self::visible(symbols, offset)
+            .filter(|symbol| symbol.name == name)
+            .max_by_key(|symbol| symbol.start)
+    }
+    /// The symbols which can be used at `offset`.
+    pub fn visible(symbols: &[Self], offset: usize) -> impl Iterator<Item = &Self> {
+        symbols
+            .iter()
+            .filter(move |symbol| symbol.start <= offset && offset <= symbol.scope.end)
+    }
+    fn new(token: &Token, kind: SymbolKind, scope: Range<usize>, detail: String) -> Self {
+        Self {
+            name: token.text.to_string(),
+            kind,
+            start: token.start,
+            scope,
+            detail,
+            top_level: token.depth == 0,
+        }
+    }
+    fn find_function(code: &str, token: &Token, rest: &[Token], symbols: &mut Vec<Self>) {
+        let Some(name) = rest.first().filter(|name| name.is_word()) else {
+            return;
+        };
+        let Some(arrow) = rest
+            .iter()
+            .position(|next| next.text == "=>" && next.depth == token.depth)
+        else {
+            return;
+        };
+        let detail = Self::detail(&code[token.start..rest[arrow].start]);
+        symbols.push(Self::new(
+            name,
+            SymbolKind::Function,
+            name.start..token.enclosing_end,
+            detail,
+        ));
+        let end = Self::statement_end(token, rest);
+        let parameters = Self::pattern_names(&rest[1..=arrow], "->");
+        for parameter in parameters {
+            let detail = format!("parameter of `{}`", name.text);
+            let scope = rest[arrow].start..end;
+            symbols.push(Self {
+                top_level: false,
+                ..Self::new(&parameter, SymbolKind::Parameter, scope, detail)
+            });
+        }
+    }
+    fn find_enum(token: &Token, rest: &[Token], symbols: &mut Vec<Self>) {
+        let Some(name) = rest.first().filter(|name| name.is_word()) else {
+            return;
+        };
+        let scope = name.start..token.enclosing_end;
+        let detail = format!("{} {}", Keyword::Enum, name.text);
+        symbols.push(Self::new(name, SymbolKind::Enum, scope.clone(), detail));
+        // a variant follows the `{` or a `,` in the braces
+        let variants = rest[1..]
+            .windows(2)
+            .take_while(|pair| pair[0].depth > token.depth || pair[0].text == "{")
+            .filter(|pair| matches!(pair[0].text, "{" | ",") && pair[1].depth == token.depth + 1)
+            .map(|pair| pair[1])
+            .filter(Token::is_word);
+        for variant in variants {
+            let detail = format!("variant of `{}`", name.text);
+            symbols.push(Self::new(
+                &variant,
+                SymbolKind::Variant,
+                scope.clone(),
+                detail,
+            ));
+        }
+    }
+    /// The names bound by the pattern which the tokens start with, and which ends at `end`.
+    ///
+    /// A word followed right by `(` names a constructor, and the words after `:` name a type.
+    fn pattern_names<'a>(tokens: &[Token<'a>], end: &str) -> Vec<Token<'a>> {
+        let Some(depth) = tokens.first().map(|token| token.depth) else {
+            return Vec::new();
+        };
+        let mut names = Vec::new();
+        let mut in_annotation = false;
+        for (i, token) in tokens.iter().enumerate() {
+            if token.text == end && token.depth == depth {
+                break;
+            }
+            match token.text {
+                ":" => in_annotation = true,
+                "," | ")" => in_annotation = false,
+                "=>" => break,
+                _ if in_annotation || !token.is_word() => (),
+                _ if tokens
+                    .get(i + 1)
+                    .is_some_and(|next| next.text == "(" && next.start == token.end()) => {}
+                _ => names.push(*token),
+            }
+        }
+        names
+    }
+    /// Where the statement which starts with `token` ends, which is its `;` or the end of
+    /// the brackets it is in.
+    fn statement_end(token: &Token, rest: &[Token]) -> usize {
+        rest.iter()
+            .find(|next| next.text == ";" && next.depth == token.depth)
+            .map_or(token.enclosing_end, Token::end)
+    }
+    /// Write a definition in one line, like `fn add x y`.
+    fn detail(s: &str) -> String {
+        s.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+    /// Split the source into words and operators, skipping string literals.
+    fn tokenize(code: &str) -> Vec<Token<'_>> {
+        let mut spans = Vec::new();
+        let mut chars = code.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            let is_word = |c: char| unicode_ident::is_xid_continue(c);
+            let is_operator = |c: char| "=<>!+-*/%&|^.:".contains(c);
+            let mut end = start + c.len_utf8();
+            if c == '"' {
+                // skip to the closing quote, past the escaped ones
+                let mut escaped = false;
+                for (_, c) in chars.by_ref() {
+                    match c {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        '"' => break,
+                        _ => (),
+                    }
+                }
+                continue;
+            } else if is_word(c) {
+                while let Some((_, c)) = chars.next_if(|&(_, c)| is_word(c)) {
+                    end += c.len_utf8();
+                }
+                // the `f` of a format string is not a name
+                if &code[start..end] == "f" && chars.peek().is_some_and(|&(_, c)| c == '"') {
+                    continue;
+                }
+            } else if is_operator(c) {
+                while let Some((_, c)) = chars.next_if(|&(_, c)| is_operator(c)) {
+                    end += c.len_utf8();
+                }
+            } else if c.is_whitespace() {
+                continue;
+            }
+            spans.push(start..end);
+        }
+        // the end of each bracket, which is where the names defined in it go out of scope
+        let mut ends = vec![code.len(); spans.len()];
+        let mut open = Vec::new();
+        for (i, span) in spans.iter().enumerate() {
+            match &code[span.clone()] {
+                "(" | "[" | "{" => open.push(i),
+                ")" | "]" | "}" => {
+                    if let Some(opening) = open.pop() {
+                        ends[opening] = span.start;
+                    }
+                }
+                _ => (),
+            }
+        }
+        let mut enclosing = vec![(code.len(), 0)];
+        let mut tokens = Vec::new();
+        for (i, span) in spans.into_iter().enumerate() {
+            let text = &code[span.clone()];
+            if matches!(text, ")" | "]" | "}") && enclosing.len() > 1 {
+                enclosing.pop();
+            }
+            let (enclosing_end, depth) = enclosing[enclosing.len() - 1];
+            tokens.push(Token {
+                start: span.start,
+                text,
+                enclosing_end,
+                depth,
+            });
+            if matches!(text, "(" | "[" | "{") {
+                enclosing.push((ends[i], depth + 1));
+            }
+        }
+        tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn names(source: &str) -> Vec<(String, SymbolKind, String)> {
+        Symbol::find(source)
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.kind, symbol.detail))
+            .collect()
+    }
+    #[test]
+    fn find_symbols() {
+        assert_eq!(
+            names(
+                "fn add (x: Int) y -> Int => x + y;\nlet mut (a, _) = (1, \"let b = 2\"); // let c"
+            ),
+            vec![
+                (
+                    "add".to_string(),
+                    SymbolKind::Function,
+                    "fn add (x: Int) y -> Int".to_string()
+                ),
+                (
+                    "x".to_string(),
+                    SymbolKind::Parameter,
+                    "parameter of `add`".to_string()
+                ),
+                (
+                    "y".to_string(),
+                    SymbolKind::Parameter,
+                    "parameter of `add`".to_string()
+                ),
+                (
+                    "a".to_string(),
+                    SymbolKind::Binding,
+                    "let mut (a, _)".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            names("enum Shape { Circle(r), Empty }; for Circle(r) in [] { f\"{r}\" }"),
+            vec![
+                (
+                    "Shape".to_string(),
+                    SymbolKind::Enum,
+                    "enum Shape".to_string()
+                ),
+                (
+                    "Circle".to_string(),
+                    SymbolKind::Variant,
+                    "variant of `Shape`".to_string()
+                ),
+                (
+                    "Empty".to_string(),
+                    SymbolKind::Variant,
+                    "variant of `Shape`".to_string()
+                ),
+                ("r".to_string(), SymbolKind::Binding, "for r".to_string()),
+            ]
+        );
+    }
+    #[test]
+    fn lookup_in_scope() {
+        let source = "let x = 1;\nfn f x => { let y = x; y };\nx";
+        let symbols = Symbol::find(source);
+        let x_in_f = source.find("= x").unwrap() + 2;
+        assert_eq!(
+            Symbol::lookup(&symbols, "x", x_in_f).map(|symbol| symbol.kind),
+            Some(SymbolKind::Parameter)
+        );
+        let last_x = source.rfind('x').unwrap();
+        let x = Symbol::lookup(&symbols, "x", last_x).unwrap();
+        assert_eq!(
+            (x.kind, x.start, x.top_level),
+            (SymbolKind::Binding, 4, true)
+        );
+        // `y` is not in scope out of the block
+        assert_eq!(Symbol::lookup(&symbols, "y", last_x), None);
+    }
+}
@@ -0,0 +1,122 @@
+use std::str::CharIndices;
+
+/// Iterate over the chars of a string which are not nested in brackets or string literals.
+///
+/// The brackets and quotes of a top-level group are yielded themselves,
+/// so `a (b) "c"` yields `a`, ` `, `(`, `)`, ` `, `"` and `"`.
+#[derive(Debug, Clone)]
+pub struct TopLevelChars<'a> {
+    chars: CharIndices<'a>,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+}
+impl<'a> TopLevelChars<'a> {
+    pub const OPENING_BRACKETS: [char; 3] = ['(', '[', '{'];
+    pub const CLOSING_BRACKETS: [char; 3] = [')', ']', '}'];
+    pub fn new(s: &'a str) -> Self {
+        Self {
+            chars: s.char_indices(),
+            depth: 0,
+            in_string: false,
+            escaped: false,
+        }
+    }
+    /// Whether all brackets and string literals are closed at the end.
+    pub fn is_closed(&mut self) -> bool {
+        self.by_ref().for_each(drop);
+        self.depth == 0 && !self.in_string
+    }
+}
+impl Iterator for TopLevelChars<'_> {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (index, c) in self.chars.by_ref() {
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if c == '\\' {
+                    self.escaped = true;
+                } else if c == '"' {
+                    self.in_string = false;
+                    if self.depth == 0 {
+                        return Some((index, c));
+                    }
+                }
+                continue;
+            }
+            let is_top_level = self.depth == 0;
+            if c == '"' {
+                self.in_string = true;
+            } else if Self::OPENING_BRACKETS.contains(&c) {
+                self.depth += 1;
+            } else if Self::CLOSING_BRACKETS.contains(&c) {
+                self.depth = self.depth.saturating_sub(1);
+            }
+            if is_top_level || self.depth == 0 {
+                return Some((index, c));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn without_brackets() {
+        assert_eq!(
+            TopLevelChars::new("1+x").collect::<Vec<_>>(),
+            vec![(0, '1'), (1, '+'), (2, 'x')]
+        );
+    }
+    #[test]
+    fn with_brackets() {
+        assert_eq!(
+            TopLevelChars::new("a(b)c").collect::<Vec<_>>(),
+            vec![(0, 'a'), (1, '('), (3, ')'), (4, 'c')]
+        );
+    }
+    #[test]
+    fn with_nested_brackets() {
+        assert_eq!(
+            TopLevelChars::new("{(x)[y]}+1").collect::<Vec<_>>(),
+            vec![(0, '{'), (7, '}'), (8, '+'), (9, '1')]
+        );
+    }
+    #[test]
+    fn with_string() {
+        assert_eq!(
+            TopLevelChars::new(r#""(a" + "\"}""#).collect::<Vec<_>>(),
+            vec![
+                (0, '"'),
+                (3, '"'),
+                (4, ' '),
+                (5, '+'),
+                (6, ' '),
+                (7, '"'),
+                (11, '"')
+            ]
+        );
+        assert_eq!(
+            TopLevelChars::new(r#"{"}"}x"#).collect::<Vec<_>>(),
+            vec![(0, '{'), (4, '}'), (5, 'x')]
+        );
+    }
+    #[test]
+    fn is_closed() {
+        assert!(TopLevelChars::new("{(x)}").is_closed());
+        assert!(TopLevelChars::new(r#""{""#).is_closed());
+        assert!(!TopLevelChars::new("{(x)").is_closed());
+        assert!(!TopLevelChars::new(r#""x\""#).is_closed());
+    }
+    #[test]
+    fn with_unbalanced_brackets() {
+        assert_eq!(
+            TopLevelChars::new(")x(y").collect::<Vec<_>>(),
+            vec![(0, ')'), (1, 'x'), (2, '(')]
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::internal::prelude::*;
 use std::ops::Deref;
 
 /// Store non-empty &str
@@ -8,6 +9,40 @@ impl<'a> TrimmedStr<'a> {
         let s = s.trim();
         Self(s)
     }
+    /// Strip `open` and the `close` matching it, only if they wrap the whole string.
+    pub fn strip_wrapping(&self, open: char, close: char) -> Option<Self> {
+        let mut chars = TopLevelChars::new(self.0);
+        if chars.next() != Some((0, open)) {
+            return None;
+        }
+        let end = self.0.len() - close.len_utf8();
+        if chars.next() != Some((end, close)) {
+            return None;
+        }
+        Some(Self::new(&self.0[open.len_utf8()..end]))
+    }
+    /// Split by the chars matching `is_separator` which are not nested in brackets.
+    pub fn split_top_level(&self, is_separator: impl Fn(char) -> bool) -> Vec<Self> {
+        let mut parts = Vec::new();
+        let mut start = 0;
+        for (index, c) in TopLevelChars::new(self.0) {
+            if is_separator(c) {
+                parts.push(Self::new(&self.0[start..index]));
+                start = index + c.len_utf8();
+            }
+        }
+        parts.push(Self::new(&self.0[start..]));
+        parts
+    }
+    /// Split elements separated by top-level `,`, where a trailing `,` is allowed.
+    pub fn split_list(&self) -> Vec<Self> {
+        let mut elements = self.split_top_level(|c| c == ',');
+        // it also makes an empty string no element
+        if elements.last().is_some_and(|s| s.is_empty()) {
+            elements.pop();
+        }
+        elements
+    }
 }
 impl Deref for TrimmedStr<'_> {
     type Target = str;
@@ -28,4 +63,46 @@ mod tests {
     fn non_white_space_string() {
         assert_eq!(TrimmedStr::new("   H e l l o  "), TrimmedStr("H e l l o"));
     }
+    #[test]
+    fn strip_wrapping() {
+        assert_eq!(
+            TrimmedStr::new(" (1 + 2) ").strip_wrapping('(', ')'),
+            Some(TrimmedStr("1 + 2"))
+        );
+        assert_eq!(
+            TrimmedStr::new("((1))").strip_wrapping('(', ')'),
+            Some(TrimmedStr("(1)"))
+        );
+        assert_eq!(TrimmedStr::new("(1) + (2)").strip_wrapping('(', ')'), None);
+        assert_eq!(TrimmedStr::new("(1").strip_wrapping('(', ')'), None);
+        assert_eq!(TrimmedStr::new("").strip_wrapping('(', ')'), None);
+    }
+    #[test]
+    fn split_top_level() {
+        assert_eq!(
+            TrimmedStr::new("f (a b) {c d} e").split_top_level(char::is_whitespace),
+            vec![
+                TrimmedStr("f"),
+                TrimmedStr("(a b)"),
+                TrimmedStr("{c d}"),
+                TrimmedStr("e")
+            ]
+        );
+        assert_eq!(
+            TrimmedStr::new("").split_top_level(char::is_whitespace),
+            vec![TrimmedStr("")]
+        );
+    }
+    #[test]
+    fn split_list() {
+        assert_eq!(
+            TrimmedStr::new("a, (b, c),").split_list(),
+            vec![TrimmedStr("a"), TrimmedStr("(b, c)")]
+        );
+        assert_eq!(
+            TrimmedStr::new("a,,").split_list(),
+            vec![TrimmedStr("a"), TrimmedStr("")]
+        );
+        assert_eq!(TrimmedStr::new(" ").split_list(), vec![]);
+    }
 }
@@ -0,0 +1,112 @@
+use crate::internal::prelude::*;
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Tuple {
+    elements: Vec<Expression>,
+}
+impl Tuple {
+    /// Parse elements separated by `,` in parentheses, where `(1,)` is a tuple but `(1)` is not.
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        let Some(inner) = s.strip_wrapping('(', ')') else {
+            return Err(TupleError::NotFound)?;
+        };
+        if !TopLevelChars::new(&inner).any(|(_, c)| c == ',') {
+            return Err(TupleError::NotFound)?;
+        }
+        let elements = inner
+            .split_list()
+            .iter()
+            .map(|s| match Expression::new(s)? {
+                Expression::Empty => Err(ListError::EmptyElement)?,
+                expression => Ok(expression),
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Self { elements })
+    }
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        for element in &self.elements {
+            element.resolve(resolver)?;
+        }
+        Ok(())
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        for element in &self.elements {
+            element.lint(linter);
+        }
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<Type, Error> {
+        let types = self
+            .elements
+            .iter()
+            .map(|expression| expression.infer(checker))
+            .collect::<Result<_, _>>()?;
+        Ok(Type::Tuple(types))
+    }
+    pub fn eval(&self, env: &Environment) -> Result<Value, Error> {
+        let values = self
+            .elements
+            .iter()
+            .map(|expression| expression.eval(env))
+            .collect::<Result<_, _>>()?;
+        Ok(Value::Tuple(values))
+    }
+}
+impl std::fmt::Display for Tuple {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(")?;
+        for (i, element) in self.elements.iter().enumerate() {
+            write!(f, "{}", if i > 0 { ", " } else { "" })?;
+            element.fmt(f)?;
+        }
+        write!(f, "{})", if self.elements.len() == 1 { "," } else { "" })
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parse_tuple() {
+        assert_eq!(
+            Tuple::new(&"(1, x + 1)".into()),
+            Ok(Tuple {
+                elements: vec![
+                    Expression::Number(Number::from_i32(1)),
+                    Expression::Operation(Operation::new(&"x + 1".into()).unwrap()),
+                ]
+            })
+        );
+        assert_eq!(
+            Tuple::new(&"(1,)".into()),
+            Ok(Tuple {
+                elements: vec![Expression::Number(Number::from_i32(1))]
+            })
+        );
+    }
+    #[test]
+    fn parse_non_tuple() {
+        assert_eq!(
+            Tuple::new(&"(1)".into()),
+            Err(Error::Tuple(TupleError::NotFound))
+        );
+        assert_eq!(
+            Tuple::new(&"(1), (2)".into()),
+            Err(Error::Tuple(TupleError::NotFound))
+        );
+        assert_eq!(
+            Tuple::new(&"(1, , 2)".into()),
+            Err(Error::List(ListError::EmptyElement))
+        );
+    }
+    #[test]
+    fn eval() {
+        assert_eq!(
+            Tuple::new(&r#"(1 + 1, "a", (2,))"#.into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Ok(Value::Tuple(vec![
+                Value::Number(Number::from_i32(2)),
+                Value::Str("a".to_string()),
+                Value::Tuple(vec![Value::Number(Number::from_i32(2))])
+            ]))
+        );
+    }
+}
@@ -0,0 +1,523 @@
+use crate::internal::prelude::*;
+use std::collections::HashMap;
+
+/// Infer the types of statements before they run, in the way of Hindley–Milner.
+///
+/// Bindings and functions are generalized, so `fn id x => x` can be called with any value.
+#[derive(Debug, Clone)]
+pub struct TypeChecker {
+    scopes: Vec<HashMap<Identifier, Scheme>>,
+    substitution: HashMap<usize, Type>,
+    constructors: HashMap<Identifier, (Vec<Type>, Type)>,
+    /// The types of the values given by `break` in the loops being inferred.
+    loops: Vec<Type>,
+    /// The output types of the functions being inferred, which `return` gives.
+    functions: Vec<Type>,
+    /// What the type variables taken by `len` and `+` may become, as no single type says it.
+    constraints: HashMap<usize, Constraint>,
+    next_var: usize,
+}
+/// A type which is polymorphic over `vars`.
+#[derive(Debug, Clone)]
+struct Scheme {
+    vars: Vec<usize>,
+    ty: Type,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Constraint {
+    /// A string or a list, which `len` takes.
+    Measurable,
+    /// A number or a string, which `+` takes.
+    Addable,
+}
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self {
+            scopes: vec![HashMap::new()],
+            substitution: HashMap::new(),
+            constructors: HashMap::new(),
+            loops: Vec::new(),
+            functions: Vec::new(),
+            constraints: HashMap::new(),
+            next_var: 0,
+        }
+    }
+}
+impl TypeChecker {
+    /// Infer the type of the statement, where nothing is bound if it is ill-typed.
+    pub fn check(&mut self, statement: &Statement) -> Result<Type, Error> {
+        let backup = self.clone();
+        let ty = statement
+            .infer(self)
+            .map(|ty| self.resolve(&ty).normalize());
+        if ty.is_err() {
+            *self = backup;
+        }
+        ty
+    }
+    pub fn fresh(&mut self) -> Type {
+        self.next_var += 1;
+        Type::Var(self.next_var - 1)
+    }
+    /// Apply what is inferred so far to the type.
+    pub fn resolve(&self, ty: &Type) -> Type {
+        ty.map_vars(&|var| self.substitution.get(&var).map(|ty| self.resolve(ty)))
+    }
+    pub fn unify(&mut self, expected: &Type, found: &Type) -> Result<(), TypeError> {
+        let (expected, found) = (self.resolve(expected), self.resolve(found));
+        let mismatch = || TypeError::Mismatch {
+            expected: expected.normalize().to_string(),
+            found: found.normalize().to_string(),
+        };
+        match (&expected, &found) {
+            (Type::Var(a), Type::Var(b)) if a == b => Ok(()),
+            (Type::Var(var), ty) | (ty, Type::Var(var)) => {
+                if ty.vars().contains(var) {
+                    return Err(TypeError::InfiniteType);
+                }
+                self.substitution.insert(*var, ty.clone());
+                match self.constraints.remove(var) {
+                    Some(constraint) => self.constrain(ty, constraint),
+                    None => Ok(()),
+                }
+            }
+            (Type::List(a), Type::List(b)) => self.unify(a, b),
+            (Type::Tuple(a), Type::Tuple(b)) if a.len() == b.len() => self.unify_all(a, b),
+            (Type::Record(a), Type::Record(b)) if a.keys().eq(b.keys()) => {
+                for (a, b) in a.values().zip(b.values()) {
+                    self.unify(a, b)?;
+                }
+                Ok(())
+            }
+            (Type::Function(a, a_output), Type::Function(b, b_output)) => {
+                if a.len() != b.len() {
+                    return Err(TypeError::ArityMismatch {
+                        expected: a.len(),
+                        found: b.len(),
+                    });
+                }
+                self.unify_all(a, b)?;
+                self.unify(a_output, b_output)
+            }
+            (a, b) if a == b => Ok(()),
+            _ => Err(mismatch()),
+        }
+    }
+    fn unify_all(&mut self, expected: &[Type], found: &[Type]) -> Result<(), TypeError> {
+        for (expected, found) in expected.iter().zip(found) {
+            self.unify(expected, found)?;
+        }
+        Ok(())
+    }
+    /// Get the type of the name, where each use of a polymorphic one gets fresh type variables.
+    pub fn lookup(&mut self, name: &Identifier) -> Result<Type, TypeError> {
+        let scheme = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .cloned();
+        let Some(Scheme { vars, ty }) = scheme else {
+            let builtin =
+                Builtin::new(name).ok_or_else(|| TypeError::UnknownIdentifier(name.to_string()))?;
+            return Ok(self.builtin(builtin));
+        };
+        let fresh = vars.iter().map(|_| self.fresh()).collect::<Vec<_>>();
+        for (var, fresh) in vars.iter().zip(&fresh) {
+            if let (Some(&constraint), Type::Var(fresh)) = (self.constraints.get(var), fresh) {
+                self.constraints.insert(*fresh, constraint);
+            }
+        }
+        Ok(ty.map_vars(&|var| {
+            vars.iter()
+                .position(|&found| found == var)
+                .map(|nth| fresh[nth].clone())
+        }))
+    }
+    /// Bind the name to a type which is the same wherever the name is used.
+    pub fn bind(&mut self, name: Identifier, ty: Type) {
+        let scheme = Scheme { vars: vec![], ty };
+        self.scopes
+            .last_mut()
+            .map(|scope| scope.insert(name, scheme));
+    }
+    /// Bind the name to a type which is polymorphic over what the outer scopes do not constrain.
+    pub fn bind_generalized(&mut self, name: Identifier, ty: &Type) {
+        let ty = self.resolve(ty);
+        let bound = self
+            .scopes
+            .iter()
+            .flat_map(HashMap::values)
+            .flat_map(|scheme| {
+                let vars = self.resolve(&scheme.ty).vars();
+                vars.into_iter().filter(|var| !scheme.vars.contains(var))
+            })
+            .collect::<Vec<_>>();
+        // what `+` is still free to be is a number, like in `fn add x y => x + y`
+        for var in ty.vars().into_iter().filter(|var| !bound.contains(var)) {
+            if self.constraints.get(&var) == Some(&Constraint::Addable) {
+                self.constraints.remove(&var);
+                self.substitution.insert(var, Type::Int);
+            }
+        }
+        let ty = self.resolve(&ty);
+        let vars = ty
+            .vars()
+            .into_iter()
+            .filter(|var| !bound.contains(var))
+            .collect();
+        self.scopes
+            .last_mut()
+            .map(|scope| scope.insert(name, Scheme { vars, ty }));
+    }
+    /// Define a variant whose fields have the types, as a value of the enum type.
+    pub fn define_constructor(&mut self, name: Identifier, fields: Vec<Type>, ty: Type) {
+        let constructor = if fields.is_empty() {
+            ty.clone()
+        } else {
+            Type::function(fields.clone(), ty.clone())
+        };
+        self.bind(name.clone(), constructor);
+        self.constructors.insert(name, (fields, ty));
+    }
+    /// Get the types of the fields and the enum type of a variant.
+    pub fn constructor(&self, name: &Identifier) -> Option<(Vec<Type>, Type)> {
+        self.constructors.get(name).cloned()
+    }
+    /// Run `f` in a new scope, whose bindings are dropped after that.
+    pub fn scoped<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, Error>) -> Result<T, Error> {
+        self.scopes.push(HashMap::new());
+        let result = f(self);
+        self.scopes.pop();
+        result
+    }
+    /// Infer the body of a loop with `f`, and get the type of the loop.
+    ///
+    /// A loop which may end when it runs out is `Empty`, so `break` only gives `Empty` in it.
+    /// Otherwise it is what `break` gives.
+    pub fn in_loop(
+        &mut self,
+        may_run_out: bool,
+        f: impl FnOnce(&mut Self) -> Result<Type, Error>,
+    ) -> Result<Type, Error> {
+        let output = if may_run_out {
+            Type::Empty
+        } else {
+            self.fresh()
+        };
+        self.loops.push(output.clone());
+        let result = f(self);
+        self.loops.pop();
+        result?;
+        if matches!(self.resolve(&output), Type::Var(_)) {
+            self.unify(&output, &Type::Empty)?;
+        }
+        Ok(output)
+    }
+    /// The type of the values given by `break`, if a loop is being inferred.
+    pub fn loop_output(&self) -> Option<Type> {
+        self.loops.last().cloned()
+    }
+    /// Infer the body of a function with `f`, where the loops outside are not left by `break`.
+    pub fn in_function<T>(
+        &mut self,
+        output: Type,
+        f: impl FnOnce(&mut Self) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let loops = std::mem::take(&mut self.loops);
+        self.functions.push(output);
+        let result = f(self);
+        self.functions.pop();
+        self.loops = loops;
+        result
+    }
+    /// The output type of the function, if a function is being inferred.
+    pub fn function_output(&self) -> Option<Type> {
+        self.functions.last().cloned()
+    }
+    /// Unify the operands of `+`, which are both numbers or both strings.
+    pub fn unify_addable(&mut self, lhs: &Type, rhs: &Type) -> Result<(), TypeError> {
+        self.constrain(lhs, Constraint::Addable)?;
+        self.unify(lhs, rhs)
+    }
+    /// Check the type may be what the constraint allows, or keep the constraint till it is known.
+    fn constrain(&mut self, ty: &Type, constraint: Constraint) -> Result<(), TypeError> {
+        match (self.resolve(ty), constraint) {
+            (Type::Var(var), _) => match self.constraints.insert(var, constraint) {
+                // only a string is both measurable and addable
+                Some(other) if other != constraint => self.unify(&Type::Str, &Type::Var(var)),
+                _ => Ok(()),
+            },
+            (Type::Str | Type::List(_), Constraint::Measurable)
+            | (Type::Int | Type::Str, Constraint::Addable) => Ok(()),
+            (found, Constraint::Measurable) => {
+                Err(TypeError::NotMeasurable(found.normalize().to_string()))
+            }
+            (found, Constraint::Addable) => Err(TypeError::Mismatch {
+                expected: "Int".to_string(),
+                found: found.normalize().to_string(),
+            }),
+        }
+    }
+    fn fresh_constrained(&mut self, constraint: Constraint) -> Type {
+        self.constraints.insert(self.next_var, constraint);
+        self.fresh()
+    }
+    fn builtin(&mut self, builtin: Builtin) -> Type {
+        let (a, b) = (self.fresh(), self.fresh());
+        let (parameters, output) = match builtin {
+            // `len` takes either a string or a list, which no single type describes
+            Builtin::Len => (
+                vec![self.fresh_constrained(Constraint::Measurable)],
+                Type::Int,
+            ),
+            Builtin::Upper | Builtin::Lower => (vec![Type::Str], Type::Str),
+            Builtin::Substr => (vec![Type::Str, Type::Int, Type::Int], Type::Str),
+            Builtin::ToString => (vec![a], Type::Str),
+            Builtin::ParseInt => (vec![Type::Str], Type::Int),
+            Builtin::Push => (vec![Type::list(a.clone()), a.clone()], Type::list(a)),
+            Builtin::Concat => (
+                vec![Type::list(a.clone()), Type::list(a.clone())],
+                Type::list(a),
+            ),
+            Builtin::Map => (
+                vec![Type::function(vec![a.clone()], b.clone()), Type::list(a)],
+                Type::list(b),
+            ),
+            Builtin::Filter => (
+                vec![
+                    Type::function(vec![a.clone()], Type::Int),
+                    Type::list(a.clone()),
+                ],
+                Type::list(a),
+            ),
+            Builtin::Fold => (
+                vec![
+                    Type::function(vec![b.clone(), a.clone()], b.clone()),
+                    b.clone(),
+                    Type::list(a),
+                ],
+                b,
+            ),
+            Builtin::Range => (vec![Type::Int, Type::Int], Type::list(Type::Int)),
+            Builtin::Divmod => (
+                vec![Type::Int, Type::Int],
+                Type::Tuple(vec![Type::Int, Type::Int]),
+            ),
+        };
+        Type::function(parameters, output)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn check(checker: &mut TypeChecker, s: &str) -> Result<String, Error> {
+        let statement = Statement::new(&s.into())?;
+        checker.check(&statement).map(|ty| ty.to_string())
+    }
+    #[test]
+    fn check_expressions() {
+        let checker = &mut TypeChecker::default();
+        assert_eq!(check(checker, "1 + 2"), Ok("Int".to_string()));
+        assert_eq!(check(checker, r#""a" + "b""#), Ok("Str".to_string()));
+        assert_eq!(
+            check(checker, r#"[(1, "a")]"#),
+            Ok("[(Int, Str)]".to_string())
+        );
+        assert_eq!(
+            check(checker, "{ width: 1, name: f\"{1}\" }"),
+            Ok("{ name: Str, width: Int }".to_string())
+        );
+        assert_eq!(check(checker, "{ let x = 1; }"), Ok("Empty".to_string()));
+        assert_eq!(
+            check(checker, "map"),
+            Ok("Fn(Fn('a) -> 'b, ['a]) -> ['b]".to_string())
+        );
+    }
+    #[test]
+    fn check_bindings() {
+        let checker = &mut TypeChecker::default();
+        assert_eq!(
+            check(checker, "let (q, r) = divmod 7 2;"),
+            Ok("Empty".to_string())
+        );
+        assert_eq!(check(checker, "q"), Ok("Int".to_string()));
+        assert_eq!(check(checker, "let xs = [q, r];"), Ok("Empty".to_string()));
+        assert_eq!(check(checker, "xs[1..]"), Ok("[Int]".to_string()));
+    }
+    #[test]
+    fn check_polymorphic_function() {
+        let checker = &mut TypeChecker::default();
+        assert_eq!(check(checker, "fn id x => x"), Ok("Empty".to_string()));
+        assert_eq!(check(checker, "id"), Ok("Fn('a) -> 'a".to_string()));
+        assert_eq!(
+            check(checker, r#"(id 1, id "a")"#),
+            Ok("(Int, Str)".to_string())
+        );
+        assert_eq!(
+            check(checker, "fn twice f x => f (f x)"),
+            Ok("Empty".to_string())
+        );
+        assert_eq!(
+            check(checker, "twice"),
+            Ok("Fn(Fn('a) -> 'a, 'a) -> 'a".to_string())
+        );
+    }
+    #[test]
+    fn check_recursive_function() {
+        let checker = &mut TypeChecker::default();
+        check(
+            checker,
+            "fn sum xs => match len xs { 0 => 0, _ => xs[0] + sum xs[1..] }",
+        )
+        .unwrap();
+        assert_eq!(check(checker, "sum"), Ok("Fn([Int]) -> Int".to_string()));
+    }
+    #[test]
+    fn check_enum_and_match() {
+        let checker = &mut TypeChecker::default();
+        check(checker, "enum Shape { Circle(r), Rect(w, h), Empty }").unwrap();
+        assert_eq!(
+            check(
+                checker,
+                "fn area s => match s { Circle(r) => 3 * r * r, Rect(w, h) if w == h => w * w, _ => 0 }"
+            ),
+            Ok("Empty".to_string())
+        );
+        assert_eq!(check(checker, "area"), Ok("Fn(Shape) -> Int".to_string()));
+        assert_eq!(check(checker, "Rect 1 2"), Ok("Shape".to_string()));
+        assert_eq!(
+            check(checker, r#"Circle "a""#),
+            Err(Error::Type(TypeError::Mismatch {
+                expected: "Int".to_string(),
+                found: "Str".to_string()
+            }))
+        );
+    }
+    #[test]
+    fn check_mismatch() {
+        let checker = &mut TypeChecker::default();
+        check(checker, "fn add_one x => x + 1").unwrap();
+        assert_eq!(
+            check(checker, "add_one + 1"),
+            Err(Error::Type(TypeError::Mismatch {
+                expected: "Int".to_string(),
+                found: "Fn(Int) -> Int".to_string()
+            }))
+        );
+        assert_eq!(
+            check(checker, "add_one 1 2"),
+            Err(Error::Type(TypeError::ArityMismatch {
+                expected: 1,
+                found: 2
+            }))
+        );
+        assert_eq!(
+            check(checker, "{ 1; } + 1"),
+            Err(Error::Type(TypeError::Mismatch {
+                expected: "Int".to_string(),
+                found: "Empty".to_string()
+            }))
+        );
+        assert_eq!(
+            check(checker, "[1, \"a\"]"),
+            Err(Error::Type(TypeError::Mismatch {
+                expected: "Int".to_string(),
+                found: "Str".to_string()
+            }))
+        );
+        assert_eq!(
+            check(checker, "fn f x => x x"),
+            Err(Error::Type(TypeError::InfiniteType))
+        );
+    }
+    #[test]
+    fn check_unknown_identifier() {
+        let checker = &mut TypeChecker::default();
+        assert_eq!(
+            check(checker, "let x = y;"),
+            Err(Error::Type(TypeError::UnknownIdentifier("y".to_string())))
+        );
+        assert_eq!(
+            check(checker, "x"),
+            Err(Error::Type(TypeError::UnknownIdentifier("x".to_string())))
+        );
+    }
+    #[test]
+    fn check_records() {
+        let checker = &mut TypeChecker::default();
+        check(checker, "let r = { width: 10, height: 20 };").unwrap();
+        assert_eq!(check(checker, "r.width"), Ok("Int".to_string()));
+        assert_eq!(
+            check(checker, "{ ..r, width: 5 }"),
+            Ok("{ height: Int, width: Int }".to_string())
+        );
+        assert_eq!(
+            check(checker, "r.depth"),
+            Err(Error::Type(TypeError::FieldNotFound("depth".to_string())))
+        );
+        assert_eq!(
+            check(checker, r#"{ ..r, width: "a" }"#),
+            Err(Error::Type(TypeError::Mismatch {
+                expected: "Int".to_string(),
+                found: "Str".to_string()
+            }))
+        );
+    }
+    #[test]
+    fn check_record_of_unknown_type() {
+        let checker = &mut TypeChecker::default();
+        assert_eq!(
+            check(checker, "fn w r => r.width;"),
+            Err(Error::Type(TypeError::UnknownRecord("width".to_string())))
+        );
+        assert_eq!(
+            check(checker, "fn u r => { ..r, width: 5 };"),
+            Err(Error::Type(TypeError::UnknownRecord("width".to_string())))
+        );
+        check(checker, "fn w (r: { width: Int }) => r.width;").unwrap();
+        assert_eq!(check(checker, "w { width: 1 }"), Ok("Int".to_string()));
+    }
+    #[test]
+    fn check_len() {
+        let checker = &mut TypeChecker::default();
+        assert_eq!(check(checker, r#"len "abc""#), Ok("Int".to_string()));
+        assert_eq!(check(checker, "len [1, 2]"), Ok("Int".to_string()));
+        assert_eq!(
+            check(checker, "len 5"),
+            Err(Error::Type(TypeError::NotMeasurable("Int".to_string())))
+        );
+        // a function of `len` takes anything which `len` does
+        check(checker, "fn f xs => len xs;").unwrap();
+        assert_eq!(check(checker, "f [1]"), Ok("Int".to_string()));
+        assert_eq!(check(checker, r#"f "ab""#), Ok("Int".to_string()));
+        assert_eq!(
+            check(checker, "f 5"),
+            Err(Error::Type(TypeError::NotMeasurable("Int".to_string())))
+        );
+        assert_eq!(
+            check(checker, "map len [1]"),
+            Err(Error::Type(TypeError::NotMeasurable("Int".to_string())))
+        );
+        check(checker, "fn size xs => { let n = len xs; push xs n };").unwrap();
+        assert_eq!(check(checker, "size"), Ok("Fn([Int]) -> [Int]".to_string()));
+    }
+    #[test]
+    fn check_add() {
+        let checker = &mut TypeChecker::default();
+        check(checker, r#"fn greet s => s + "!";"#).unwrap();
+        assert_eq!(check(checker, "greet"), Ok("Fn(Str) -> Str".to_string()));
+        check(checker, "fn add x y => x + y;").unwrap();
+        assert_eq!(check(checker, "add"), Ok("Fn(Int, Int) -> Int".to_string()));
+        assert_eq!(
+            check(checker, "[1] + [2]"),
+            Err(Error::Type(TypeError::Mismatch {
+                expected: "Int".to_string(),
+                found: "[Int]".to_string()
+            }))
+        );
+        // a string is the only value which is both added and measured
+        check(checker, "fn twice s => { let n = len s; s + s };").unwrap();
+        assert_eq!(check(checker, "twice"), Ok("Fn(Str) -> Str".to_string()));
+    }
+}
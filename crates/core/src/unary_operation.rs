@@ -0,0 +1,113 @@
+use crate::internal::prelude::*;
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UnaryOperation {
+    operand: Box<Expression>,
+    op: UnaryOperator,
+}
+
+impl UnaryOperation {
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        let op = UnaryOperator::from_prefix(s).ok_or(OperationError::NotFound)?;
+        let (op, operand) = s.split_at(op.symbol().len());
+        let operand = Expression::new(&operand.into())?;
+        if operand.is_empty() {
+            return Err(OperationError::InvalidOperand)?;
+        }
+        Ok(Self {
+            operand: operand.into(),
+            op: UnaryOperator::new(&op.into())?,
+        })
+    }
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        self.operand.resolve(resolver)
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        self.operand.lint(linter);
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<Type, Error> {
+        let operand = self.operand.infer(checker)?;
+        checker.unify(&Type::Int, &operand)?;
+        Ok(Type::Int)
+    }
+    pub fn eval(&self, env: &Environment) -> Result<Value, Error> {
+        let Value::Number(operand) = self.operand.eval(env)? else {
+            return Err(OperationError::InvalidOperand)?;
+        };
+        let operand = operand.inner();
+        let value = match self.op {
+            UnaryOperator::Neg => operand.checked_neg().ok_or(OperationError::Overflow)?,
+            UnaryOperator::BitNot => !operand,
+        };
+        Ok(Value::Number(Number::from_i32(value)))
+    }
+}
+impl std::fmt::Display for UnaryOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.op)?;
+        self.operand.fmt_term(f)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parse_bit_not() {
+        assert_eq!(
+            UnaryOperation::new(&"~x".into()),
+            Ok(UnaryOperation {
+                operand: Expression::Binding(Identifier::new(&"x".into()).unwrap()).into(),
+                op: UnaryOperator::BitNot
+            })
+        );
+    }
+    #[test]
+    fn parse_without_operator() {
+        assert_eq!(
+            UnaryOperation::new(&"x".into()),
+            Err(Error::Operation(OperationError::NotFound))
+        );
+    }
+    #[test]
+    fn parse_without_operand() {
+        assert_eq!(
+            UnaryOperation::new(&"~ ".into()),
+            Err(Error::Operation(OperationError::InvalidOperand))
+        );
+    }
+    #[test]
+    fn eval_bit_not() {
+        assert_eq!(
+            UnaryOperation::new(&"~0xF0".into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Ok(Value::Number(Number::from_i32(!0xF0)))
+        );
+    }
+    #[test]
+    fn eval_neg() {
+        let env = &mut Environment::default();
+        BindingDef::new(&"let x = 5".into())
+            .unwrap()
+            .store(env)
+            .unwrap();
+        assert_eq!(
+            UnaryOperation::new(&"-x".into()).unwrap().eval(env),
+            Ok(Value::Number(Number::from_i32(-5)))
+        );
+        assert_eq!(
+            UnaryOperation::new(&"-(-2147483648)".into())
+                .unwrap()
+                .eval(env),
+            Err(Error::Operation(OperationError::Overflow))
+        );
+    }
+    #[test]
+    fn eval_empty_operand() {
+        assert_eq!(
+            UnaryOperation::new(&"~{}".into())
+                .unwrap()
+                .eval(&Environment::default()),
+            Err(Error::Operation(OperationError::InvalidOperand))
+        );
+    }
+}
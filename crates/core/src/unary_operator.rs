@@ -0,0 +1,58 @@
+use crate::internal::prelude::*;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum UnaryOperator {
+    Neg,
+    BitNot,
+}
+impl UnaryOperator {
+    const ALL: [Self; 2] = [Self::Neg, Self::BitNot];
+    pub fn new(s: &TrimmedStr) -> Result<Self, OperatorError> {
+        Self::ALL
+            .into_iter()
+            .find(|op| op.symbol() == &**s)
+            .ok_or(OperatorError::InvalidOperator)
+    }
+    /// Find the operator which `s` starts with.
+    pub fn from_prefix(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|op| s.starts_with(op.symbol()))
+    }
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Self::Neg => "-",
+            Self::BitNot => "~",
+        }
+    }
+}
+impl std::fmt::Display for UnaryOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parse_neg_op() {
+        assert_eq!(UnaryOperator::new(&"-".into()), Ok(UnaryOperator::Neg));
+    }
+    #[test]
+    fn parse_bit_not_op() {
+        assert_eq!(UnaryOperator::new(&"~".into()), Ok(UnaryOperator::BitNot));
+    }
+    #[test]
+    fn parse_invalid_operator() {
+        assert_eq!(
+            UnaryOperator::new(&"+".into()),
+            Err(OperatorError::InvalidOperator)
+        );
+    }
+    #[test]
+    fn from_prefix() {
+        assert_eq!(
+            UnaryOperator::from_prefix("~x"),
+            Some(UnaryOperator::BitNot)
+        );
+        assert_eq!(UnaryOperator::from_prefix("x"), None);
+    }
+}
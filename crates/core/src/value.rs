@@ -1,18 +1,342 @@
 use crate::internal::prelude::*;
+use std::collections::BTreeMap;
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Value {
     Number(Number),
+    Str(String),
+    List(Vec<Value>),
+    Tuple(Vec<Value>),
+    Record(BTreeMap<String, Value>),
+    Variant(Constructor, Vec<Value>),
+    Function(Function),
     Empty,
 }
 
+impl Value {
+    pub const fn type_name(&self) -> &'static str {
+        match self {
+            Self::Number(_) => "Int",
+            Self::Str(_) => "Str",
+            Self::List(_) => "List",
+            Self::Tuple(_) => "Tuple",
+            Self::Record(_) => "Record",
+            Self::Variant(..) => "Enum",
+            Self::Function(_) => "Fn",
+            Self::Empty => "Empty",
+        }
+    }
+    pub fn try_into_number(self) -> Result<Number, ValueError> {
+        match self {
+            Self::Number(number) => Ok(number),
+            value => Err(ValueError::TypeMismatch {
+                expected: "Int",
+                found: value.type_name(),
+            }),
+        }
+    }
+    pub fn try_into_str(self) -> Result<String, ValueError> {
+        match self {
+            Self::Str(s) => Ok(s),
+            value => Err(ValueError::TypeMismatch {
+                expected: "Str",
+                found: value.type_name(),
+            }),
+        }
+    }
+    /// A non-zero number is true, as it is in C.
+    pub fn try_into_condition(self) -> Result<bool, ValueError> {
+        self.try_into_number().map(|number| number.inner() != 0)
+    }
+    pub fn try_into_list(self) -> Result<Vec<Self>, ValueError> {
+        match self {
+            Self::List(list) => Ok(list),
+            value => Err(ValueError::TypeMismatch {
+                expected: "List",
+                found: value.type_name(),
+            }),
+        }
+    }
+    pub fn try_into_record(self) -> Result<BTreeMap<String, Self>, ValueError> {
+        match self {
+            Self::Record(record) => Ok(record),
+            value => Err(ValueError::TypeMismatch {
+                expected: "Record",
+                found: value.type_name(),
+            }),
+        }
+    }
+    pub fn try_into_function(self) -> Result<Function, ValueError> {
+        match self {
+            Self::Function(function) => Ok(function),
+            value => Err(ValueError::TypeMismatch {
+                expected: "Fn",
+                found: value.type_name(),
+            }),
+        }
+    }
+}
+
+impl Value {
+    /// Whether the value is of the type, where the type of a function is only checked by arity.
+    pub fn is_of_type(&self, ty: &Type) -> bool {
+        match (self, ty) {
+            (_, Type::Var(_))
+            | (Self::Number(_), Type::Int)
+            | (Self::Str(_), Type::Str)
+            | (Self::Empty, Type::Empty) => true,
+            (Self::List(values), Type::List(ty)) => values.iter().all(|value| value.is_of_type(ty)),
+            (Self::Tuple(values), Type::Tuple(types)) => {
+                values.len() == types.len()
+                    && values
+                        .iter()
+                        .zip(types)
+                        .all(|(value, ty)| value.is_of_type(ty))
+            }
+            (Self::Record(values), Type::Record(types)) => {
+                values.keys().eq(types.keys())
+                    && values
+                        .values()
+                        .zip(types.values())
+                        .all(|(value, ty)| value.is_of_type(ty))
+            }
+            (Self::Function(function), Type::Function(parameters, _)) => function
+                .arity()
+                .is_none_or(|arity| arity == parameters.len()),
+            (Self::Variant(constructor, _), Type::Enum(name)) => constructor.enum_name() == name,
+            _ => false,
+        }
+    }
+    /// The expression which evaluates to the value, like `"a"` for a string or `Some 1` for a variant.
+    pub fn to_source(&self) -> String {
+        let join = |values: &[Self]| {
+            values
+                .iter()
+                .map(Self::to_source)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            Self::Number(number) => number.to_string(),
+            Self::Str(s) => format!("{s:?}"),
+            Self::List(list) => format!("[{}]", join(list)),
+            Self::Tuple(tuple) if tuple.len() == 1 => format!("({},)", join(tuple)),
+            Self::Tuple(tuple) => format!("({})", join(tuple)),
+            Self::Record(record) => {
+                let fields = record.iter().map(|(name, value)| {
+                    let raw = if Keyword::new(name).is_some() {
+                        "r#"
+                    } else {
+                        ""
+                    };
+                    format!("{raw}{name}: {}", value.to_source())
+                });
+                format!("{{ {} }}", fields.collect::<Vec<_>>().join(", "))
+            }
+            Self::Variant(constructor, fields) => {
+                let mut source = constructor.name().to_string();
+                for field in fields {
+                    // an argument is a single term, so `Some (-1)` is not a subtraction
+                    let is_term = match field {
+                        Self::Number(number) => number.inner() >= 0,
+                        Self::Variant(_, fields) => fields.is_empty(),
+                        _ => true,
+                    };
+                    if is_term {
+                        source = format!("{source} {}", field.to_source());
+                    } else {
+                        source = format!("{source} ({})", field.to_source());
+                    }
+                }
+                source
+            }
+            Self::Function(function) => function.name().to_string(),
+            Self::Empty => "{}".to_string(),
+        }
+    }
+    fn fmt_elements(values: &[Self], f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, value) in values.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            value.fmt_element(f)?;
+        }
+        Ok(())
+    }
+    fn fmt_element(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // strings are quoted, so that `["a, b"]` is not taken as two elements
+        match self {
+            Self::Str(s) => write!(f, "{s:?}"),
+            value => write!(f, "{value}"),
+        }
+    }
+}
+
 impl std::fmt::Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Number(number) => write!(f, "{}", number.inner()),
+            Self::Str(s) => write!(f, "{s}"),
+            Self::List(list) => {
+                write!(f, "[")?;
+                Self::fmt_elements(list, f)?;
+                write!(f, "]")
+            }
+            Self::Tuple(tuple) => {
+                write!(f, "(")?;
+                Self::fmt_elements(tuple, f)?;
+                if tuple.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            Self::Record(record) => {
+                write!(f, "{{")?;
+                for (i, (name, value)) in record.iter().enumerate() {
+                    write!(f, "{} {name}: ", if i > 0 { "," } else { "" })?;
+                    value.fmt_element(f)?;
+                }
+                write!(f, " }}")
+            }
+            Self::Variant(constructor, fields) => {
+                write!(f, "{}", constructor.name())?;
+                if !fields.is_empty() {
+                    write!(f, "(")?;
+                    Self::fmt_elements(fields, f)?;
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+            Self::Function(function) => write!(f, "{function}"),
             Self::Empty => write!(f, ""),
         }
     }
 }
 
+/// Values written shortly in tests, like `Value::from(1)` and `Value::from("a")`.
 #[cfg(test)]
-mod tests {}
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Self::Number(Number::from_i32(value))
+    }
+}
+#[cfg(test)]
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Self::Str(s.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn to_source() {
+        let record = BTreeMap::from([
+            ("in".to_string(), Value::from(1)),
+            ("name".to_string(), Value::Str("a\"b\n".to_string())),
+        ]);
+        assert_eq!(
+            Value::Tuple(vec![
+                Value::List(vec![Value::from(-1)]),
+                Value::Record(record)
+            ])
+            .to_source(),
+            r#"([-1], { r#in: 1, name: "a\"b\n" })"#
+        );
+        assert_eq!(Value::Tuple(vec![Value::Empty]).to_source(), "({},)");
+        assert_eq!(
+            Value::Function(Function::Builtin(Builtin::Len)).to_source(),
+            "len"
+        );
+    }
+    #[test]
+    fn type_name() {
+        assert_eq!(Value::Number(Number::from_i32(1)).type_name(), "Int");
+        assert_eq!(Value::Str(String::new()).type_name(), "Str");
+        assert_eq!(Value::List(vec![]).type_name(), "List");
+        assert_eq!(Value::Tuple(vec![]).type_name(), "Tuple");
+        assert_eq!(Value::Record(BTreeMap::new()).type_name(), "Record");
+        assert_eq!(Value::Empty.type_name(), "Empty");
+    }
+    #[test]
+    fn is_of_type() {
+        let number = Value::Number(Number::from_i32(1));
+        assert!(number.is_of_type(&Type::Int));
+        assert!(!number.is_of_type(&Type::Str));
+        assert!(Value::List(vec![number.clone()]).is_of_type(&Type::list(Type::Int)));
+        assert!(!Value::List(vec![number.clone()]).is_of_type(&Type::list(Type::Str)));
+        assert!(
+            Value::Tuple(vec![number.clone(), Value::Str(String::new())])
+                .is_of_type(&Type::Tuple(vec![Type::Int, Type::Str]))
+        );
+        assert!(!Value::Tuple(vec![number]).is_of_type(&Type::Tuple(vec![Type::Int, Type::Int])));
+        assert!(
+            Value::Function(Function::Builtin(Builtin::Len))
+                .is_of_type(&Type::function(vec![Type::Str], Type::Int))
+        );
+    }
+    #[test]
+    fn try_into_something() {
+        assert_eq!(
+            Value::Number(Number::from_i32(1)).try_into_number(),
+            Ok(Number::from_i32(1))
+        );
+        assert_eq!(
+            Value::Str("a".to_string()).try_into_number(),
+            Err(ValueError::TypeMismatch {
+                expected: "Int",
+                found: "Str"
+            })
+        );
+        assert_eq!(
+            Value::Str("a".to_string()).try_into_str(),
+            Ok("a".to_string())
+        );
+        assert_eq!(Value::List(vec![]).try_into_list(), Ok(vec![]));
+        assert_eq!(
+            Value::Empty.try_into_list(),
+            Err(ValueError::TypeMismatch {
+                expected: "List",
+                found: "Empty"
+            })
+        );
+        assert_eq!(
+            Value::Empty.try_into_str(),
+            Err(ValueError::TypeMismatch {
+                expected: "Str",
+                found: "Empty"
+            })
+        );
+    }
+    #[test]
+    fn display() {
+        assert_eq!(Value::Number(Number::from_i32(-1)).to_string(), "-1");
+        assert_eq!(Value::Str("a\"b".to_string()).to_string(), "a\"b");
+        assert_eq!(Value::Empty.to_string(), "");
+        assert_eq!(
+            Value::List(vec![
+                Value::Number(Number::from_i32(1)),
+                Value::Str("a, b".to_string()),
+                Value::List(vec![])
+            ])
+            .to_string(),
+            r#"[1, "a, b", []]"#
+        );
+        assert_eq!(
+            Value::Tuple(vec![
+                Value::Number(Number::from_i32(1)),
+                Value::Tuple(vec![Value::Str("a".to_string())])
+            ])
+            .to_string(),
+            r#"(1, ("a",))"#
+        );
+        assert_eq!(
+            Value::Record(BTreeMap::from([
+                ("width".to_string(), Value::Number(Number::from_i32(10))),
+                ("name".to_string(), Value::Str("a".to_string()))
+            ]))
+            .to_string(),
+            r#"{ name: "a", width: 10 }"#
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use crate::internal::prelude::*;
+use std::collections::BTreeMap;
+
+/// The static type of a value, where `Var` is a type to be inferred.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Type {
+    Int,
+    Str,
+    List(Box<Type>),
+    Tuple(Vec<Type>),
+    Record(BTreeMap<String, Type>),
+    Function(Vec<Type>, Box<Type>),
+    Enum(Identifier),
+    Empty,
+    Var(usize),
+}
+impl Type {
+    /// Parse an annotation like `Int`, `[Str]`, `(Int, Str)`, `{ width: Int }`,
+    /// `Fn(Int) -> Int` or the name of an enum.
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        let invalid = || AnnotationError::InvalidType(s.to_string());
+        match &**s {
+            "Int" => return Ok(Self::Int),
+            "Str" => return Ok(Self::Str),
+            "Empty" => return Ok(Self::Empty),
+            _ => (),
+        }
+        if let Some(element) = s.strip_wrapping('[', ']') {
+            return Ok(Self::list(Self::new(&element)?));
+        }
+        if let Some(inner) = s.strip_wrapping('(', ')') {
+            if !TopLevelChars::new(&inner).any(|(_, c)| c == ',') {
+                return Self::new(&inner);
+            }
+            return Ok(Self::Tuple(Self::new_list(&inner)?));
+        }
+        if let Some(inner) = s.strip_wrapping('{', '}') {
+            let mut fields = BTreeMap::new();
+            for field in inner.split_list() {
+                let (name, ty) = field.split_once(':').ok_or_else(invalid)?;
+                let name = Identifier::new(&name.into())?;
+                fields.insert(name.to_string(), Self::new(&ty.into())?);
+            }
+            return Ok(Self::Record(fields));
+        }
+        if let Some(rest) = s.strip_prefix("Fn").filter(|rest| rest.starts_with('(')) {
+            let (arrow, _) = TopLevelChars::new(rest)
+                .find(|&(nth, _)| rest[nth..].starts_with("->"))
+                .ok_or_else(invalid)?;
+            let parameters = TrimmedStr::new(&rest[..arrow])
+                .strip_wrapping('(', ')')
+                .ok_or_else(invalid)?;
+            return Ok(Self::function(
+                Self::new_list(&parameters)?,
+                Self::new(&rest[arrow + "->".len()..].into())?,
+            ));
+        }
+        let name = Identifier::new(s).map_err(|_| invalid())?;
+        Ok(Self::Enum(name))
+    }
+    fn new_list(s: &TrimmedStr) -> Result<Vec<Self>, Error> {
+        s.split_list().iter().map(Self::new).collect()
+    }
+    pub fn function(parameters: Vec<Self>, output: Self) -> Self {
+        Self::Function(parameters, output.into())
+    }
+    pub fn list(element: Self) -> Self {
+        Self::List(element.into())
+    }
+    /// The type variables in the type, in the order they appear.
+    pub fn vars(&self) -> Vec<usize> {
+        let mut vars = Vec::new();
+        self.collect_vars(&mut vars);
+        vars
+    }
+    fn collect_vars(&self, vars: &mut Vec<usize>) {
+        match self {
+            Self::Var(var) if !vars.contains(var) => vars.push(*var),
+            Self::List(element) => element.collect_vars(vars),
+            Self::Tuple(types) => types.iter().for_each(|ty| ty.collect_vars(vars)),
+            Self::Record(fields) => fields.values().for_each(|ty| ty.collect_vars(vars)),
+            Self::Function(parameters, output) => {
+                parameters.iter().for_each(|ty| ty.collect_vars(vars));
+                output.collect_vars(vars);
+            }
+            _ => (),
+        }
+    }
+    /// Replace the type variables with the types which `f` maps them to.
+    pub fn map_vars(&self, f: &impl Fn(usize) -> Option<Self>) -> Self {
+        match self {
+            Self::Var(var) => f(*var).unwrap_or(Self::Var(*var)),
+            Self::List(element) => Self::list(element.map_vars(f)),
+            Self::Tuple(types) => Self::Tuple(types.iter().map(|ty| ty.map_vars(f)).collect()),
+            Self::Record(fields) => Self::Record(
+                fields
+                    .iter()
+                    .map(|(name, ty)| (name.clone(), ty.map_vars(f)))
+                    .collect(),
+            ),
+            Self::Function(parameters, output) => Self::function(
+                parameters.iter().map(|ty| ty.map_vars(f)).collect(),
+                output.map_vars(f),
+            ),
+            ty => ty.clone(),
+        }
+    }
+    /// Name the type variables `'a`, `'b` and so on, in the order they appear.
+    pub fn normalize(&self) -> Self {
+        let vars = self.vars();
+        self.map_vars(&|var| vars.iter().position(|&found| found == var).map(Self::Var))
+    }
+    fn fmt_list(types: &[Self], f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, ty) in types.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{ty}")?;
+        }
+        Ok(())
+    }
+}
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Int => write!(f, "Int"),
+            Self::Str => write!(f, "Str"),
+            Self::List(element) => write!(f, "[{element}]"),
+            Self::Tuple(types) => {
+                write!(f, "(")?;
+                Self::fmt_list(types, f)?;
+                if types.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            Self::Record(fields) => {
+                write!(f, "{{")?;
+                for (i, (name, ty)) in fields.iter().enumerate() {
+                    write!(f, "{} {name}: {ty}", if i > 0 { "," } else { "" })?;
+                }
+                write!(f, " }}")
+            }
+            Self::Function(parameters, output) => {
+                write!(f, "Fn(")?;
+                Self::fmt_list(parameters, f)?;
+                write!(f, ") -> {output}")
+            }
+            Self::Enum(name) => write!(f, "{name}"),
+            Self::Empty => write!(f, "Empty"),
+            // `'a` to `'z`, and then `'t26` and so on
+            Self::Var(var) => match u8::try_from(*var).ok().filter(|&var| var < 26) {
+                Some(var) => write!(f, "'{}", char::from(b'a' + var)),
+                None => write!(f, "'t{var}"),
+            },
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parse_type() {
+        let parse = |s: &str| Type::new(&s.into());
+        assert_eq!(parse("Int"), Ok(Type::Int));
+        assert_eq!(
+            parse("[(Int, Str)]"),
+            Ok(Type::list(Type::Tuple(vec![Type::Int, Type::Str])))
+        );
+        assert_eq!(
+            parse("{ width: Int, name: Str }"),
+            Ok(Type::Record(BTreeMap::from([
+                ("width".to_string(), Type::Int),
+                ("name".to_string(), Type::Str)
+            ])))
+        );
+        assert_eq!(
+            parse("Fn(Int, Fn(Int) -> Int) -> Shape"),
+            Ok(Type::function(
+                vec![Type::Int, Type::function(vec![Type::Int], Type::Int)],
+                Type::Enum("Shape".try_into().unwrap())
+            ))
+        );
+        assert_eq!(parse("(Str)"), Ok(Type::Str));
+    }
+    #[test]
+    fn parse_invalid_type() {
+        assert_eq!(
+            Type::new(&"Fn(Int)".into()),
+            Err(Error::Annotation(AnnotationError::InvalidType(
+                "Fn(Int)".to_string()
+            )))
+        );
+        assert_eq!(
+            Type::new(&"1".into()),
+            Err(Error::Annotation(AnnotationError::InvalidType(
+                "1".to_string()
+            )))
+        );
+        assert_eq!(
+            Type::new(&"{ Int }".into()),
+            Err(Error::Annotation(AnnotationError::InvalidType(
+                "{ Int }".to_string()
+            )))
+        );
+    }
+    #[test]
+    fn display() {
+        assert_eq!(
+            Type::function(vec![Type::list(Type::Int), Type::Var(1)], Type::Str).to_string(),
+            "Fn([Int], 'b) -> Str"
+        );
+        assert_eq!(
+            Type::Tuple(vec![Type::Int, Type::Tuple(vec![Type::Str])]).to_string(),
+            "(Int, (Str,))"
+        );
+        assert_eq!(
+            Type::Record(BTreeMap::from([
+                ("width".to_string(), Type::Int),
+                ("name".to_string(), Type::Str)
+            ]))
+            .to_string(),
+            "{ name: Str, width: Int }"
+        );
+        assert_eq!(Type::Var(30).to_string(), "'t30");
+    }
+    #[test]
+    fn normalize() {
+        assert_eq!(
+            Type::function(vec![Type::Var(7), Type::Var(3)], Type::Var(7)).normalize(),
+            Type::function(vec![Type::Var(0), Type::Var(1)], Type::Var(0))
+        );
+    }
+}
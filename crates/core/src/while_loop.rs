@@ -0,0 +1,114 @@
+use crate::internal::prelude::*;
+
+/// A loop like `while i != 10 { i += 1; }`, whose value is what `break` gives, or `Empty`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct WhileLoop {
+    condition: Box<Expression>,
+    body: Block,
+}
+impl WhileLoop {
+    pub fn new(s: &TrimmedStr) -> Result<Self, Error> {
+        let s = Keyword::While.strip(s).ok_or(LoopError::NotFound)?;
+        let (condition, body) = Block::split_trailing(s).ok_or(LoopError::MissingBody)?;
+        let condition = Expression::new(&condition.into())?;
+        if condition.is_empty() {
+            return Err(LoopError::MissingCondition)?;
+        }
+        Ok(Self {
+            condition: condition.into(),
+            body,
+        })
+    }
+    pub fn resolve(&self, resolver: &mut Resolver) -> Result<(), Error> {
+        self.condition.resolve(resolver)?;
+        resolver.scoped(|resolver| self.body.resolve(resolver))
+    }
+    pub fn lint(&self, linter: &mut Linter) {
+        self.condition.lint(linter);
+        linter.scoped(|linter| self.body.lint(linter));
+    }
+    pub fn infer(&self, checker: &mut TypeChecker) -> Result<Type, Error> {
+        let condition = self.condition.infer(checker)?;
+        checker.unify(&Type::Int, &condition)?;
+        // only `break` ends a loop like `while 1 { ... }`
+        let may_run_out =
+            !matches!(*self.condition, Expression::Number(number) if number.inner() != 0);
+        checker.in_loop(may_run_out, |checker| {
+            checker.scoped(|checker| self.body.infer(checker))
+        })
+    }
+    pub fn eval(&self, env: &Environment) -> Result<Value, Error> {
+        while self.condition.eval(env)?.try_into_condition()? {
+            if let Some(value) = ControlFlow::iterate(&self.body, env)? {
+                return Ok(value);
+            }
+        }
+        Ok(Value::Empty)
+    }
+}
+impl std::fmt::Display for WhileLoop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ", Keyword::While)?;
+        self.condition.fmt(f)?;
+        write!(f, " ")?;
+        self.body.fmt(f)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn eval(s: &str, env: &mut Environment) -> Result<Value, Error> {
+        let statement = Statement::new(&s.into())?;
+        statement.get_expression_in(env)?.eval(env)
+    }
+    #[test]
+    fn parse_while_loop() {
+        assert_eq!(
+            WhileLoop::new(&"while i != 10 { i += 1; }".into()),
+            Ok(WhileLoop {
+                condition: Expression::new(&"i != 10".into()).unwrap().into(),
+                body: Block::new(&"{ i += 1; }".into()).unwrap()
+            })
+        );
+        assert_eq!(
+            WhileLoop::new(&"while { }".into()),
+            Err(Error::Loop(LoopError::MissingCondition))
+        );
+        assert_eq!(
+            WhileLoop::new(&"while 1".into()),
+            Err(Error::Loop(LoopError::MissingBody))
+        );
+    }
+    #[test]
+    fn eval_while_loop() {
+        let env = &mut Environment::default();
+        eval("let mut i = 0;", env).unwrap();
+        eval("let mut sum = 0;", env).unwrap();
+        assert_eq!(
+            eval("while i != 5 { i += 1; sum += i; }", env),
+            Ok(Value::Empty)
+        );
+        assert_eq!(eval("sum", env), Ok(Value::Number(Number::from_i32(15))));
+    }
+    #[test]
+    fn eval_with_break_and_continue() {
+        let env = &mut Environment::default();
+        eval("let mut i = 0;", env).unwrap();
+        assert_eq!(
+            eval(
+                "while 1 { i += 1; match i { 3 => break i * 10, _ => continue } }",
+                env
+            ),
+            Ok(Value::Number(Number::from_i32(30)))
+        );
+    }
+    #[test]
+    fn eval_with_step_budget() {
+        let env = &mut Environment::default();
+        env.set_step_budget(Some(100));
+        assert_eq!(
+            eval("while 1 { }", env),
+            Err(Error::Loop(LoopError::StepBudgetExceeded))
+        );
+    }
+}
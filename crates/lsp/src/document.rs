@@ -0,0 +1,294 @@
+use const_lang::{Level, Linter, Parser, Symbol, SymbolKind};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, Diagnostic, DiagnosticSeverity, DocumentSymbol, Hover,
+    HoverContents, MarkupContent, MarkupKind, NumberOrString, Position, Range,
+};
+
+/// An open file, with what has been found by checking it, which never runs it.
+pub struct Document {
+    text: String,
+    symbols: Vec<Symbol>,
+    /// The parser which has checked the file, holding the types and definitions of its names.
+    parser: Parser<'static>,
+    diagnostics: Vec<Diagnostic>,
+}
+impl Document {
+    pub const SOURCE: &str = "const-lang";
+    pub fn new(text: String) -> Self {
+        let mut parser = Parser::with_type_check();
+        let mut diagnostics: Vec<_> = parser
+            .diagnose(&text)
+            .into_iter()
+            .map(|diagnostic| Diagnostic {
+                range: Self::range(&text, diagnostic.span),
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some(Self::SOURCE.to_string()),
+                message: diagnostic.message,
+                ..Default::default()
+            })
+            .collect();
+        // a statement which cannot be parsed is already reported, and stops the linter
+        for lint in Linter::default().lint(&text).unwrap_or_default() {
+            let severity = match lint.level {
+                Level::Deny => DiagnosticSeverity::ERROR,
+                _ => DiagnosticSeverity::WARNING,
+            };
+            diagnostics.push(Diagnostic {
+                range: Self::line_range(&text, lint.line - 1),
+                severity: Some(severity),
+                code: Some(NumberOrString::String(lint.rule.name().to_string())),
+                source: Some(Self::SOURCE.to_string()),
+                message: lint.message,
+                ..Default::default()
+            });
+        }
+        Self {
+            symbols: Symbol::find(&text),
+            text,
+            parser,
+            diagnostics,
+        }
+    }
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.diagnostics.clone()
+    }
+    /// Show the type of the name at `position`, and its definition if it is defined
+    /// at the top level.
+    pub fn hover(&mut self, position: Position) -> Option<Hover> {
+        let offset = Self::offset(&self.text, position);
+        let (start, word) = Self::word_at(&self.text, offset)?;
+        let symbol = Symbol::lookup(&self.symbols, word, offset);
+        let value = match symbol {
+            Some(symbol) if !symbol.top_level => format!("```\n{}\n```", symbol.detail),
+            None if !Parser::is_builtin(word) => return None,
+            _ => {
+                let ty = self.parser.type_of(word).ok()?;
+                match self.parser.definition(word) {
+                    Some(definition) => {
+                        format!("```\n{word}: {ty}\n```\n---\n```\n{definition}\n```")
+                    }
+                    None => format!("```\n{word}: {ty}\n```"),
+                }
+            }
+        };
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: Some(Self::range(&self.text, start..start + word.len())),
+        })
+    }
+    /// Find where the name at `position` is defined.
+    pub fn definition(&self, position: Position) -> Option<Range> {
+        let offset = Self::offset(&self.text, position);
+        let (_, word) = Self::word_at(&self.text, offset)?;
+        let symbol = Symbol::lookup(&self.symbols, word, offset)?;
+        Some(Self::range(
+            &self.text,
+            symbol.start..symbol.start + symbol.name.len(),
+        ))
+    }
+    /// The functions and bindings defined in the file.
+    #[allow(deprecated)]
+    pub fn symbols(&self) -> Vec<DocumentSymbol> {
+        self.symbols
+            .iter()
+            .filter_map(|symbol| {
+                let kind = match symbol.kind {
+                    SymbolKind::Function => lsp_types::SymbolKind::FUNCTION,
+                    SymbolKind::Binding => lsp_types::SymbolKind::VARIABLE,
+                    _ => return None,
+                };
+                let range = Self::range(&self.text, symbol.start..symbol.start + symbol.name.len());
+                Some(DocumentSymbol {
+                    name: symbol.name.clone(),
+                    detail: Some(symbol.detail.clone()),
+                    kind,
+                    tags: None,
+                    deprecated: None,
+                    range,
+                    selection_range: range,
+                    children: None,
+                })
+            })
+            .collect()
+    }
+    /// The names which can be written at `position`, starting with the word before it.
+    pub fn completions(&self, position: Position) -> Vec<CompletionItem> {
+        let offset = Self::offset(&self.text, position);
+        let prefix =
+            Self::word_at(&self.text, offset).map_or("", |(start, word)| &word[..offset - start]);
+        let mut items: Vec<CompletionItem> = Vec::new();
+        let push = |items: &mut Vec<CompletionItem>, item: CompletionItem| {
+            if item.label.starts_with(prefix) && items.iter().all(|other| other.label != item.label)
+            {
+                items.push(item);
+            }
+        };
+        // the innermost symbols come first, as they shadow the others
+        let mut visible: Vec<_> = Symbol::visible(&self.symbols, offset).collect();
+        visible.sort_by_key(|symbol| std::cmp::Reverse(symbol.start));
+        for symbol in visible {
+            let kind = match symbol.kind {
+                SymbolKind::Function => CompletionItemKind::FUNCTION,
+                SymbolKind::Parameter | SymbolKind::Binding => CompletionItemKind::VARIABLE,
+                SymbolKind::Enum => CompletionItemKind::ENUM,
+                SymbolKind::Variant => CompletionItemKind::ENUM_MEMBER,
+            };
+            let item = CompletionItem {
+                label: symbol.name.clone(),
+                kind: Some(kind),
+                detail: Some(symbol.detail.clone()),
+                ..Default::default()
+            };
+            push(&mut items, item);
+        }
+        for name in self.parser.names() {
+            let kind = if Parser::is_keyword(&name) {
+                CompletionItemKind::KEYWORD
+            } else if Parser::is_builtin(&name) {
+                CompletionItemKind::FUNCTION
+            } else {
+                CompletionItemKind::VARIABLE
+            };
+            let item = CompletionItem {
+                label: name,
+                kind: Some(kind),
+                ..Default::default()
+            };
+            push(&mut items, item);
+        }
+        items
+    }
+    /// The word at `offset`, which may also end there, with where it starts.
+    fn word_at(text: &str, offset: usize) -> Option<(usize, &str)> {
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+        let start = text[..offset]
+            .char_indices()
+            .rev()
+            .take_while(|&(_, c)| is_word(c))
+            .last()
+            .map_or(offset, |(start, _)| start);
+        let end = text[offset..]
+            .find(|c: char| !is_word(c))
+            .map_or(text.len(), |end| offset + end);
+        (start < end).then(|| (start, &text[start..end]))
+    }
+    /// Find the byte offset of a position, whose character counts UTF-16 code units.
+    fn offset(text: &str, position: Position) -> usize {
+        let Some(line_start) = Self::line_start(text, position.line as usize) else {
+            return text.len();
+        };
+        let line = text[line_start..].split('\n').next().unwrap_or_default();
+        let mut units = 0;
+        for (index, c) in line.char_indices() {
+            if units >= position.character as usize {
+                return line_start + index;
+            }
+            units += c.len_utf16();
+        }
+        line_start + line.len()
+    }
+    fn position(text: &str, offset: usize) -> Position {
+        let before = &text[..offset];
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        let character: usize = before[line_start..].chars().map(char::len_utf16).sum();
+        Position::new(
+            u32::try_from(before.matches('\n').count()).unwrap_or(u32::MAX),
+            u32::try_from(character).unwrap_or(u32::MAX),
+        )
+    }
+    fn range(text: &str, span: std::ops::Range<usize>) -> Range {
+        Range::new(
+            Self::position(text, span.start),
+            Self::position(text, span.end),
+        )
+    }
+    /// The range of the text on a line, without the indentation.
+    fn line_range(text: &str, line: usize) -> Range {
+        let start = Self::line_start(text, line).unwrap_or(text.len());
+        let content = text[start..].split('\n').next().unwrap_or_default();
+        let indentation = content.len() - content.trim_start().len();
+        Self::range(text, start + indentation..start + content.trim_end().len())
+    }
+    fn line_start(text: &str, line: usize) -> Option<usize> {
+        if line == 0 {
+            return Some(0);
+        }
+        text.match_indices('\n')
+            .nth(line - 1)
+            .map(|(newline, _)| newline + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn convert_positions() {
+        let text = "let é = \"😀\";\nx";
+        let offset = text.find(';').unwrap();
+        assert_eq!(Document::position(text, offset), Position::new(0, 12));
+        assert_eq!(Document::offset(text, Position::new(0, 12)), offset);
+        assert_eq!(Document::offset(text, Position::new(1, 0)), text.len() - 1);
+        assert_eq!(Document::offset(text, Position::new(5, 0)), text.len());
+        assert_eq!(Document::word_at(text, 4), Some((4, "é")));
+        assert_eq!(Document::word_at(text, 3), Some((0, "let")));
+    }
+    #[test]
+    fn analyze_document() {
+        let mut document =
+            Document::new("let x = 1;\nfn f y => { let z = y; z };\nf x + q".to_string());
+        let diagnostics = document.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(2, 0), Position::new(2, 7))
+        );
+        let hover = |document: &mut Document, line, character| match document
+            .hover(Position::new(line, character))?
+            .contents
+        {
+            HoverContents::Markup(markup) => Some(markup.value),
+            _ => None,
+        };
+        assert_eq!(
+            hover(&mut document, 2, 2),
+            Some("```\nx: Int\n```\n---\n```\nlet x = 1;\n```".to_string())
+        );
+        assert_eq!(
+            hover(&mut document, 1, 20),
+            Some("```\nparameter of `f`\n```".to_string())
+        );
+        assert_eq!(hover(&mut document, 0, 0), None);
+        assert_eq!(
+            document.definition(Position::new(1, 24)),
+            Some(Range::new(Position::new(1, 16), Position::new(1, 17)))
+        );
+        let names: Vec<_> = document
+            .symbols()
+            .into_iter()
+            .map(|symbol| symbol.name)
+            .collect();
+        assert_eq!(names, vec!["x", "f", "z"]);
+    }
+    #[test]
+    fn analyze_annotations() {
+        let document = Document::new("let n = 1;\nlet x: Str = n;".to_string());
+        // the error points at the annotated name, and the unused `x` is linted after it
+        assert_eq!(
+            document.diagnostics()[0].range,
+            Range::new(Position::new(1, 4), Position::new(1, 5))
+        );
+    }
+    #[test]
+    fn analyze_without_running() {
+        // running any of these would overflow the stack or run out of memory
+        let document = Document::new(
+            "fn f x => f x;\nf 1;\nlet mut s = \"a\";\nfor _ in range 0 64 { s = s + s; }"
+                .to_string(),
+        );
+        assert_eq!(document.diagnostics(), vec![]);
+    }
+}
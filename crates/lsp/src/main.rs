@@ -0,0 +1,19 @@
+mod document;
+mod server;
+
+use lsp_server::Connection;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let (connection, io_threads) = Connection::stdio();
+    let result = server::run(&connection);
+    // the writer thread stops once the connection is dropped
+    drop(connection);
+    match result.and_then(|()| Ok(io_threads.join()?)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
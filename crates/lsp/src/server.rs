@@ -0,0 +1,330 @@
+use crate::document::Document;
+use lsp_server::{Connection, ErrorCode, ExtractError, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+    PublishDiagnostics,
+};
+use lsp_types::request::{
+    Completion, DocumentSymbolRequest, GotoDefinition, HoverRequest, Request as _,
+};
+use lsp_types::{
+    CompletionOptions, CompletionResponse, DocumentSymbolResponse, GotoDefinitionResponse,
+    HoverProviderCapability, Location, OneOf, PublishDiagnosticsParams, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Uri,
+};
+use std::collections::HashMap;
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// The open files, which are analyzed again each time they change.
+#[derive(Default)]
+struct Server {
+    documents: HashMap<Uri, Document>,
+}
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions::default()),
+        ..Default::default()
+    }
+}
+
+/// Serve the requests of a client until it shuts the server down.
+///
+/// # Errors
+///
+/// This function will return an error if the connection fails, or the client does not
+/// follow the protocol.
+pub fn run(connection: &Connection) -> Result<(), Error> {
+    connection.initialize(serde_json::to_value(capabilities())?)?;
+    let mut server = Server::default();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                let response = server.handle_request(request);
+                connection.sender.send(response.into())?;
+            }
+            Message::Notification(notification) => {
+                if let Some(published) = server.handle_notification(notification)? {
+                    connection.sender.send(published.into())?;
+                }
+            }
+            Message::Response(_) => (),
+        }
+    }
+    Ok(())
+}
+
+impl Server {
+    fn handle_request(&mut self, request: Request) -> Response {
+        let id = request.id.clone();
+        let result = match request.method.as_str() {
+            HoverRequest::METHOD => Self::extract::<HoverRequest>(request).map(|params| {
+                let position = params.text_document_position_params;
+                let hover = self
+                    .documents
+                    .get_mut(&position.text_document.uri)
+                    .and_then(|document| document.hover(position.position));
+                serde_json::to_value(hover)
+            }),
+            GotoDefinition::METHOD => Self::extract::<GotoDefinition>(request).map(|params| {
+                let position = params.text_document_position_params;
+                let uri = position.text_document.uri;
+                let definition = self
+                    .documents
+                    .get(&uri)
+                    .and_then(|document| document.definition(position.position))
+                    .map(|range| GotoDefinitionResponse::Scalar(Location { uri, range }));
+                serde_json::to_value(definition)
+            }),
+            DocumentSymbolRequest::METHOD => {
+                Self::extract::<DocumentSymbolRequest>(request).map(|params| {
+                    let symbols = self
+                        .documents
+                        .get(&params.text_document.uri)
+                        .map(|document| DocumentSymbolResponse::Nested(document.symbols()));
+                    serde_json::to_value(symbols)
+                })
+            }
+            Completion::METHOD => Self::extract::<Completion>(request).map(|params| {
+                let position = params.text_document_position;
+                let items = self
+                    .documents
+                    .get(&position.text_document.uri)
+                    .map(|document| {
+                        CompletionResponse::Array(document.completions(position.position))
+                    });
+                serde_json::to_value(items)
+            }),
+            method => {
+                let message = format!("Unknown method `{method}`");
+                return Response::new_err(id, ErrorCode::MethodNotFound as i32, message);
+            }
+        };
+        match result {
+            Ok(Ok(value)) => Response {
+                id,
+                result: Some(value),
+                error: None,
+            },
+            Ok(Err(err)) => Response::new_err(id, ErrorCode::InternalError as i32, err.to_string()),
+            Err(err) => Response::new_err(id, ErrorCode::InvalidParams as i32, err),
+        }
+    }
+    fn extract<R: lsp_types::request::Request>(request: Request) -> Result<R::Params, String> {
+        request
+            .extract(R::METHOD)
+            .map(|(_, params)| params)
+            .map_err(|err| err.to_string())
+    }
+    /// Update the documents, and get the diagnostics to publish for the one which changed.
+    fn handle_notification(
+        &mut self,
+        notification: Notification,
+    ) -> Result<Option<Notification>, Error> {
+        let (uri, text) = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params = notification
+                    .extract::<lsp_types::DidOpenTextDocumentParams>(DidOpenTextDocument::METHOD);
+                let document = params.map_err(Self::extract_error)?.text_document;
+                (document.uri, Some(document.text))
+            }
+            DidChangeTextDocument::METHOD => {
+                let params = notification
+                    .extract::<lsp_types::DidChangeTextDocumentParams>(
+                        DidChangeTextDocument::METHOD,
+                    )
+                    .map_err(Self::extract_error)?;
+                // the whole text is sent, as the sync is `FULL`
+                let text = params
+                    .content_changes
+                    .into_iter()
+                    .last()
+                    .map(|change| change.text);
+                (params.text_document.uri, text)
+            }
+            DidCloseTextDocument::METHOD => {
+                let params = notification
+                    .extract::<lsp_types::DidCloseTextDocumentParams>(DidCloseTextDocument::METHOD)
+                    .map_err(Self::extract_error)?;
+                self.documents.remove(&params.text_document.uri);
+                (params.text_document.uri, None)
+            }
+            _ => return Ok(None),
+        };
+        let diagnostics = match text {
+            Some(text) => {
+                let document = Document::new(text);
+                let diagnostics = document.diagnostics();
+                self.documents.insert(uri.clone(), document);
+                diagnostics
+            }
+            None => Vec::new(),
+        };
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+        Ok(Some(Notification::new(
+            PublishDiagnostics::METHOD.to_string(),
+            params,
+        )))
+    }
+    fn extract_error(err: ExtractError<Notification>) -> Error {
+        err.to_string().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_server::RequestId;
+    use lsp_types::notification::{Exit, Initialized};
+    use lsp_types::request::{Initialize, Shutdown};
+    use lsp_types::{
+        CompletionParams, DidOpenTextDocumentParams, DocumentSymbolParams, GotoDefinitionParams,
+        HoverParams, Position, Range, TextDocumentIdentifier, TextDocumentItem,
+        TextDocumentPositionParams,
+    };
+
+    /// A client talking to a server which runs in another thread.
+    struct Client {
+        connection: Connection,
+        next_id: i32,
+    }
+    impl Client {
+        fn request<R: lsp_types::request::Request>(&mut self, params: R::Params) -> R::Result {
+            self.next_id += 1;
+            let id = RequestId::from(self.next_id);
+            let request = Request::new(id.clone(), R::METHOD.to_string(), params);
+            self.connection.sender.send(request.into()).unwrap();
+            loop {
+                match self.connection.receiver.recv().unwrap() {
+                    Message::Response(response) if response.id == id => {
+                        let result = response.result.unwrap_or_default();
+                        return serde_json::from_value(result).unwrap();
+                    }
+                    _ => (),
+                }
+            }
+        }
+        fn notify<N: lsp_types::notification::Notification>(&self, params: N::Params) {
+            let notification = Notification::new(N::METHOD.to_string(), params);
+            self.connection.sender.send(notification.into()).unwrap();
+        }
+        fn receive_notification(&self) -> Notification {
+            match self.connection.receiver.recv().unwrap() {
+                Message::Notification(notification) => notification,
+                message => panic!("Expect a notification, not {message:?}"),
+            }
+        }
+    }
+    fn position(uri: &Uri, line: u32, character: u32) -> TextDocumentPositionParams {
+        TextDocumentPositionParams::new(
+            TextDocumentIdentifier::new(uri.clone()),
+            Position::new(line, character),
+        )
+    }
+
+    #[test]
+    fn serve_client() {
+        let (server, connection) = Connection::memory();
+        let thread = std::thread::spawn(move || run(&server).map_err(|err| err.to_string()));
+        let mut client = Client {
+            connection,
+            next_id: 0,
+        };
+        let initialized =
+            client.request::<Initialize>(serde_json::from_str("{\"capabilities\": {}}").unwrap());
+        assert_eq!(
+            initialized.capabilities.hover_provider,
+            Some(HoverProviderCapability::Simple(true))
+        );
+        client.notify::<Initialized>(lsp_types::InitializedParams {});
+
+        let uri: Uri = "file:///main.cl".parse().unwrap();
+        let text = "fn double x => x * 2;\nlet y = double 3;\nlet unused = 1 + \"a\";\nd";
+        client.notify::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem::new(
+                uri.clone(),
+                "const-lang".to_string(),
+                1,
+                text.to_string(),
+            ),
+        });
+        let published: PublishDiagnosticsParams = client
+            .receive_notification()
+            .extract(PublishDiagnostics::METHOD)
+            .unwrap();
+        let messages: Vec<_> = published
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.range.start.line, diagnostic.severity))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (2, Some(lsp_types::DiagnosticSeverity::ERROR)),
+                (3, Some(lsp_types::DiagnosticSeverity::ERROR)),
+                (1, Some(lsp_types::DiagnosticSeverity::WARNING)),
+                (2, Some(lsp_types::DiagnosticSeverity::WARNING)),
+            ]
+        );
+
+        let hover = client.request::<HoverRequest>(HoverParams {
+            text_document_position_params: position(&uri, 1, 10),
+            work_done_progress_params: Default::default(),
+        });
+        let lsp_types::HoverContents::Markup(markup) = hover.unwrap().contents else {
+            panic!("Expect markdown");
+        };
+        assert_eq!(
+            markup.value,
+            "```\ndouble: Fn(Int) -> Int\n```\n---\n```\nfn double x => x * 2;\n```"
+        );
+
+        let definition = client.request::<GotoDefinition>(GotoDefinitionParams {
+            text_document_position_params: position(&uri, 1, 10),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        });
+        assert_eq!(
+            definition,
+            Some(GotoDefinitionResponse::Scalar(Location {
+                uri: uri.clone(),
+                range: Range::new(Position::new(0, 3), Position::new(0, 9)),
+            }))
+        );
+
+        let symbols = client.request::<DocumentSymbolRequest>(DocumentSymbolParams {
+            text_document: TextDocumentIdentifier::new(uri.clone()),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        });
+        let Some(DocumentSymbolResponse::Nested(symbols)) = symbols else {
+            panic!("Expect nested symbols");
+        };
+        let names: Vec<_> = symbols.iter().map(|symbol| symbol.name.as_str()).collect();
+        assert_eq!(names, vec!["double", "y", "unused"]);
+
+        let completions = client.request::<Completion>(CompletionParams {
+            text_document_position: position(&uri, 3, 1),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: None,
+        });
+        let Some(CompletionResponse::Array(items)) = completions else {
+            panic!("Expect completion items");
+        };
+        let labels: Vec<_> = items.iter().map(|item| item.label.as_str()).collect();
+        assert_eq!(labels, vec!["double", "divmod"]);
+
+        client.request::<Shutdown>(());
+        client.notify::<Exit>(());
+        assert_eq!(thread.join().unwrap(), Ok(()));
+    }
+}